@@ -4,13 +4,15 @@ mod asset_buffer;
 mod tests;
 
 use crate::asset_cache::asset_blob_buffer::AssetBlobBuffer;
-use crate::{AssetIdentifier, AssetRegistry};
+use crate::{AssetIdentifier, AssetRegistry, AssetSerializationFormat, AssetVariantSet};
+pub use asset_buffer::AssetState;
 use asset_buffer::*;
 use crossbeam::queue::ArrayQueue;
 use dashmap::DashMap;
 use serde::de::DeserializeOwned;
 use std::any::Any;
 use std::marker::PhantomData;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::sync::Weak;
 use tokio::fs::File;
@@ -25,6 +27,9 @@ impl AssetBlobHandle {
     pub fn read(&self) -> Option<&[u8]> {
         self.reference.try_read()
     }
+    pub fn state(&self) -> AssetState {
+        self.reference.state()
+    }
 }
 
 pub struct AssetHandle<T> {
@@ -66,6 +71,8 @@ pub struct AssetCache<R: AsyncReadExt + AsyncSeekExt + Unpin + Send = File> {
     registry: Arc<AssetRegistry<R>>,
     // TODO: Smarter buffer sizing and the likes
     buffers: Arc<ArrayQueue<Vec<u8>>>,
+    /// Variant generation of the registry the cached handles were resolved with.
+    variant_generation: AtomicU64,
 }
 
 impl<R: AsyncReadExt + AsyncSeekExt + Unpin + Send + 'static> AssetCache<R> {
@@ -81,15 +88,56 @@ impl<R: AsyncReadExt + AsyncSeekExt + Unpin + Send + 'static> AssetCache<R> {
             dispatcher,
             loaded_raw_buffers: DashMap::default(),
             loaded_asset_buffers: DashMap::default(),
+            variant_generation: AtomicU64::new(registry.variant_generation()),
             registry,
             buffers: Arc::new(buffers),
         }
     }
 
+    /// Replaces the active variant set of the underlying registry and drops all cached handles.
+    /// Handles which are still held elsewhere remain valid, but new requests resolve to the new variants.
+    pub fn set_active_variants(&self, variants: AssetVariantSet) {
+        self.registry.set_active_variants(variants);
+        self.invalidate_outdated_variants();
+    }
+
+    pub fn active_variants(&self) -> AssetVariantSet {
+        self.registry.active_variants()
+    }
+
+    /// Incremented whenever the active variants change, see [`AssetRegistry::variant_generation`].
+    pub fn variant_generation(&self) -> u64 {
+        self.registry.variant_generation()
+    }
+
+    /// Resolves a logical identifier to the variant that would be loaded for it.
+    pub fn resolve_identifier(&self, asset_id: AssetIdentifier) -> AssetIdentifier {
+        self.registry.resolve_identifier(asset_id)
+    }
+
+    /// Returns the serialization format of the variant that would be loaded for the identifier.
+    pub fn asset_format(&self, asset_id: AssetIdentifier) -> Option<AssetSerializationFormat> {
+        let asset_id = self.registry.resolve_identifier(asset_id);
+        self.registry
+            .get_asset_descriptor(asset_id)
+            .ok()
+            .map(|e| e.format())
+    }
+
+    fn invalidate_outdated_variants(&self) {
+        let generation = self.registry.variant_generation();
+        if self.variant_generation.swap(generation, Ordering::AcqRel) != generation {
+            self.loaded_raw_buffers.clear();
+            self.loaded_asset_buffers.clear();
+        }
+    }
+
     pub fn request_binary(
         &self,
         asset_id: AssetIdentifier,
     ) -> Result<AssetBlobHandle, AssetCacheError> {
+        self.invalidate_outdated_variants();
+        let asset_id = self.registry.resolve_identifier(asset_id);
         if let Some(value) = self.loaded_raw_buffers.get(&asset_id) {
             if let Some(item) = value.value().upgrade() {
                 return Ok(AssetBlobHandle { reference: item });
@@ -143,6 +191,8 @@ impl<R: AsyncReadExt + AsyncSeekExt + Unpin + Send + 'static> AssetCache<R> {
         &self,
         asset_id: AssetIdentifier,
     ) -> Result<AssetBlobHandle, AssetCacheError> {
+        self.invalidate_outdated_variants();
+        let asset_id = self.registry.resolve_identifier(asset_id);
         if let Some(value) = self.loaded_raw_buffers.get(&asset_id) {
             if let Some(item) = value.value().upgrade() {
                 return Ok(AssetBlobHandle { reference: item });
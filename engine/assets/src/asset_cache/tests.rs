@@ -1,5 +1,5 @@
 use crate::asset_cache::AssetCache;
-use crate::{ArchiveBuilder, AssetArchive, AssetRegistry};
+use crate::{ArchiveBuilder, AssetArchive, AssetRegistry, AssetVariantSet};
 use crate::{ArchiveCompressionFormat, AssetSerializationFormat};
 use std::io::Cursor;
 use std::num::NonZeroUsize;
@@ -58,3 +58,60 @@ fn test_asset_cache() {
         .expect("Could not request binary synchronously");
     assert!(handle.read().is_some());
 }
+
+#[test]
+fn test_asset_cache_variants() {
+    let dispatcher = Arc::new(
+        Dispatcher::new(
+            Some(unsafe { NonZeroUsize::new_unchecked(1) }),
+            unsafe { NonZeroUsize::new_unchecked(1) },
+            Some(unsafe { NonZeroUsize::new_unchecked(1) }),
+            unsafe { NonZeroUsize::new_unchecked(1) },
+        )
+        .unwrap(),
+    );
+    let base_dispatcher = Arc::clone(&dispatcher);
+    let manager = base_dispatcher.spawn_async_blocking(async {
+        let mut cursor = Cursor::new(Vec::<u8>::with_capacity(KB));
+        let mut builder = ArchiveBuilder::new(&mut cursor).await.unwrap();
+        for (identifier, blob) in [("ui.title", b"base"), ("ui.title@nl", b"nl__")] {
+            builder
+                .write_file(
+                    identifier,
+                    AssetSerializationFormat::Binary,
+                    blob,
+                    0,
+                    ArchiveCompressionFormat::None,
+                )
+                .await
+                .unwrap();
+        }
+        builder.finish(uuid::Uuid::new_v4()).await.unwrap();
+        let archive = AssetArchive::load_from_readable(cursor).await.unwrap();
+
+        let registry = AssetRegistry::<Cursor<Vec<u8>>>::default();
+        registry.register_asset_archive(archive).unwrap();
+        AssetCache::<Cursor<Vec<u8>>>::new(Arc::new(registry), dispatcher)
+    });
+
+    assert_eq!(
+        manager.resolve_identifier(asset_id!(ui.title)),
+        asset_id!(ui.title)
+    );
+    let base = manager
+        .request_binary_synchronous(asset_id!(ui.title))
+        .expect("Could not request base asset.");
+    assert_eq!(base.read(), Some(&b"base"[..]));
+
+    manager.set_active_variants(AssetVariantSet::new().with_tag("de").with_tag("nl"));
+    assert_eq!(
+        manager.resolve_identifier(asset_id!(ui.title)),
+        crate::AssetIdentifier::named("ui.title@nl")
+    );
+    let variant = manager
+        .request_binary_synchronous(asset_id!(ui.title))
+        .expect("Could not request variant asset.");
+    assert_eq!(variant.read(), Some(&b"nl__"[..]));
+    // Handles acquired before the change remain valid.
+    assert_eq!(base.read(), Some(&b"base"[..]));
+}
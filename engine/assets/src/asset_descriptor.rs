@@ -1,5 +1,6 @@
 use crate::formats::AssetSerializationFormat;
-use serde::{Deserialize, Serialize};
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::fmt::{Display, Formatter};
 use uuid::Uuid;
 
@@ -10,10 +11,48 @@ macro_rules! asset_id {
     };
 }
 
+/// Deserializes from either the hash or the name of an asset, e.g. `"assets.meshes.cube"`.
+///
+/// The hash is serialized as a bit-cast `i64`, since formats such as TOML cannot represent integers above `i64::MAX`.
 #[repr(transparent)]
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize, Hash)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub struct AssetIdentifier(u64);
 
+impl Serialize for AssetIdentifier {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_newtype_struct("AssetIdentifier", &(self.0 as i64))
+    }
+}
+
+impl<'de> Deserialize<'de> for AssetIdentifier {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct IdentifierVisitor;
+        impl<'de> Visitor<'de> for IdentifierVisitor {
+            type Value = AssetIdentifier;
+
+            fn expecting(&self, f: &mut Formatter) -> std::fmt::Result {
+                f.write_str("an asset identifier or asset name")
+            }
+            fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+                Ok(AssetIdentifier(v))
+            }
+            fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+                Ok(AssetIdentifier(v as u64))
+            }
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                Ok(AssetIdentifier::named(v))
+            }
+            fn visit_newtype_struct<D: Deserializer<'de>>(
+                self,
+                deserializer: D,
+            ) -> Result<Self::Value, D::Error> {
+                deserializer.deserialize_any(self)
+            }
+        }
+        deserializer.deserialize_newtype_struct("AssetIdentifier", IdentifierVisitor)
+    }
+}
+
 impl From<u64> for AssetIdentifier {
     fn from(v: u64) -> Self {
         Self(v)
@@ -6,6 +6,7 @@ mod formats;
 mod registry;
 #[cfg(test)]
 mod tests;
+mod variants;
 
 #[allow(dead_code)]
 pub(crate) const IDENTIFIER: &'static str = "AssetRegistry";
@@ -15,3 +16,4 @@ pub use asset_cache::*;
 pub use asset_descriptor::*;
 pub use formats::*;
 pub use registry::*;
+pub use variants::*;
@@ -4,6 +4,8 @@ use dashmap::mapref::entry::Entry;
 use dashmap::DashMap;
 use serde::{Deserialize, Serialize};
 use std::ops::Deref;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::RwLock;
 use tokio::fs::File;
 use tokio::io;
 use tokio::io::{AsyncReadExt, AsyncSeekExt};
@@ -25,6 +27,11 @@ pub struct AssetRegistry<R: AsyncReadExt + AsyncSeekExt + Unpin + Send = File> {
     registered_files: DashMap<u64, MappedFile>,
     registered_directory_mappings: DashMap<u64, MappedDirectory>,
     assets: DashMap<AssetIdentifier, AssetDescriptor, RandomState>,
+    /// Maps base identifiers to their available variants as (tag, variant identifier) pairs.
+    variants: DashMap<AssetIdentifier, Vec<(String, AssetIdentifier)>, RandomState>,
+    active_variants: RwLock<AssetVariantSet>,
+    /// Incremented every time the active variant set changes.
+    variant_generation: AtomicU64,
 }
 #[derive(Debug)]
 pub enum AssetRegistryError {
@@ -44,6 +51,9 @@ impl<R: AsyncReadExt + AsyncSeekExt + Unpin + Send> Default for AssetRegistry<R>
             registered_files: Default::default(),
             registered_directory_mappings: Default::default(),
             assets: Default::default(),
+            variants: Default::default(),
+            active_variants: Default::default(),
+            variant_generation: AtomicU64::new(0),
         }
     }
 }
@@ -86,6 +96,7 @@ impl<R: AsyncReadExt + AsyncSeekExt + Unpin + Send> AssetRegistry<R> {
                     .iter()
                     .enumerate()
                     .for_each(|(file_offset, file_header)| {
+                        self.register_variant(file_header);
                         match self.assets.entry(file_header.id()) {
                             Entry::Occupied(mut entry) => {
                                 // If existing version is larger or equal, don't insert it.
@@ -117,6 +128,58 @@ impl<R: AsyncReadExt + AsyncSeekExt + Unpin + Send> AssetRegistry<R> {
         };
     }
 
+    fn register_variant(&self, file_header: &FileHeader) {
+        let Some((base, tag)) = split_variant_identifier(file_header.identifier()) else {
+            return;
+        };
+        let mut variants = self
+            .variants
+            .entry(AssetIdentifier::named(base))
+            .or_default();
+        if !variants.iter().any(|(t, _)| t == tag) {
+            variants.push((String::from(tag), file_header.id()));
+        }
+    }
+
+    /// Replaces the active variant set.
+    /// Caches observing [`AssetRegistry::variant_generation`] will drop their cached handles.
+    pub fn set_active_variants(&self, variants: AssetVariantSet) {
+        let mut active = self.active_variants.write().unwrap();
+        if *active == variants {
+            return;
+        }
+        *active = variants;
+        self.variant_generation.fetch_add(1, Ordering::AcqRel);
+        t_info!("Active asset variants changed: {:?}", active.tags());
+    }
+
+    pub fn active_variants(&self) -> AssetVariantSet {
+        self.active_variants.read().unwrap().clone()
+    }
+
+    pub fn variant_generation(&self) -> u64 {
+        self.variant_generation.load(Ordering::Acquire)
+    }
+
+    /// Resolves a logical identifier to the highest priority variant that is available.
+    /// Falls back to the identifier itself if no active variant exists for it.
+    pub fn resolve_identifier(&self, identifier: AssetIdentifier) -> AssetIdentifier {
+        let Some(variants) = self.variants.get(&identifier) else {
+            return identifier;
+        };
+        let active = self.active_variants.read().unwrap();
+        active
+            .tags()
+            .iter()
+            .find_map(|tag| {
+                variants
+                    .iter()
+                    .find(|(t, _)| t == tag)
+                    .map(|(_, variant)| *variant)
+            })
+            .unwrap_or(identifier)
+    }
+
     pub fn register_mapped_file(&self) {
         todo!()
     }
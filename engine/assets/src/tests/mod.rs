@@ -35,3 +35,30 @@ async fn test_asynchronous_load() {
     let mut result = archive.read_asset_into(0, &mut buffer).await.unwrap();
     assert_eq!(result, random_data);
 }
+
+#[tokio::test]
+async fn test_asset_identifier_round_trip() {
+    #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+    struct Reference {
+        asset: crate::AssetIdentifier,
+    }
+
+    for id in [0, 42, i64::MAX as u64, i64::MAX as u64 + 1, u64::MAX] {
+        let reference = Reference {
+            asset: crate::AssetIdentifier::from(id),
+        };
+        let toml = toml::to_string(&reference).unwrap();
+        assert_eq!(toml::from_str::<Reference>(&toml).unwrap(), reference);
+        let cbor = serde_cbor::to_vec(&reference).unwrap();
+        assert_eq!(
+            serde_cbor::from_slice::<Reference>(&cbor).unwrap(),
+            reference
+        );
+    }
+    assert_eq!(
+        toml::from_str::<Reference>(r#"asset = "assets.meshes.cube""#).unwrap(),
+        Reference {
+            asset: crate::asset_id!(assets.meshes.cube)
+        }
+    );
+}
@@ -0,0 +1,63 @@
+use serde::{Deserialize, Serialize};
+
+/// Separates the base identifier from the variant tag, e.g. `assets.ui.title@nl`.
+pub const ASSET_VARIANT_SEPARATOR: char = '@';
+
+/// Ordered set of variant tags (locale, quality tier, platform, ...) which are currently active.
+/// Tags earlier in the set take precedence over later ones when resolving an asset.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AssetVariantSet {
+    tags: Vec<String>,
+}
+
+impl AssetVariantSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a tag with a lower priority than all tags already in the set.
+    pub fn with_tag(mut self, tag: impl Into<String>) -> Self {
+        self.push_tag(tag);
+        self
+    }
+
+    pub fn push_tag(&mut self, tag: impl Into<String>) {
+        let tag = tag.into();
+        if !self.tags.contains(&tag) {
+            self.tags.push(tag);
+        }
+    }
+
+    pub fn remove_tag(&mut self, tag: &str) {
+        self.tags.retain(|t| t != tag);
+    }
+
+    pub fn contains(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
+
+    pub fn tags(&self) -> &[String] {
+        &self.tags
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty()
+    }
+}
+
+impl<S: Into<String>> FromIterator<S> for AssetVariantSet {
+    fn from_iter<I: IntoIterator<Item = S>>(iter: I) -> Self {
+        let mut set = Self::default();
+        iter.into_iter().for_each(|tag| set.push_tag(tag));
+        set
+    }
+}
+
+/// Splits a string identifier into its base identifier and variant tag, if it has one.
+pub fn split_variant_identifier(identifier: &str) -> Option<(&str, &str)> {
+    let (base, tag) = identifier.rsplit_once(ASSET_VARIANT_SEPARATOR)?;
+    if base.is_empty() || tag.is_empty() {
+        return None;
+    }
+    Some((base, tag))
+}
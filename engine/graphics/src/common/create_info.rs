@@ -1,5 +1,7 @@
 use super::*;
 use assets::*;
+use engine::render_world::RenderWorld;
+use engine::scene_manager::SceneComponentRegistry;
 use engine::*;
 use std::sync::Arc;
 
@@ -7,5 +9,7 @@ pub struct GraphicsStageCreateInfo<'a> {
     pub platform: &'a mut dyn PlatformInterface,
     pub application_info: ApplicationInfo,
     pub asset_system: Arc<AssetCache>,
+    pub render_world: Arc<RenderWorld>,
+    pub scene_components: Arc<SceneComponentRegistry>,
     pub options: GraphicsOptions,
 }
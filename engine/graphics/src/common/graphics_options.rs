@@ -1,5 +1,7 @@
+use engine::config::{ConfigError, EngineConfig};
 use serde::{Deserialize, Serialize};
 use std::ffi::CString;
+use std::path::PathBuf;
 
 #[derive(Deserialize, Serialize)]
 pub struct GraphicsOptions {
@@ -17,4 +19,18 @@ pub struct GraphicsOptions {
     pub preferred_frames_in_flight: u32,
     pub use_transfer_queues: bool,
     pub resize_on_sub_optimal: bool,
+    /// Directory the screenshots taken with [`engine::EngineCommand::Screenshot`] are saved to.
+    pub screenshot_directory: PathBuf,
+}
+
+impl GraphicsOptions {
+    pub const SECTION: &'static str = "graphics";
+
+    /// Applies the `graphics` section of the config over the given options, e.g. those loaded from the config asset.
+    pub fn from_config(
+        config: &EngineConfig,
+        defaults: &GraphicsOptions,
+    ) -> Result<Self, ConfigError> {
+        config.section(Self::SECTION, defaults)
+    }
 }
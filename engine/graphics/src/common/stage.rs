@@ -6,18 +6,21 @@ use crate::common::vk_library_wrapper::VkLibraryWrapper;
 use crate::render_target::*;
 use crate::*;
 use assets::{asset_id, AssetCache};
+use engine::render_world::RenderWorld;
 use engine::{
     engine_stages::{RenderStageMessageContext, RenderStageUpdateThreadHandlerCreateInfo},
     *,
 };
 
 use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
 use utils::*;
 
 pub struct GraphicsStage {
     temp_renderables: Vec<(PrimitiveRenderer, VkPrimitiveRenderer)>,
 
     asset_cache: Arc<AssetCache>,
+    render_world: Arc<RenderWorld>,
     update_receiver: Option<UpdateReceivers>,
     available_window_targets: Vec<WindowRenderTarget>,
     render_targets: Vec<WindowRenderTargetBinding>,
@@ -25,6 +28,8 @@ pub struct GraphicsStage {
     _debug_messenger: Option<DebugExtension>,
     vk: VkLibraryWrapper,
     graphics_options: GraphicsOptions,
+    /// Set by [`EngineCommand::Screenshot`], the next frame of every window is saved.
+    screenshot_requested: bool,
 }
 
 impl Drop for GraphicsStage {
@@ -49,6 +54,20 @@ impl GraphicsStage {
     pub fn new(create_info: GraphicsStageCreateInfo) -> Option<Self> {
         let asset_cache = Arc::clone(&create_info.asset_system);
 
+        create_info.render_world.register_component::<Transform>();
+        create_info
+            .render_world
+            .register_component::<GlobalTransform>();
+        create_info.render_world.register_component::<Camera>();
+        create_info
+            .scene_components
+            .register_component::<Camera>("camera");
+        create_info
+            .render_world
+            .register_extractor::<PrimitiveRenderer, _>(|e| ExtractedPrimitiveRenderer {
+                id: e.id,
+            });
+
         let (entry, instance) = {
             let (entry, instance) =
                 setup_vulkan_instance(&create_info.application_info, &create_info.options)?;
@@ -83,9 +102,11 @@ impl GraphicsStage {
             vk: VkLibraryWrapper::new(instance, entry),
             _debug_messenger: debug_messenger,
             graphics_options: create_info.options,
+            screenshot_requested: false,
             device,
             render_targets: vec![],
             asset_cache,
+            render_world: create_info.render_world,
         }
         .into()
     }
@@ -99,6 +120,7 @@ impl RenderStage for GraphicsStage {
         registerer.register::<WindowDidOpen>();
         registerer.register::<WindowDidResize>();
         registerer.register::<WindowWillClose>();
+        registerer.register::<EngineCommand>();
     }
 
     fn create_update_thread_handler(
@@ -134,6 +156,7 @@ impl RenderStage for GraphicsStage {
                         self.vk.instance(),
                         &self.device,
                         &is_bound.camera,
+                        is_bound.transform,
                         Arc::clone(&self.asset_cache),
                         input.platform,
                         target,
@@ -162,16 +185,43 @@ impl RenderStage for GraphicsStage {
                         *vk_renderer = vkr;
                     }
                     Err(_) => {
-                        t_error!("Could not upload to GPU.");
-                        return EngineUpdateResult::Stop;
+                        return EngineUpdateResult::error("Could not upload to GPU.");
                     }
                 };
             }
         }
 
+        let screenshot_name = std::mem::take(&mut self.screenshot_requested).then(|| {
+            let timestamp = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default();
+            format!("screenshot_{}", timestamp.as_millis())
+        });
+
+        // Cameras are rendered in between the last two update ticks.
+        let snapshots = self.render_world.snapshots();
         for render_target in &mut self.render_targets {
-            if !render_target.render(&self.device, &mut input, &self.graphics_options) {
-                return EngineUpdateResult::Stop;
+            if let Some(transform) = interpolated_camera_transform(
+                &snapshots,
+                render_target.camera().handle(),
+                input.alpha_till_next_update,
+            ) {
+                render_target.set_camera_transform(transform);
+            }
+            let screenshot_path = screenshot_name.as_ref().map(|name| {
+                self.graphics_options.screenshot_directory.join(format!(
+                    "{}_window_{}.png",
+                    name,
+                    render_target.window_handle().value
+                ))
+            });
+            if !render_target.render(
+                &mut self.device,
+                &mut input,
+                &self.graphics_options,
+                screenshot_path.as_deref(),
+            ) {
+                return EngineUpdateResult::error("Could not render to window render target.");
             }
         }
         EngineUpdateResult::Ok
@@ -200,6 +250,14 @@ impl<'a> MessageHandler<RenderStageMessageContext<'a>, WindowWillClose> for Grap
     }
 }
 
+impl<'a> MessageHandler<RenderStageMessageContext<'a>, EngineCommand> for GraphicsStage {
+    fn handle(&mut self, _context: &mut RenderStageMessageContext, message: EngineCommand) {
+        if message == EngineCommand::Screenshot {
+            self.screenshot_requested = true;
+        }
+    }
+}
+
 impl<'a> MessageHandler<RenderStageMessageContext<'a>, WindowDidResize> for GraphicsStage {
     fn handle(&mut self, context: &mut RenderStageMessageContext, message: WindowDidResize) {
         let device = &self.device;
@@ -1,12 +1,11 @@
 use crate::RenderPathType;
 use engine::ecs::*;
 use engine::scene_manager::SceneHandle;
-use math::*;
 use serde::{Deserialize, Serialize};
 use utils::handles::Handle;
 
 #[repr(u8)]
-#[derive(Clone, Copy, Deserialize, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
 pub enum CameraKind {
     Orthographic = 0,
     Perspective = 1,
@@ -14,10 +13,9 @@ pub enum CameraKind {
 
 pub type CameraHandle = Handle<Camera, u16>;
 
-#[derive(Clone, Component)]
+/// Cameras keep their handle when saved with a scene, so cameras bound to a window stay bound after reloading.
+#[derive(Debug, Clone, PartialEq, Component, Serialize, Deserialize)]
 pub struct Camera {
-    previous_cycle_position: Vec3f,
-    previous_cycle_rotation: Vec4f,
     scene: SceneHandle,
     handle: CameraHandle,
     kind: CameraKind,
@@ -25,21 +23,6 @@ pub struct Camera {
 }
 
 impl Camera {
-    pub(crate) fn previous_cycle_position(&self) -> Vec3f {
-        self.previous_cycle_position
-    }
-    pub(crate) fn previous_cycle_rotation(&self) -> Vec4f {
-        self.previous_cycle_rotation
-    }
-
-    pub(crate) fn set_previous_cycle_position(&mut self, position: Vec3f) {
-        self.previous_cycle_position = position;
-    }
-
-    pub(crate) fn set_previous_cycle_rotation(&mut self, rotation: Vec4f) {
-        self.previous_cycle_rotation = rotation;
-    }
-
     pub(crate) fn handle(&self) -> CameraHandle {
         self.handle
     }
@@ -57,12 +40,8 @@ impl Camera {
         handle: CameraHandle,
         kind: CameraKind,
         path: RenderPathType,
-        starting_position: Vec3f,
-        starting_rotation: Vec4f,
     ) -> Self {
         Camera {
-            previous_cycle_position: starting_position,
-            previous_cycle_rotation: starting_rotation,
             scene,
             handle,
             kind,
@@ -1,64 +1,51 @@
-use crate::{Camera, CameraHandle, CameraKind, RenderPathType, Transform};
+use crate::{Camera, CameraHandle, CameraKind, GlobalTransform, RenderPathType, Transform};
 use crossbeam::channel::*;
-use engine::ecs::Registry;
+use engine::render_world::RenderSnapshots;
 use engine::scene_manager::SceneHandle;
 use engine::PlatformWindowHandle;
-use math::{Vec3f, Vec4f};
-use utils::*;
 
 pub struct CameraManager {
     handle_counter: u16,
-    cameras_updated_sender: Sender<Vec<CameraStateUpdate>>,
     camera_is_bound_sender: Sender<CameraIsBoundToWindow>,
     camera_is_unbound_sender: Sender<CameraIsUnbound>,
 }
 
 impl CameraManager {
     pub(crate) fn new(
-        cameras_updated_sender: Sender<Vec<CameraStateUpdate>>,
         camera_is_bound_sender: Sender<CameraIsBoundToWindow>,
         camera_is_unbound_sender: Sender<CameraIsUnbound>,
     ) -> CameraManager {
         Self {
             handle_counter: 0,
-            cameras_updated_sender,
             camera_is_bound_sender,
             camera_is_unbound_sender,
         }
     }
 
     /// Creates a new camera. Camera's must only be added to entities containing a Transform component!!
-    /// Otherwise, they will be ignored when updating the camera state every update cycle.
+    /// Otherwise, their transform can not be interpolated from the render snapshots.
     pub fn create_camera(
         &mut self,
         scene: SceneHandle,
         kind: CameraKind,
         path: RenderPathType,
-        transform: &Transform,
     ) -> Camera {
         let id: u16 = self.handle_counter;
-        let camera = Camera::new(
-            scene,
-            CameraHandle::from(id),
-            kind,
-            path,
-            transform.position(),
-            transform.rotation(),
-        );
+        let camera = Camera::new(scene, CameraHandle::from(id), kind, path);
         self.handle_counter += 1;
         camera
     }
 
     /// Binding a camera implicitly unbinds the previous camera.
+    /// The global transform of the camera's entity is used until the camera is part of the render snapshots.
     pub fn bind_camera_to_window(
         &mut self,
-        transform: &Transform,
+        transform: &GlobalTransform,
         camera: &Camera,
         window_handle: PlatformWindowHandle,
     ) {
         self.camera_is_bound_sender.send(CameraIsBoundToWindow {
-            position: transform.position(),
-            rotation: transform.rotation(),
+            transform: *transform.transform(),
             camera: camera.clone(),
             window_handle,
         });
@@ -70,52 +57,28 @@ impl CameraManager {
             camera: camera.handle(),
         });
     }
-
-    /// Must not be called externally. Internal function which updates the camera state on the main thread for rendering.
-    pub(crate) fn update_cameras(&mut self, registry: &mut Registry) {
-        let mut camera_updates: Vec<CameraStateUpdate> = Vec::with_capacity(16);
-        for (transforms, cameras) in registry.iter_components_matching_mut::<(Transform, Camera)>()
-        {
-            for (transform, camera) in transforms.iter_mut().zip(cameras) {
-                let previous_position = camera.previous_cycle_position();
-                let previous_rotation = camera.previous_cycle_rotation();
-                camera_updates.push(CameraStateUpdate {
-                    previous_cycle_position: previous_position,
-                    previous_cycle_rotation: previous_rotation,
-                    current_cycle_position: transform.position(),
-                    current_cycle_rotation: transform.rotation(),
-                    camera_handle: camera.handle(),
-                });
-                camera.set_previous_cycle_position(transform.position());
-                camera.set_previous_cycle_rotation(transform.rotation());
-            }
-        }
-        if let Err(_e) = self.cameras_updated_sender.send(camera_updates) {
-            t_warn!("Camera update failed!");
-        }
-    }
 }
 
-#[derive(Clone)]
-pub(crate) struct CameraState {
-    pub(crate) previous_cycle_position: Vec3f,
-    pub(crate) previous_cycle_rotation: Vec4f,
-    pub(crate) camera: Camera,
-}
-
-#[derive(Clone)]
-pub(crate) struct CameraStateUpdate {
-    pub(crate) previous_cycle_position: Vec3f,
-    pub(crate) previous_cycle_rotation: Vec4f,
-    pub(crate) current_cycle_position: Vec3f,
-    pub(crate) current_cycle_rotation: Vec4f,
-    pub(crate) camera_handle: CameraHandle,
+/// Returns the global transform of the camera's entity, interpolated between the render snapshots.
+/// None if the camera is not part of the current snapshot.
+pub(crate) fn interpolated_camera_transform(
+    snapshots: &RenderSnapshots,
+    camera: CameraHandle,
+    alpha: f32,
+) -> Option<Transform> {
+    let (entity, _) = snapshots
+        .current()
+        .components::<Camera>()?
+        .iter()
+        .find(|(_, e)| e.handle() == camera)?;
+    snapshots
+        .interpolate::<GlobalTransform>(entity, alpha)
+        .map(|e| *e.transform())
 }
 
 #[derive(Clone)]
 pub(crate) struct CameraIsBoundToWindow {
-    pub(crate) position: Vec3f,
-    pub(crate) rotation: Vec4f,
+    pub(crate) transform: Transform,
     pub(crate) camera: Camera,
     pub(crate) window_handle: PlatformWindowHandle,
 }
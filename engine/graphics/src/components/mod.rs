@@ -1,9 +1,10 @@
 mod camera;
 mod camera_manager;
 mod primitive_renderer;
-mod transform;
+#[cfg(test)]
+mod tests;
 
 pub use camera::*;
 pub use camera_manager::*;
+pub use engine::scene_manager::{Children, GlobalTransform, Parent, Transform, TransformHierarchy};
 pub use primitive_renderer::*;
-pub use transform::*;
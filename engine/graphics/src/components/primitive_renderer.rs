@@ -10,6 +10,13 @@ pub struct PrimitiveRenderer {
     pub primitive: Primitive,
 }
 
+/// Render snapshot representation of a [`PrimitiveRenderer`].
+/// The primitive itself is not copied every update, it is resolved through the asset cache instead.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct ExtractedPrimitiveRenderer {
+    pub id: AssetIdentifier,
+}
+
 pub struct VkPrimitiveRenderer {
     pub vertex_buffers: Vec<vk::Buffer>,
     pub index_buffer: Option<vk::Buffer>,
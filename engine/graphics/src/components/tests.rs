@@ -0,0 +1,52 @@
+use super::*;
+use crate::RenderPathType;
+use assets::AssetSerializationFormat;
+use engine::scene_manager::*;
+use math::*;
+
+#[test]
+fn test_camera_serialization_round_trip() {
+    let components = SceneComponentRegistry::default();
+    components.register_component::<Transform>("transform");
+    components.register_component::<Camera>("camera");
+
+    let mut manager = SceneManager::default();
+    let level = manager.create_scene("level");
+    let camera = Camera::new(
+        level,
+        CameraHandle::from(3),
+        CameraKind::Orthographic,
+        RenderPathType::Forward,
+    );
+    let transform = Transform::new(
+        Vec3f::from_components(1.0, 2.0, 3.0),
+        Vec4f::from_components(0.0, 0.0, 0.0, 1.0),
+        2.0,
+    );
+    manager
+        .scene_mut(level)
+        .unwrap()
+        .registry_mut()
+        .create_entity((transform, camera.clone()))
+        .unwrap();
+
+    let serialized = components.serialize(manager.scene(level).unwrap()).unwrap();
+    for format in [
+        AssetSerializationFormat::Binary,
+        AssetSerializationFormat::Toml,
+    ] {
+        let bytes = serialized.to_bytes(format).unwrap();
+        let decoded = SerializedScene::from_bytes(&bytes, format).unwrap();
+        let handle = manager
+            .load_serialized_scene(&decoded, &components)
+            .unwrap();
+        let cameras = manager
+            .scene(handle)
+            .unwrap()
+            .registry()
+            .iter_components_matching::<(Transform, Camera)>()
+            .flat_map(|(transforms, cameras)| transforms.iter().zip(cameras.iter()))
+            .collect::<Vec<_>>();
+        assert_eq!(cameras, vec![(&transform, &camera)]);
+    }
+}
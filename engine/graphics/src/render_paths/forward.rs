@@ -1,6 +1,6 @@
 use crate::{
     render_target::{AcquiredFrameInfo, SwapChain, WindowRenderTarget},
-    Camera, GraphicsDevice, RenderPath, RenderPathCreateInfo,
+    Camera, GraphicsDevice, RenderPath, RenderPathCreateInfo, Transform,
 };
 use ash::*;
 use assets::{asset_id, AssetCache};
@@ -254,6 +254,7 @@ impl RenderPath for ForwardRenderPath {
     fn render(
         &mut self,
         _camera: &Camera,
+        _camera_transform: &Transform,
         info: &AcquiredFrameInfo,
         _window_render_target: &mut WindowRenderTarget,
         device: &GraphicsDevice,
@@ -1,6 +1,6 @@
 use crate::{
     render_target::{AcquiredFrameInfo, SwapChain, WindowRenderTarget},
-    Camera, GraphicsDevice, GraphicsOptions,
+    Camera, GraphicsDevice, GraphicsOptions, Transform,
 };
 use ash::*;
 use assets::AssetCache;
@@ -12,7 +12,7 @@ use std::{
 };
 
 #[repr(u8)]
-#[derive(Clone, Copy, Deserialize, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
 pub enum RenderPathType {
     Forward = 0,
 }
@@ -96,9 +96,11 @@ pub(crate) trait RenderPath {
         device: &GraphicsDevice,
     ) -> bool;
 
+    /// Renders the frame as seen through the camera placed at `camera_transform`.
     fn render(
         &mut self,
         camera: &Camera,
+        camera_transform: &Transform,
         info: &AcquiredFrameInfo,
         window_render_target: &mut WindowRenderTarget,
         device: &GraphicsDevice,
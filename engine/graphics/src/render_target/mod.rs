@@ -1,9 +1,13 @@
 mod render_target;
+mod screenshot;
 mod swap_chain;
+#[cfg(test)]
+mod tests;
 mod window_render_target;
 mod window_render_target_binding;
 
 pub use render_target::*;
+pub(crate) use screenshot::*;
 pub use swap_chain::*;
 pub use window_render_target::*;
 pub use window_render_target_binding::*;
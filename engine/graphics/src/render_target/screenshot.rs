@@ -0,0 +1,197 @@
+use crate::GraphicsDevice;
+use ash::*;
+use gpu_allocator::vulkan::*;
+use gpu_allocator::MemoryLocation;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+use utils::*;
+
+/// Pixels of a swap chain image, tightly packed as 8 bit RGBA.
+pub(crate) struct Screenshot {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+impl Screenshot {
+    /// Converts the pixels copied from an image of the given format.
+    pub fn from_image_data(
+        width: u32,
+        height: u32,
+        format: vk::Format,
+        mut pixels: Vec<u8>,
+    ) -> Result<Self, vk::Result> {
+        match format {
+            vk::Format::B8G8R8A8_SRGB | vk::Format::B8G8R8A8_UNORM => {
+                pixels
+                    .chunks_exact_mut(4)
+                    .for_each(|pixel| pixel.swap(0, 2));
+            }
+            vk::Format::R8G8B8A8_SRGB | vk::Format::R8G8B8A8_UNORM => (),
+            _ => return Err(vk::Result::ERROR_FORMAT_NOT_SUPPORTED),
+        }
+        Ok(Self {
+            width,
+            height,
+            pixels,
+        })
+    }
+
+    /// Copies a swap chain image, which must be in the present layout and no longer in use by the device.
+    pub fn capture(
+        device: &mut GraphicsDevice,
+        image: vk::Image,
+        extent: vk::Extent2D,
+        format: vk::Format,
+    ) -> Result<Self, vk::Result> {
+        let size = extent.width as vk::DeviceSize * extent.height as vk::DeviceSize * 4;
+        let buffer_info = vk::BufferCreateInfo::builder()
+            .size(size)
+            .usage(vk::BufferUsageFlags::TRANSFER_DST)
+            .sharing_mode(vk::SharingMode::EXCLUSIVE);
+        let buffer = unsafe { device.create_buffer(&buffer_info, None)? };
+        let requirements = unsafe { device.get_buffer_memory_requirements(buffer) };
+        let allocation = match device.allocator().allocate(&AllocationCreateDesc {
+            name: "Screenshot",
+            requirements,
+            location: MemoryLocation::GpuToCpu,
+            linear: true,
+            allocation_scheme: AllocationScheme::GpuAllocatorManaged,
+        }) {
+            Ok(v) => v,
+            Err(e) => {
+                t_error!("Could not allocate the screenshot buffer: {}", e);
+                unsafe { device.destroy_buffer(buffer, None) };
+                return Err(vk::Result::ERROR_OUT_OF_DEVICE_MEMORY);
+            }
+        };
+
+        let result = unsafe {
+            device
+                .bind_buffer_memory(buffer, allocation.memory(), allocation.offset())
+                .and_then(|_| copy_image_to_buffer(device, image, extent, buffer))
+        }
+        .and_then(|_| {
+            allocation
+                .mapped_slice()
+                .map(|slice| slice[..size as usize].to_vec())
+                .ok_or(vk::Result::ERROR_MEMORY_MAP_FAILED)
+        });
+
+        unsafe { device.destroy_buffer(buffer, None) };
+        if let Err(e) = device.allocator().free(allocation) {
+            t_error!("Could not free the screenshot buffer: {}", e);
+        }
+        Self::from_image_data(extent.width, extent.height, format, result?)
+    }
+
+    pub fn save_png(&self, path: &Path) -> Result<(), png::EncodingError> {
+        if let Some(directory) = path.parent() {
+            std::fs::create_dir_all(directory)?;
+        }
+        let mut encoder =
+            png::Encoder::new(BufWriter::new(File::create(path)?), self.width, self.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.write_header()?.write_image_data(&self.pixels)
+    }
+}
+
+unsafe fn copy_image_to_buffer(
+    device: &GraphicsDevice,
+    image: vk::Image,
+    extent: vk::Extent2D,
+    buffer: vk::Buffer,
+) -> Result<(), vk::Result> {
+    let queue = device.graphics_queue();
+    let command_pool_info = vk::CommandPoolCreateInfo::builder()
+        .queue_family_index(queue.qf_index)
+        .flags(vk::CommandPoolCreateFlags::TRANSIENT);
+    let command_pool = device.create_command_pool(&command_pool_info, None)?;
+
+    let result = (|| {
+        let alloc_info = vk::CommandBufferAllocateInfo::builder()
+            .command_pool(command_pool)
+            .command_buffer_count(1)
+            .level(vk::CommandBufferLevel::PRIMARY);
+        let command_buffer = device.allocate_command_buffers(&alloc_info)?[0];
+        let begin_info = vk::CommandBufferBeginInfo::builder()
+            .flags(vk::CommandBufferUsageFlags::ONE_TIME_SUBMIT);
+        device.begin_command_buffer(command_buffer, &begin_info)?;
+
+        let subresource_range = vk::ImageSubresourceRange::builder()
+            .aspect_mask(vk::ImageAspectFlags::COLOR)
+            .level_count(1)
+            .layer_count(1)
+            .build();
+        let to_transfer = vk::ImageMemoryBarrier::builder()
+            .src_access_mask(vk::AccessFlags::MEMORY_READ)
+            .dst_access_mask(vk::AccessFlags::TRANSFER_READ)
+            .old_layout(vk::ImageLayout::PRESENT_SRC_KHR)
+            .new_layout(vk::ImageLayout::TRANSFER_SRC_OPTIMAL)
+            .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+            .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+            .image(image)
+            .subresource_range(subresource_range)
+            .build();
+        device.cmd_pipeline_barrier(
+            command_buffer,
+            vk::PipelineStageFlags::TOP_OF_PIPE,
+            vk::PipelineStageFlags::TRANSFER,
+            vk::DependencyFlags::empty(),
+            &[],
+            &[],
+            &[to_transfer],
+        );
+
+        let region = vk::BufferImageCopy::builder()
+            .image_subresource(
+                vk::ImageSubresourceLayers::builder()
+                    .aspect_mask(vk::ImageAspectFlags::COLOR)
+                    .layer_count(1)
+                    .build(),
+            )
+            .image_extent(vk::Extent3D {
+                width: extent.width,
+                height: extent.height,
+                depth: 1,
+            })
+            .build();
+        device.cmd_copy_image_to_buffer(
+            command_buffer,
+            image,
+            vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+            buffer,
+            &[region],
+        );
+
+        // Hand the image back to the presentation engine.
+        let to_present = vk::ImageMemoryBarrier {
+            src_access_mask: vk::AccessFlags::TRANSFER_READ,
+            dst_access_mask: vk::AccessFlags::MEMORY_READ,
+            old_layout: vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+            new_layout: vk::ImageLayout::PRESENT_SRC_KHR,
+            ..to_transfer
+        };
+        device.cmd_pipeline_barrier(
+            command_buffer,
+            vk::PipelineStageFlags::TRANSFER,
+            vk::PipelineStageFlags::BOTTOM_OF_PIPE,
+            vk::DependencyFlags::empty(),
+            &[],
+            &[],
+            &[to_present],
+        );
+        device.end_command_buffer(command_buffer)?;
+
+        let submit_info = vk::SubmitInfo::builder()
+            .command_buffers(core::slice::from_ref(&command_buffer))
+            .build();
+        device.queue_submit(queue.queue, &[submit_info], vk::Fence::null())?;
+        device.queue_wait_idle(queue.queue)
+    })();
+
+    device.destroy_command_pool(command_pool, None);
+    result
+}
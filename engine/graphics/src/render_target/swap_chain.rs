@@ -17,6 +17,7 @@ pub struct SwapChain {
     in_flight_fences: Vec<vk::Fence>,
     image_views: Vec<vk::ImageView>,
     images: Vec<vk::Image>,
+    image_usage: vk::ImageUsageFlags,
     surface_format: vk::SurfaceFormatKHR,
     swap_chain: vk::SwapchainKHR,
     swapchain_loader: extensions::khr::Swapchain,
@@ -78,6 +79,7 @@ impl SwapChain {
         Self {
             image_views,
             images,
+            image_usage: select_image_usage(&surface_info.surface_caps),
             swap_chain,
             swapchain_loader: swap_loader,
             device: device.device_arc(),
@@ -100,6 +102,15 @@ impl SwapChain {
         self.image_views[image_index]
     }
 
+    pub fn image(&self, image_index: usize) -> vk::Image {
+        self.images[image_index]
+    }
+
+    /// Whether the images can be copied from, e.g. to take screenshots.
+    pub fn is_readable(&self) -> bool {
+        self.image_usage.contains(vk::ImageUsageFlags::TRANSFER_SRC)
+    }
+
     pub fn resize_swap_chain(
         &mut self,
         window: &dyn PlatformWindow,
@@ -140,6 +151,7 @@ impl SwapChain {
         let old_swap = self.swap_chain;
         self.swap_chain = new_swap;
         self.current_extent = new_extent;
+        self.image_usage = select_image_usage(&surface_info.surface_caps);
 
         let (images, image_views) = create_images_and_views(
             &self.device,
@@ -365,6 +377,12 @@ fn select_surface_format(_surface_info: &SurfaceInfo) -> vk::SurfaceFormatKHR {
         .build()
 }
 
+fn select_image_usage(caps: &vk::SurfaceCapabilitiesKHR) -> vk::ImageUsageFlags {
+    // Copying from the images is only needed for screenshots, so it is optional.
+    let readable = caps.supported_usage_flags & vk::ImageUsageFlags::TRANSFER_SRC;
+    vk::ImageUsageFlags::COLOR_ATTACHMENT | readable
+}
+
 fn create_swap_chain(
     _device: &Device,
     swap_loader: &ash::extensions::khr::Swapchain,
@@ -386,7 +404,7 @@ fn create_swap_chain(
         .image_color_space(surface_format.color_space)
         .image_extent(extent)
         .image_array_layers(1)
-        .image_usage(vk::ImageUsageFlags::COLOR_ATTACHMENT)
+        .image_usage(select_image_usage(&surface_info.surface_caps))
         .image_sharing_mode(vk::SharingMode::EXCLUSIVE)
         .pre_transform(surface_info.surface_caps.current_transform)
         .composite_alpha(vk::CompositeAlphaFlagsKHR::OPAQUE)
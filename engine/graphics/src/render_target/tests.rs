@@ -0,0 +1,41 @@
+use super::*;
+use ash::vk;
+use std::fs::File;
+
+#[test]
+fn test_screenshot_pixel_formats() {
+    let bgra = vec![1, 2, 3, 4, 5, 6, 7, 8];
+    let screenshot =
+        Screenshot::from_image_data(2, 1, vk::Format::B8G8R8A8_SRGB, bgra.clone()).unwrap();
+    assert_eq!(screenshot.pixels, vec![3, 2, 1, 4, 7, 6, 5, 8]);
+
+    let screenshot =
+        Screenshot::from_image_data(2, 1, vk::Format::R8G8B8A8_UNORM, bgra.clone()).unwrap();
+    assert_eq!(screenshot.pixels, bgra);
+
+    assert_eq!(
+        Screenshot::from_image_data(2, 1, vk::Format::R16G16B16A16_SFLOAT, bgra).err(),
+        Some(vk::Result::ERROR_FORMAT_NOT_SUPPORTED)
+    );
+}
+
+#[test]
+fn test_screenshot_save_png() {
+    let directory = std::env::temp_dir().join(format!("zircon_screenshots_{}", std::process::id()));
+    let path = directory.join("screenshot.png");
+    let pixels = (0..2 * 3 * 4).collect::<Vec<u8>>();
+    let screenshot =
+        Screenshot::from_image_data(2, 3, vk::Format::R8G8B8A8_SRGB, pixels.clone()).unwrap();
+    screenshot.save_png(&path).unwrap();
+
+    let mut reader = png::Decoder::new(File::open(&path).unwrap())
+        .read_info()
+        .unwrap();
+    let mut decoded = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut decoded).unwrap();
+    assert_eq!((info.width, info.height), (2, 3));
+    assert_eq!(info.color_type, png::ColorType::Rgba);
+    assert_eq!(decoded, pixels);
+
+    std::fs::remove_dir_all(directory).unwrap();
+}
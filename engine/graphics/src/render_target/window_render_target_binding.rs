@@ -1,3 +1,4 @@
+use std::path::Path;
 use std::sync::Arc;
 
 use super::*;
@@ -9,6 +10,8 @@ use utils::*;
 
 pub(crate) struct WindowRenderTargetBinding {
     camera: Camera,
+    /// Transform the camera is rendered from, updated from the render snapshots every frame.
+    camera_transform: Transform,
     render_path: Box<dyn RenderPath>,
     swap_chain: SwapChain,
     window_render_target: WindowRenderTarget,
@@ -20,6 +23,14 @@ impl WindowRenderTargetBinding {
         self.window_render_target.window()
     }
 
+    pub fn camera(&self) -> &Camera {
+        &self.camera
+    }
+
+    pub fn set_camera_transform(&mut self, transform: Transform) {
+        self.camera_transform = transform;
+    }
+
     pub fn window_did_resize(
         &mut self,
         device: &GraphicsDevice,
@@ -69,11 +80,13 @@ impl WindowRenderTargetBinding {
         Ok(())
     }
 
+    /// Renders and presents the next frame, saving it as a PNG to `screenshot_path` if given.
     pub fn render(
         &mut self,
-        device: &GraphicsDevice,
+        device: &mut GraphicsDevice,
         input: &mut RenderStageUpdateInput,
         graphics_options: &GraphicsOptions,
+        screenshot_path: Option<&Path>,
     ) -> bool {
         if self.swap_chain.current_extent().width == 0
             && self.swap_chain.current_extent().height == 0
@@ -113,11 +126,15 @@ impl WindowRenderTargetBinding {
         // Render
         self.render_path.render(
             &self.camera,
+            &self.camera_transform,
             &info,
             &mut self.window_render_target,
             device,
             input,
         );
+        if let Some(path) = screenshot_path {
+            self.save_screenshot(device, info.image_index, path);
+        }
 
         // Present the frame to the screen
         match unsafe {
@@ -151,6 +168,34 @@ impl WindowRenderTargetBinding {
         };
         true
     }
+
+    /// Failing to take a screenshot is not fatal, the frame is presented regardless.
+    fn save_screenshot(&self, device: &mut GraphicsDevice, image_index: u32, path: &Path) {
+        if !self.swap_chain.is_readable() {
+            t_warn!("The swap chain images of this window can not be copied for screenshots.");
+            return;
+        }
+        if let Err(e) = unsafe { device.device().device_wait_idle() } {
+            t_error!("Could not wait for the frame to finish rendering: {}", e);
+            return;
+        }
+        let screenshot = match Screenshot::capture(
+            device,
+            self.swap_chain.image(image_index as usize),
+            self.swap_chain.current_extent(),
+            self.swap_chain.surface_format().format,
+        ) {
+            Ok(v) => v,
+            Err(e) => {
+                t_error!("Could not copy the frame for a screenshot: {}", e);
+                return;
+            }
+        };
+        match screenshot.save_png(path) {
+            Ok(_) => t_info!("Saved screenshot to {}", path.display()),
+            Err(e) => t_error!("Could not save screenshot to {}: {}", path.display(), e),
+        }
+    }
 }
 
 impl WindowRenderTargetBinding {
@@ -158,6 +203,7 @@ impl WindowRenderTargetBinding {
         instance: &Instance,
         graphics_device: &GraphicsDevice,
         camera: &Camera,
+        camera_transform: Transform,
         asset_cache: Arc<AssetCache>,
         platform_interface: &mut dyn PlatformInterface,
         mut window_render_target: WindowRenderTarget,
@@ -199,6 +245,7 @@ impl WindowRenderTargetBinding {
         Ok(WindowRenderTargetBinding {
             window_render_target,
             camera: camera.clone(),
+            camera_transform,
             swap_chain,
             render_path: Box::new(render_path),
             resize_on_sub_optimal: options.resize_on_sub_optimal,
@@ -0,0 +1,11 @@
+pub mod platform;
+pub mod window;
+
+#[cfg(test)]
+mod tests;
+
+pub use platform::*;
+pub use window::*;
+
+#[allow(dead_code)]
+pub(crate) const IDENTIFIER: &'static str = "Headless Platform";
@@ -0,0 +1,318 @@
+use crate::*;
+use engine::input::{InputEvent, InputManager, InputRecording};
+use engine::*;
+use std::sync::Arc;
+use utils::as_any::*;
+use utils::defer_drop::{DeferDrop, WeakDeferDrop};
+use utils::*;
+
+/// Platform without any display or event loop.
+/// Intended for dedicated servers and automated tests, where the engine is ticked manually
+/// and window events are emitted on demand.
+#[derive(Default)]
+pub struct HeadlessPlatform {
+    pub(crate) window_id_counter: u16,
+    pub(crate) windows: Vec<HeadlessPlatformWindow>,
+    pub(crate) window_did_open_sender: Option<MessageSender<WindowDidOpen>>,
+    pub(crate) window_did_resize_sender: Option<MessageSender<WindowDidResize>>,
+    pub(crate) window_will_close_sender: Option<MessageSender<WindowWillClose>>,
+    pub(crate) input_sender: Option<MessageSender<InputEvent>>,
+    pub(crate) input_manager: Option<Arc<InputManager>>,
+    /// Replayed once the engine is initialized.
+    pub(crate) input_replay: Option<InputRecording>,
+    pub(crate) stop_after_input_replay: bool,
+    pub(crate) windows_which_close: Vec<WeakDeferDrop>,
+    pub(crate) max_ticks: Option<u64>,
+}
+
+impl HeadlessPlatform {
+    /// Limits the amount of ticks executed by [`Platform::run`]. By default it runs until the engine stops.
+    pub fn with_max_ticks(mut self, max_ticks: u64) -> Self {
+        self.max_ticks = Some(max_ticks);
+        self
+    }
+
+    /// Replays the recorded input once the engine is initialized.
+    /// Unless limited by [`HeadlessPlatform::with_max_ticks`], [`Platform::run`] stops after the replay finished.
+    pub fn with_input_replay(mut self, recording: InputRecording) -> Self {
+        self.input_replay = Some(recording);
+        self.stop_after_input_replay = true;
+        self
+    }
+
+    /// Initializes the engine on this platform and puts it into the running state.
+    /// If initialization fails, the engine stays uninitialized and the failure is returned.
+    pub fn start(&mut self, controller: &mut EngineController) -> EngineUpdateResult {
+        let result = controller.initialize(self);
+        if result.is_ok() {
+            controller.run();
+            return result;
+        }
+        self.handle_update_result(controller, result)
+    }
+
+    /// Executes a single engine tick. A requested restart is handled before returning.
+    pub fn tick(&mut self, controller: &mut EngineController) -> EngineUpdateResult {
+        self.windows_which_close.retain(|e| !e.is_dropped());
+
+        let mut result = EngineUpdateResult::Ok;
+        controller.as_running(|s| result = s.tick(self));
+        self.handle_update_result(controller, result)
+    }
+
+    /// Executes `n_ticks` engine ticks, waiting for the update thread after each one.
+    /// Use together with a [`ManualClock`] to drive the engine deterministically.
+    pub fn step(&mut self, controller: &mut EngineController, n_ticks: u32) -> EngineUpdateResult {
+        self.windows_which_close.retain(|e| !e.is_dropped());
+
+        let mut result = EngineUpdateResult::Ok;
+        controller.as_running(|s| result = s.step(self, n_ticks));
+        self.handle_update_result(controller, result)
+    }
+
+    /// Suspends the engine, executing the suspend hooks of all stages.
+    pub fn suspend(&mut self, controller: &mut EngineController) -> EngineUpdateResult {
+        let result = controller.suspend(self);
+        self.handle_update_result(controller, result)
+    }
+
+    /// Resumes a suspended engine, executing the resume hooks of all stages.
+    pub fn resume(&mut self, controller: &mut EngineController) -> EngineUpdateResult {
+        let result = controller.resume(self);
+        self.handle_update_result(controller, result)
+    }
+
+    /// Stops or restarts the engine if requested by the result. Errors stop the engine.
+    fn handle_update_result(
+        &mut self,
+        controller: &mut EngineController,
+        result: EngineUpdateResult,
+    ) -> EngineUpdateResult {
+        match result {
+            EngineUpdateResult::Stop => {
+                self.shutdown(controller);
+            }
+            EngineUpdateResult::Restart => {
+                self.shutdown(controller);
+                controller.reset();
+                let restarted = self.start(controller);
+                if !restarted.is_ok() {
+                    return restarted;
+                }
+            }
+            EngineUpdateResult::Error(_) => {
+                self.shutdown(controller);
+            }
+            EngineUpdateResult::Ok => (),
+        }
+        result
+    }
+
+    /// Shuts down the engine, tearing down all stages in reverse construction order, and closes all windows.
+    pub fn shutdown(&mut self, controller: &mut EngineController) {
+        controller.shutdown(self);
+        self.clear_windows();
+    }
+
+    pub fn clear_windows(&mut self) {
+        self.windows.clear();
+    }
+
+    /// Opens a virtual window and emits [`WindowDidOpen`].
+    pub fn open_window(
+        &mut self,
+        width: u32,
+        height: u32,
+        title: &str,
+        tag: Option<String>,
+    ) -> Option<PlatformWindowHandle> {
+        self.request_window(width, height, title, tag)
+            .map(|window| window.handle())
+    }
+
+    /// Resizes a virtual window and emits [`WindowDidResize`].
+    /// Returns false if the window does not exist.
+    pub fn resize_window(&mut self, handle: PlatformWindowHandle, width: u32, height: u32) -> bool {
+        let Some(window) = self.windows.iter_mut().find(|e| e.handle == handle) else {
+            return false;
+        };
+        window.width = width;
+        window.height = height;
+        if let Some(sender) = &self.window_did_resize_sender {
+            sender.send(WindowDidResize {
+                window: handle,
+                new_width: width,
+                new_height: height,
+            });
+        }
+        true
+    }
+
+    /// Closes a virtual window and emits [`WindowWillClose`].
+    /// The window is kept alive until all receivers processed the message.
+    /// Returns false if the window does not exist.
+    pub fn close_window(&mut self, handle: PlatformWindowHandle) -> bool {
+        let Some(window_idx) = self.windows.iter().position(|e| e.handle == handle) else {
+            return false;
+        };
+        let window = self.windows.remove(window_idx);
+        if let Some(sender) = &self.window_will_close_sender {
+            let defer_drop = DeferDrop::new(window);
+            self.windows_which_close.push(defer_drop.weak());
+            sender.send(WindowWillClose::new(handle, Some(defer_drop)));
+        }
+        true
+    }
+
+    /// Emits an [`InputEvent`], applied to the input state at the start of the next update tick.
+    pub fn send_input(&self, event: InputEvent) {
+        if let Some(sender) = &self.input_sender {
+            sender.send(event);
+        }
+    }
+
+    /// Replays the recorded input, starting with the next update tick.
+    /// Returns false if the engine was not initialized.
+    pub fn replay_input(&self, recording: InputRecording) -> bool {
+        match &self.input_manager {
+            Some(input_manager) => {
+                input_manager.replay(recording);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns true while recorded input is replayed.
+    pub fn is_replaying_input(&self) -> bool {
+        self.input_manager
+            .as_ref()
+            .is_some_and(|e| e.is_replaying())
+    }
+
+    pub fn get_window(&self, handle: PlatformWindowHandle) -> Option<&HeadlessPlatformWindow> {
+        self.windows.iter().find(|e| e.handle == handle)
+    }
+
+    pub fn get_window_mut(
+        &mut self,
+        handle: PlatformWindowHandle,
+    ) -> Option<&mut HeadlessPlatformWindow> {
+        self.windows.iter_mut().find(|e| e.handle == handle)
+    }
+}
+
+impl PlatformInitalizationHandler for HeadlessPlatform {
+    fn systems_will_init(&mut self, input: engine_stages::PlatformInitInput) -> EngineUpdateResult {
+        let message_bus = input
+            .resources
+            .get_resource::<MessageBus>()
+            .expect("Requires a message bus!");
+
+        self.window_did_open_sender = Some(message_bus.get_sender::<WindowDidOpen>());
+        self.window_did_resize_sender = Some(message_bus.get_sender::<WindowDidResize>());
+        self.window_will_close_sender = Some(message_bus.get_sender::<WindowWillClose>());
+        self.input_sender = Some(message_bus.get_sender::<InputEvent>());
+        self.input_manager = input.resources.get_resource::<InputManager>();
+        if let Some(recording) = self.input_replay.take() {
+            self.replay_input(recording);
+        }
+        EngineUpdateResult::Ok
+    }
+
+    fn systems_did_init(&mut self, _input: engine_stages::PlatformInitInput) -> EngineUpdateResult {
+        EngineUpdateResult::Ok
+    }
+}
+
+impl PlatformInterface for HeadlessPlatform {
+    fn get_windows(&self) -> Vec<PlatformWindowHandle> {
+        self.windows.iter().map(|e| e.handle).collect()
+    }
+
+    fn get_window(&self, handle: PlatformWindowHandle) -> Option<&dyn PlatformWindow> {
+        if let Some(window) = self.windows.iter().find(|e| e.handle == handle) {
+            Some(window)
+        } else {
+            None
+        }
+    }
+
+    fn get_window_mut(&mut self, handle: PlatformWindowHandle) -> Option<&mut dyn PlatformWindow> {
+        if let Some(window) = self.windows.iter_mut().find(|e| e.handle == handle) {
+            Some(window)
+        } else {
+            None
+        }
+    }
+
+    fn get_window_handle_by_tag(&self, tag: &str) -> Option<PlatformWindowHandle> {
+        self.windows
+            .iter()
+            .find(|w| w.tag.as_deref() == Some(tag))
+            .map(|w| w.handle)
+    }
+
+    fn request_window(
+        &mut self,
+        width: u32,
+        height: u32,
+        title: &str,
+        tag: Option<String>,
+    ) -> Option<&dyn PlatformWindow> {
+        if self.window_id_counter == u16::MAX {
+            t_warn!("Constructed too many windows.");
+            return None;
+        }
+        let handle = PlatformWindowHandle::from(self.window_id_counter);
+        self.window_id_counter += 1;
+        self.windows.push(HeadlessPlatformWindow {
+            handle,
+            tag,
+            title: String::from(title),
+            width,
+            height,
+        });
+        if let Some(sender) = &self.window_did_open_sender {
+            sender.send(WindowDidOpen { window: handle });
+        }
+        Some(self.windows.last().unwrap())
+    }
+
+    fn platform_as_any(&mut self) -> &mut dyn std::any::Any {
+        self.as_any_mut()
+    }
+}
+
+impl Platform for HeadlessPlatform {
+    fn run(mut self, controller: EngineController) -> Result<(), StageError> {
+        let mut controller = controller;
+        match self.start(&mut controller) {
+            EngineUpdateResult::Stop => return Ok(()),
+            EngineUpdateResult::Error(e) => return Err(e),
+            _ => (),
+        }
+
+        let mut ticks: u64 = 0;
+        while self.max_ticks.is_none_or(|max_ticks| ticks < max_ticks) {
+            match self.tick(&mut controller) {
+                EngineUpdateResult::Stop => {
+                    t_info!("Engine stopped after {} ticks.", ticks);
+                    return Ok(());
+                }
+                EngineUpdateResult::Error(e) => {
+                    t_info!("Engine failed after {} ticks.", ticks);
+                    return Err(e);
+                }
+                _ => (),
+            }
+            ticks += 1;
+            if self.stop_after_input_replay && !self.is_replaying_input() {
+                t_info!("Input replay finished after {} ticks.", ticks);
+                break;
+            }
+        }
+        self.shutdown(&mut controller);
+        Ok(())
+    }
+}
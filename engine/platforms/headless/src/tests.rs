@@ -0,0 +1,1437 @@
+use crate::HeadlessPlatform;
+use engine::ecs::*;
+use engine::engine_stages::*;
+use engine::input::*;
+use engine::render_world::*;
+use engine::scene_manager::{GlobalTransform, SceneManager, Transform, TransformHierarchy};
+use engine::*;
+use math::*;
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+struct RecordingStage {
+    updates: Arc<AtomicU64>,
+    events: Arc<Mutex<Vec<String>>>,
+}
+
+impl RecordingStage {
+    fn constructor(
+        updates: &Arc<AtomicU64>,
+        events: &Arc<Mutex<Vec<String>>>,
+    ) -> Box<UpdateStageConstructor> {
+        let updates = Arc::clone(updates);
+        let events = Arc::clone(events);
+        Box::new(move |_input| {
+            Box::from(RecordingStage {
+                updates: Arc::clone(&updates),
+                events: Arc::clone(&events),
+            })
+        })
+    }
+}
+
+impl UpdateStage for RecordingStage {
+    const IDENTIFIER: &'static str = "RecordingStage";
+
+    fn register_message_handlers(&self, mut registerer: UpdateMessageRegisterer<'_, Self>) {
+        registerer.register::<WindowDidOpen>();
+        registerer.register::<WindowDidResize>();
+        registerer.register::<WindowWillClose>();
+        registerer.register::<InputEvent>();
+    }
+
+    fn update(&mut self, _input: UpdateStageUpdateInput) -> EngineUpdateResult {
+        self.updates.fetch_add(1, Ordering::AcqRel);
+        EngineUpdateResult::Ok
+    }
+}
+
+impl<'a> MessageHandler<UpdateStageMessageContext<'a>, WindowDidOpen> for RecordingStage {
+    fn handle(&mut self, _context: &mut UpdateStageMessageContext<'a>, _message: WindowDidOpen) {
+        self.events.lock().unwrap().push(String::from("open"));
+    }
+}
+
+impl<'a> MessageHandler<UpdateStageMessageContext<'a>, WindowDidResize> for RecordingStage {
+    fn handle(&mut self, _context: &mut UpdateStageMessageContext<'a>, message: WindowDidResize) {
+        self.events.lock().unwrap().push(format!(
+            "resize {}x{}",
+            message.new_width, message.new_height
+        ));
+    }
+}
+
+impl<'a> MessageHandler<UpdateStageMessageContext<'a>, WindowWillClose> for RecordingStage {
+    fn handle(&mut self, _context: &mut UpdateStageMessageContext<'a>, _message: WindowWillClose) {
+        self.events.lock().unwrap().push(String::from("close"));
+    }
+}
+
+impl<'a> MessageHandler<UpdateStageMessageContext<'a>, InputEvent> for RecordingStage {
+    fn handle(&mut self, _context: &mut UpdateStageMessageContext<'a>, message: InputEvent) {
+        self.events.lock().unwrap().push(format!("{:?}", message));
+    }
+}
+
+struct LifecycleStage {
+    name: &'static str,
+    suspend_result: EngineUpdateResult,
+    events: Arc<Mutex<Vec<String>>>,
+}
+
+impl LifecycleStage {
+    fn constructor(
+        name: &'static str,
+        suspend_result: EngineUpdateResult,
+        events: &Arc<Mutex<Vec<String>>>,
+    ) -> Box<UpdateStageConstructor> {
+        let events = Arc::clone(events);
+        Box::new(move |_input| {
+            Box::from(LifecycleStage {
+                name,
+                suspend_result: suspend_result.clone(),
+                events: Arc::clone(&events),
+            })
+        })
+    }
+
+    fn log(&self, event: &str) {
+        self.events
+            .lock()
+            .unwrap()
+            .push(format!("{} {}", self.name, event));
+    }
+}
+
+impl UpdateStage for LifecycleStage {
+    const IDENTIFIER: &'static str = "LifecycleStage";
+
+    fn update(&mut self, _input: UpdateStageUpdateInput) -> EngineUpdateResult {
+        self.log("update");
+        EngineUpdateResult::Ok
+    }
+
+    fn engine_will_suspend(&mut self, _input: UpdateStageUpdateInput) -> EngineUpdateResult {
+        self.log("suspend");
+        self.suspend_result.clone()
+    }
+
+    fn engine_will_resume(&mut self, _input: UpdateStageUpdateInput) -> EngineUpdateResult {
+        self.log("resume");
+        EngineUpdateResult::Ok
+    }
+
+    fn engine_will_shutdown(&mut self, input: UpdateStageUpdateInput) {
+        self.log("shutdown");
+        let events = Arc::clone(&self.events);
+        let job = format!("{} shutdown job", self.name);
+        input.dispatcher.spawn(move || {
+            std::thread::sleep(Duration::from_millis(20));
+            events.lock().unwrap().push(job);
+        });
+        let events = Arc::clone(&self.events);
+        let job = format!("{} async shutdown job", self.name);
+        input.dispatcher.spawn_async(async move {
+            std::thread::sleep(Duration::from_millis(20));
+            events.lock().unwrap().push(job);
+        });
+    }
+}
+
+const SCHEDULED_STAGE_NAMES: [&str; 3] = ["physics", "ai", "audio"];
+
+/// Stages 0 and 1 only succeed if they run at the same time.
+struct ScheduledStage<const N: usize> {
+    dependencies: UpdateStageDependencies,
+    arrived: Arc<AtomicU64>,
+    events: Arc<Mutex<Vec<String>>>,
+}
+
+impl<const N: usize> UpdateStage for ScheduledStage<N> {
+    const IDENTIFIER: &'static str = SCHEDULED_STAGE_NAMES[N];
+
+    fn dependencies(&self) -> UpdateStageDependencies {
+        self.dependencies.clone()
+    }
+
+    fn update(&mut self, input: UpdateStageUpdateInput) -> EngineUpdateResult {
+        let _ = input.scene_manager.active_scene();
+        if N < 2 {
+            self.arrived.fetch_add(1, Ordering::AcqRel);
+            let deadline = Instant::now() + Duration::from_secs(5);
+            while self.arrived.load(Ordering::Acquire) < 2 {
+                if Instant::now() > deadline {
+                    return EngineUpdateResult::Stop;
+                }
+                std::thread::yield_now();
+            }
+        }
+        self.events
+            .lock()
+            .unwrap()
+            .push(String::from(Self::IDENTIFIER));
+        EngineUpdateResult::Ok
+    }
+}
+
+fn scheduled_stage<const N: usize>(
+    dependencies: UpdateStageDependencies,
+    arrived: &Arc<AtomicU64>,
+    events: &Arc<Mutex<Vec<String>>>,
+) -> Box<UpdateStageConstructor> {
+    let arrived = Arc::clone(arrived);
+    let events = Arc::clone(events);
+    Box::new(move |_input| {
+        Box::from(ScheduledStage::<N> {
+            dependencies: dependencies.clone(),
+            arrived: Arc::clone(&arrived),
+            events: Arc::clone(&events),
+        })
+    })
+}
+
+const FAILING_STAGE_NAMES: [&str; 2] = ["physics", "audio"];
+
+/// Fails every update, and optionally its initialization.
+struct FailingStage<const N: usize> {
+    fail_initialization: bool,
+    updates: Arc<AtomicU64>,
+}
+
+impl<const N: usize> UpdateStage for FailingStage<N> {
+    const IDENTIFIER: &'static str = FAILING_STAGE_NAMES[N];
+
+    fn engine_did_initialize(&mut self, _input: EngineDidInitInput) -> EngineUpdateResult {
+        if self.fail_initialization {
+            return EngineUpdateResult::error("device not found");
+        }
+        EngineUpdateResult::Ok
+    }
+
+    fn update(&mut self, _input: UpdateStageUpdateInput) -> EngineUpdateResult {
+        self.updates.fetch_add(1, Ordering::AcqRel);
+        EngineUpdateResult::error(format!("{} failed", Self::IDENTIFIER))
+    }
+}
+
+fn failing_stage<const N: usize>(
+    fail_initialization: bool,
+    updates: &Arc<AtomicU64>,
+) -> Box<UpdateStageConstructor> {
+    let updates = Arc::clone(updates);
+    Box::new(move |_input| {
+        Box::from(FailingStage::<N> {
+            fail_initialization,
+            updates: Arc::clone(&updates),
+        })
+    })
+}
+
+/// Headless platform whose systems fail to initialize.
+#[derive(Default)]
+struct FailingPlatform(HeadlessPlatform);
+
+impl PlatformInterface for FailingPlatform {
+    fn get_windows(&self) -> Vec<PlatformWindowHandle> {
+        self.0.get_windows()
+    }
+
+    fn get_window(&self, handle: PlatformWindowHandle) -> Option<&dyn PlatformWindow> {
+        PlatformInterface::get_window(&self.0, handle)
+    }
+
+    fn get_window_mut(&mut self, handle: PlatformWindowHandle) -> Option<&mut dyn PlatformWindow> {
+        PlatformInterface::get_window_mut(&mut self.0, handle)
+    }
+
+    fn get_window_handle_by_tag(&self, tag: &str) -> Option<PlatformWindowHandle> {
+        self.0.get_window_handle_by_tag(tag)
+    }
+
+    fn request_window(
+        &mut self,
+        width: u32,
+        height: u32,
+        title: &str,
+        tag: Option<String>,
+    ) -> Option<&dyn PlatformWindow> {
+        self.0.request_window(width, height, title, tag)
+    }
+
+    fn platform_as_any(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+}
+
+impl PlatformInitalizationHandler for FailingPlatform {
+    fn systems_will_init(&mut self, _input: PlatformInitInput) -> EngineUpdateResult {
+        EngineUpdateResult::error("no display")
+    }
+
+    fn systems_did_init(&mut self, _input: PlatformInitInput) -> EngineUpdateResult {
+        EngineUpdateResult::Ok
+    }
+}
+
+struct AlphaRecordingStage {
+    events: Arc<Mutex<Vec<String>>>,
+}
+
+struct NoopUpdateThreadHandler;
+
+impl RenderStageUpdateThreadHandler for NoopUpdateThreadHandler {}
+
+impl RenderStage for AlphaRecordingStage {
+    const IDENTIFIER: &'static str = "AlphaRecordingStage";
+    type UpdateThreadHandler = NoopUpdateThreadHandler;
+
+    fn create_update_thread_handler(
+        &mut self,
+        _create_info: RenderStageUpdateThreadHandlerCreateInfo<'_>,
+    ) -> Self::UpdateThreadHandler {
+        NoopUpdateThreadHandler
+    }
+
+    fn update_thread_did_run(&mut self, _input: RenderStageUpdateInput) -> EngineUpdateResult {
+        self.events.lock().unwrap().push(String::from("update"));
+        EngineUpdateResult::Ok
+    }
+
+    fn render(&mut self, input: RenderStageUpdateInput) -> EngineUpdateResult {
+        self.events
+            .lock()
+            .unwrap()
+            .push(format!("render {:.2}", input.alpha_till_next_update));
+        EngineUpdateResult::Ok
+    }
+
+    fn engine_will_shutdown(&mut self, _input: RenderStageUpdateInput) {
+        self.events
+            .lock()
+            .unwrap()
+            .push(String::from("render shutdown"));
+    }
+}
+
+#[derive(Debug, Copy, Clone, Component)]
+struct Position(f32);
+
+impl Interpolate for Position {
+    fn interpolate(&self, next: &Self, alpha: f32) -> Self {
+        Position(self.0.interpolate(&next.0, alpha))
+    }
+}
+
+/// Attaches a child to a root on the first update, then records the global position of the child.
+struct HierarchyStage {
+    child: Option<Entity>,
+    positions: Arc<Mutex<Vec<f32>>>,
+}
+
+impl UpdateStage for HierarchyStage {
+    const IDENTIFIER: &'static str = "HierarchyStage";
+
+    fn update(&mut self, mut input: UpdateStageUpdateInput) -> EngineUpdateResult {
+        let registry = input.scene_manager.active_scene_mut().registry_mut();
+        match self.child {
+            Some(child) => {
+                let global = registry.get_component::<GlobalTransform>(child).unwrap();
+                self.positions
+                    .lock()
+                    .unwrap()
+                    .push(global.transform().position().x());
+            }
+            None => {
+                let translation = Transform::new(
+                    Vec3f::from_components(1.0, 0.0, 0.0),
+                    Vec4f::from_components(0.0, 0.0, 0.0, 1.0),
+                    1.0,
+                );
+                let root = registry.create_entity(translation).unwrap();
+                let child = registry.create_entity(translation).unwrap();
+                TransformHierarchy::set_parent(registry, child, root);
+                self.child = Some(child);
+            }
+        }
+        EngineUpdateResult::Ok
+    }
+}
+
+/// Moves a single entity by one unit per update.
+struct MovingStage {
+    entity: Option<Entity>,
+}
+
+impl UpdateStage for MovingStage {
+    const IDENTIFIER: &'static str = "MovingStage";
+
+    fn update(&mut self, mut input: UpdateStageUpdateInput) -> EngineUpdateResult {
+        let registry = input.scene_manager.active_scene_mut().registry_mut();
+        match self.entity {
+            Some(entity) => registry.get_component_mut::<Position>(entity).unwrap().0 += 1.0,
+            None => self.entity = registry.create_entity(Position(1.0)).ok(),
+        }
+        EngineUpdateResult::Ok
+    }
+}
+
+/// Creates an entity in the active scene and in a second scene.
+struct TwoScenesStage {
+    created: bool,
+}
+
+impl UpdateStage for TwoScenesStage {
+    const IDENTIFIER: &'static str = "TwoScenesStage";
+
+    fn update(&mut self, mut input: UpdateStageUpdateInput) -> EngineUpdateResult {
+        if !std::mem::replace(&mut self.created, true) {
+            let scene_manager = &mut input.scene_manager;
+            let background = scene_manager.create_scene("background");
+            let registry = scene_manager.scene_mut(background).unwrap().registry_mut();
+            registry.create_entity(Position(10.0)).unwrap();
+            let registry = scene_manager.active_scene_mut().registry_mut();
+            registry.create_entity(Position(1.0)).unwrap();
+        }
+        EngineUpdateResult::Ok
+    }
+}
+
+/// Records the interpolated position of every entity.
+struct InterpolatingStage {
+    render_world: Arc<RenderWorld>,
+    events: Arc<Mutex<Vec<String>>>,
+}
+
+impl RenderStage for InterpolatingStage {
+    const IDENTIFIER: &'static str = "InterpolatingStage";
+    type UpdateThreadHandler = NoopUpdateThreadHandler;
+
+    fn create_update_thread_handler(
+        &mut self,
+        _create_info: RenderStageUpdateThreadHandlerCreateInfo<'_>,
+    ) -> Self::UpdateThreadHandler {
+        NoopUpdateThreadHandler
+    }
+
+    fn render(&mut self, input: RenderStageUpdateInput) -> EngineUpdateResult {
+        let snapshots = self.render_world.snapshots();
+        let positions = snapshots
+            .iter_interpolated::<Position>(input.alpha_till_next_update)
+            .map(|(_, position)| format!("{:.2}", position.0))
+            .collect::<Vec<_>>();
+        self.events.lock().unwrap().push(format!(
+            "{} [{}]",
+            snapshots.current().update_index(),
+            positions.join(", ")
+        ));
+        EngineUpdateResult::Ok
+    }
+}
+
+/// Records the state of the `jump` action in every update.
+struct InputStage {
+    events: Arc<Mutex<Vec<String>>>,
+}
+
+impl UpdateStage for InputStage {
+    const IDENTIFIER: &'static str = "InputStage";
+
+    fn update(&mut self, input: UpdateStageUpdateInput) -> EngineUpdateResult {
+        let input_manager = input.resources.resource::<InputManager>();
+        let state = input_manager.state();
+        if state.was_action_pressed("jump") {
+            self.events
+                .lock()
+                .unwrap()
+                .push(String::from("jump pressed"));
+        }
+        if state.was_action_released("jump") {
+            self.events
+                .lock()
+                .unwrap()
+                .push(String::from("jump released"));
+        }
+        if !state.text().is_empty() {
+            self.events
+                .lock()
+                .unwrap()
+                .push(format!("text {}", state.text()));
+        }
+        EngineUpdateResult::Ok
+    }
+}
+
+/// Moves a single entity along the `move_x` axis and records its position in every update.
+struct PlayerStage {
+    entity: Option<Entity>,
+    events: Arc<Mutex<Vec<String>>>,
+}
+
+impl PlayerStage {
+    fn constructor(events: &Arc<Mutex<Vec<String>>>) -> Box<UpdateStageConstructor> {
+        let events = Arc::clone(events);
+        Box::new(move |_input| {
+            Box::from(PlayerStage {
+                entity: None,
+                events: Arc::clone(&events),
+            })
+        })
+    }
+}
+
+impl UpdateStage for PlayerStage {
+    const IDENTIFIER: &'static str = "PlayerStage";
+
+    fn update(&mut self, mut input: UpdateStageUpdateInput) -> EngineUpdateResult {
+        let input_manager = input.resources.resource::<InputManager>();
+        let registry = input.scene_manager.active_scene_mut().registry_mut();
+        let entity = match self.entity {
+            Some(entity) => entity,
+            None => {
+                input_manager.bind_axis(
+                    "move_x",
+                    AxisBinding {
+                        positive: vec![InputBinding::Key(KeyCode::D)],
+                        negative: vec![InputBinding::Key(KeyCode::A)],
+                        ..Default::default()
+                    },
+                );
+                *self
+                    .entity
+                    .insert(registry.create_entity(Position(0.0)).unwrap())
+            }
+        };
+        let position = registry.get_component_mut::<Position>(entity).unwrap();
+        position.0 += input_manager.state().axis("move_x");
+        self.events.lock().unwrap().push(format!("{}", position.0));
+        EngineUpdateResult::Ok
+    }
+}
+
+fn create_info(
+    update_tick_rate: u32,
+    update_stages: Vec<Box<UpdateStageConstructor>>,
+    render_stages: Vec<Box<RenderStageConstructor>>,
+    clock: Option<Arc<dyn Clock>>,
+) -> EngineCreateInfo {
+    EngineCreateInfo {
+        update_tick_rate,
+        max_skipped_frames: 1,
+        max_frame_rate: None,
+        update_stages,
+        render_stages,
+        application_info: Box::new(|_| ApplicationInfo::default()),
+        concurrency_settings: EngineConcurrencySettings {
+            max_async_threads: NonZeroUsize::new(1),
+            max_worker_thread: NonZeroUsize::new(2),
+            fallback_worker_threads: NonZeroUsize::new(2).unwrap(),
+            fallback_async_threads: NonZeroUsize::new(1).unwrap(),
+        },
+        asset_registry: Box::new(|_| Default::default()),
+        clock,
+        frame_stats: Default::default(),
+        stage_error_policies: Default::default(),
+        message_delivery_policies: Default::default(),
+        input_recording: None,
+        commands: Default::default(),
+    }
+}
+
+fn create_controller(updates: Arc<AtomicU64>, events: Arc<Mutex<Vec<String>>>) -> EngineController {
+    let create_info = create_info(
+        1000,
+        vec![RecordingStage::constructor(&updates, &events)],
+        vec![],
+        None,
+    );
+    EngineController::from(Engine::from(create_info))
+}
+
+/// Creates an engine running a single update stage, ticking 10 times per second of the manual clock.
+fn create_stage_controller(
+    clock: &Arc<ManualClock>,
+    stage: Box<UpdateStageConstructor>,
+    commands: EngineCommandSettings,
+) -> EngineController {
+    let mut create_info = create_info(10, vec![stage], vec![], Some(clock.clone()));
+    create_info.commands = commands;
+    EngineController::from(Engine::from(create_info))
+}
+
+#[test]
+fn test_headless_window_events() {
+    let updates = Arc::new(AtomicU64::new(0));
+    let events = Arc::new(Mutex::new(vec![]));
+    let mut controller = create_controller(Arc::clone(&updates), Arc::clone(&events));
+    let mut platform = HeadlessPlatform::default();
+    platform.start(&mut controller);
+
+    let window = platform
+        .open_window(800, 600, "Headless", Some("main_window".into()))
+        .expect("Could not open virtual window.");
+    assert!(platform.resize_window(window, 1024, 768));
+    assert!(platform.close_window(window));
+    assert!(platform.get_window(window).is_none());
+
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while (updates.load(Ordering::Acquire) < 3 || events.lock().unwrap().len() < 3)
+        && Instant::now() < deadline
+    {
+        assert_eq!(platform.tick(&mut controller), EngineUpdateResult::Ok);
+    }
+
+    assert!(updates.load(Ordering::Acquire) >= 3);
+    assert_eq!(
+        *events.lock().unwrap(),
+        vec!["open", "resize 1024x768", "close"]
+    );
+}
+
+#[test]
+fn test_headless_manual_clock_stepping() {
+    let clock = Arc::new(ManualClock::new());
+    let updates = Arc::new(AtomicU64::new(0));
+    let events = Arc::new(Mutex::new(vec![]));
+    let create_info = {
+        let render_events = Arc::clone(&events);
+        create_info(
+            10,
+            vec![RecordingStage::constructor(&updates, &Default::default())],
+            vec![Box::new(move |_input| {
+                Box::from(RenderStageContainer::from(AlphaRecordingStage {
+                    events: Arc::clone(&render_events),
+                }))
+            })],
+            Some(clock.clone()),
+        )
+    };
+    let mut controller = EngineController::from(Engine::from(create_info));
+    let mut platform = HeadlessPlatform::default();
+    platform.start(&mut controller);
+
+    // Half a tick has passed, nothing is updated yet.
+    clock.advance(Duration::from_millis(50));
+    assert_eq!(platform.step(&mut controller, 1), EngineUpdateResult::Ok);
+    assert_eq!(updates.load(Ordering::Acquire), 0);
+
+    // A single tick is due.
+    clock.advance(Duration::from_millis(60));
+    assert_eq!(platform.step(&mut controller, 1), EngineUpdateResult::Ok);
+    assert_eq!(updates.load(Ordering::Acquire), 1);
+
+    // Falling behind is capped by the amount of frames that may be skipped.
+    clock.advance(Duration::from_millis(350));
+    assert_eq!(platform.step(&mut controller, 1), EngineUpdateResult::Ok);
+    assert_eq!(updates.load(Ordering::Acquire), 3);
+
+    // The remaining backlog is worked off by the next tick.
+    assert_eq!(platform.step(&mut controller, 1), EngineUpdateResult::Ok);
+    assert_eq!(updates.load(Ordering::Acquire), 4);
+
+    assert_eq!(
+        *events.lock().unwrap(),
+        vec![
+            "render 0.50",
+            "update",
+            "render 0.10",
+            "update",
+            "update",
+            "render 1.00",
+            "update",
+            "render 0.60",
+        ]
+    );
+}
+
+#[test]
+fn test_headless_frame_stats() {
+    let clock = Arc::new(ManualClock::new());
+    let updates = Arc::new(AtomicU64::new(0));
+    let mut controller = create_stage_controller(
+        &clock,
+        RecordingStage::constructor(&updates, &Default::default()),
+        Default::default(),
+    );
+    let mut platform = HeadlessPlatform::default();
+    platform.start(&mut controller);
+
+    for frame_time in [100, 300, 200] {
+        clock.advance(Duration::from_millis(frame_time));
+        assert_eq!(platform.step(&mut controller, 1), EngineUpdateResult::Ok);
+    }
+
+    let stats = controller
+        .shared()
+        .resources
+        .get_resource::<EngineFrameStats>()
+        .expect("Frame stats should be registered as resource.")
+        .snapshot();
+    assert_eq!(stats.frame_times.len(), 3);
+    assert_eq!(stats.frame_times.min(), Some(Duration::from_millis(100)));
+    assert_eq!(stats.frame_times.max(), Some(Duration::from_millis(300)));
+    assert_eq!(
+        stats.frame_times.average(),
+        Some(Duration::from_millis(200))
+    );
+    assert_eq!(
+        stats.frame_times.percentile(50.0),
+        Some(Duration::from_millis(200))
+    );
+    assert_eq!(
+        stats.update_times.len() as u64,
+        updates.load(Ordering::Acquire)
+    );
+    assert_eq!(
+        stats.update_stage_times["RecordingStage"].len() as u64,
+        updates.load(Ordering::Acquire)
+    );
+}
+
+#[test]
+fn test_headless_suspend_resume_hooks() {
+    let clock = Arc::new(ManualClock::new());
+    let events = Arc::new(Mutex::new(vec![]));
+    let create_info = create_info(
+        10,
+        vec![
+            LifecycleStage::constructor("first", EngineUpdateResult::Ok, &events),
+            LifecycleStage::constructor("second", EngineUpdateResult::Ok, &events),
+        ],
+        vec![],
+        Some(clock.clone()),
+    );
+    let mut controller = EngineController::from(Engine::from(create_info));
+    let mut platform = HeadlessPlatform::default();
+    platform.start(&mut controller);
+
+    clock.advance(Duration::from_millis(100));
+    assert_eq!(platform.step(&mut controller, 1), EngineUpdateResult::Ok);
+    assert_eq!(platform.suspend(&mut controller), EngineUpdateResult::Ok);
+
+    // Suspended engines do not tick, and the time spent suspended is not caught up on.
+    clock.advance(Duration::from_secs(10));
+    assert_eq!(platform.tick(&mut controller), EngineUpdateResult::Ok);
+    assert_eq!(platform.resume(&mut controller), EngineUpdateResult::Ok);
+    assert_eq!(platform.step(&mut controller, 1), EngineUpdateResult::Ok);
+
+    assert_eq!(
+        *events.lock().unwrap(),
+        vec![
+            "first update",
+            "second update",
+            "first suspend",
+            "second suspend",
+            "first resume",
+            "second resume",
+        ]
+    );
+}
+
+#[test]
+fn test_headless_suspend_honours_stop() {
+    let events = Arc::new(Mutex::new(vec![]));
+    let create_info = create_info(
+        10,
+        vec![
+            LifecycleStage::constructor("first", EngineUpdateResult::Stop, &events),
+            LifecycleStage::constructor("second", EngineUpdateResult::Ok, &events),
+        ],
+        vec![],
+        Some(Arc::new(ManualClock::new())),
+    );
+    let mut controller = EngineController::from(Engine::from(create_info));
+    let mut platform = HeadlessPlatform::default();
+    platform.start(&mut controller);
+
+    assert_eq!(platform.suspend(&mut controller), EngineUpdateResult::Stop);
+
+    // The second stage is never suspended, and stopping shuts the engine down.
+    let events = events.lock().unwrap();
+    let hooks = events
+        .iter()
+        .filter(|e| !e.ends_with("job"))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        hooks,
+        vec!["first suspend", "second shutdown", "first shutdown"]
+    );
+
+    let mut is_running = false;
+    controller.as_running(|_| is_running = true);
+    assert!(!is_running);
+}
+
+#[test]
+fn test_headless_ordered_shutdown() {
+    let events = Arc::new(Mutex::new(vec![]));
+    let create_info = {
+        let render_events = Arc::clone(&events);
+        create_info(
+            10,
+            vec![
+                LifecycleStage::constructor("first", EngineUpdateResult::Ok, &events),
+                LifecycleStage::constructor("second", EngineUpdateResult::Ok, &events),
+            ],
+            vec![Box::new(move |_input| {
+                Box::from(RenderStageContainer::from(AlphaRecordingStage {
+                    events: Arc::clone(&render_events),
+                }))
+            })],
+            Some(Arc::new(ManualClock::new())),
+        )
+    };
+    let mut controller = EngineController::from(Engine::from(create_info));
+    let mut platform = HeadlessPlatform::default();
+    platform.start(&mut controller);
+    platform.shutdown(&mut controller);
+
+    // Jobs spawned during shutdown are joined before it returns, including async jobs.
+    let events = events.lock().unwrap();
+    let (jobs, hooks): (Vec<_>, Vec<_>) = events.iter().partition(|e| e.ends_with("job"));
+    assert_eq!(
+        hooks,
+        vec!["render shutdown", "second shutdown", "first shutdown"]
+    );
+    assert_eq!(jobs.len(), 4);
+
+    // Shutting down again does nothing.
+    drop(events);
+    assert!(!controller.shutdown(&mut platform));
+}
+
+#[test]
+fn test_headless_parallel_update_stages() {
+    let clock = Arc::new(ManualClock::new());
+    let arrived = Arc::new(AtomicU64::new(0));
+    let events = Arc::new(Mutex::new(vec![]));
+    let create_info = create_info(
+        10,
+        vec![
+            scheduled_stage::<2>(
+                UpdateStageDependencies::new()
+                    .reads::<SceneManager>()
+                    .after::<ScheduledStage<0>>()
+                    .after::<ScheduledStage<1>>(),
+                &arrived,
+                &events,
+            ),
+            scheduled_stage::<0>(
+                UpdateStageDependencies::new().reads::<SceneManager>(),
+                &arrived,
+                &events,
+            ),
+            scheduled_stage::<1>(
+                UpdateStageDependencies::new().reads::<SceneManager>(),
+                &arrived,
+                &events,
+            ),
+        ],
+        vec![],
+        Some(clock.clone()),
+    );
+    let mut controller = EngineController::from(Engine::from(create_info));
+    let mut platform = HeadlessPlatform::default();
+    platform.start(&mut controller);
+
+    clock.advance(Duration::from_millis(100));
+    assert_eq!(platform.step(&mut controller, 1), EngineUpdateResult::Ok);
+
+    let events = events.lock().unwrap();
+    assert_eq!(events.len(), 3);
+    assert_eq!(events[2], "audio");
+}
+
+#[test]
+fn test_headless_render_snapshot_interpolation() {
+    let clock = Arc::new(ManualClock::new());
+    let events = Arc::new(Mutex::new(vec![]));
+    let create_info = {
+        let events = Arc::clone(&events);
+        create_info(
+            10,
+            vec![Box::new(|_input| Box::from(MovingStage { entity: None }))],
+            vec![Box::new(move |input| {
+                let render_world = input.resources.get_resource::<RenderWorld>().unwrap();
+                render_world.register_component::<Position>();
+                Box::from(RenderStageContainer::from(InterpolatingStage {
+                    render_world,
+                    events: Arc::clone(&events),
+                }))
+            })],
+            Some(clock.clone()),
+        )
+    };
+    let mut controller = EngineController::from(Engine::from(create_info));
+    let mut platform = HeadlessPlatform::default();
+    platform.start(&mut controller);
+
+    for frame_time in [100, 100, 150, 100] {
+        clock.advance(Duration::from_millis(frame_time));
+        assert_eq!(platform.step(&mut controller, 1), EngineUpdateResult::Ok);
+    }
+
+    // Snapshots are published once the engine collects an update, entities without a previous value are not
+    // interpolated.
+    assert_eq!(
+        *events.lock().unwrap(),
+        vec!["0 []", "1 [1.00]", "2 [1.50]", "3 [2.50]"]
+    );
+}
+
+#[test]
+fn test_headless_render_snapshot_scenes() {
+    let clock = Arc::new(ManualClock::new());
+    let events = Arc::new(Mutex::new(vec![]));
+    let create_info = {
+        let events = Arc::clone(&events);
+        create_info(
+            10,
+            vec![Box::new(|_input| {
+                Box::from(TwoScenesStage { created: false })
+            })],
+            vec![Box::new(move |input| {
+                let render_world = input.resources.get_resource::<RenderWorld>().unwrap();
+                render_world.register_component::<Position>();
+                Box::from(RenderStageContainer::from(InterpolatingStage {
+                    render_world,
+                    events: Arc::clone(&events),
+                }))
+            })],
+            Some(clock.clone()),
+        )
+    };
+    let mut controller = EngineController::from(Engine::from(create_info));
+    let mut platform = HeadlessPlatform::default();
+    platform.start(&mut controller);
+
+    for _ in 0..2 {
+        clock.advance(Duration::from_millis(100));
+        assert_eq!(platform.step(&mut controller, 1), EngineUpdateResult::Ok);
+    }
+
+    // Every scene is extracted in creation order, entities of different scenes do not collide.
+    assert_eq!(*events.lock().unwrap(), vec!["0 []", "1 [1.00, 10.00]"]);
+}
+
+#[test]
+fn test_headless_transform_propagation() {
+    let clock = Arc::new(ManualClock::new());
+    let positions = Arc::new(Mutex::new(vec![]));
+    let create_info = {
+        let positions = Arc::clone(&positions);
+        create_info(
+            10,
+            vec![Box::new(move |_input| {
+                Box::from(HierarchyStage {
+                    child: None,
+                    positions: Arc::clone(&positions),
+                })
+            })],
+            vec![],
+            Some(clock.clone()),
+        )
+    };
+    let mut controller = EngineController::from(Engine::from(create_info));
+    let mut platform = HeadlessPlatform::default();
+    platform.start(&mut controller);
+
+    // The engine propagates the hierarchy after every update, without any render stage.
+    for _ in 0..3 {
+        clock.advance(Duration::from_millis(100));
+        assert_eq!(platform.step(&mut controller, 1), EngineUpdateResult::Ok);
+    }
+    platform.shutdown(&mut controller);
+    assert_eq!(*positions.lock().unwrap(), vec![2.0, 2.0]);
+}
+
+#[test]
+fn test_headless_stage_error_policies() {
+    let clock = Arc::new(ManualClock::new());
+    let disabled_updates = Arc::new(AtomicU64::new(0));
+    let continued_updates = Arc::new(AtomicU64::new(0));
+    let mut create_info = create_info(
+        10,
+        vec![
+            failing_stage::<0>(false, &disabled_updates),
+            failing_stage::<1>(false, &continued_updates),
+        ],
+        vec![],
+        Some(clock.clone()),
+    );
+    create_info.stage_error_policies = StageErrorPolicies::default()
+        .with_policy("physics", StageErrorPolicy::Disable)
+        .with_policy("audio", StageErrorPolicy::LogAndContinue);
+    let mut controller = EngineController::from(Engine::from(create_info));
+    let mut platform = HeadlessPlatform::default();
+    assert_eq!(platform.start(&mut controller), EngineUpdateResult::Ok);
+
+    for _ in 0..3 {
+        clock.advance(Duration::from_millis(100));
+        assert_eq!(platform.step(&mut controller, 1), EngineUpdateResult::Ok);
+    }
+    assert_eq!(disabled_updates.load(Ordering::Acquire), 1);
+    assert_eq!(continued_updates.load(Ordering::Acquire), 3);
+}
+
+#[test]
+fn test_headless_stage_error_stops_engine() {
+    let updates = Arc::new(AtomicU64::new(0));
+    let create_info = create_info(
+        1000,
+        vec![failing_stage::<1>(false, &updates)],
+        vec![],
+        None,
+    );
+
+    // The default policy stops the engine and returns the error to the caller of run.
+    let error = Engine::from(create_info)
+        .run(HeadlessPlatform::default())
+        .unwrap_err();
+    assert_eq!(error.stage(), Some("audio"));
+    assert_eq!(error.phase(), Some(StagePhase::Update));
+    assert_eq!(
+        error.to_string(),
+        "Stage audio failed during update: audio failed"
+    );
+    assert_eq!(updates.load(Ordering::Acquire), 1);
+}
+
+#[test]
+fn test_headless_stage_initialization_error() {
+    let updates = Arc::new(AtomicU64::new(0));
+    let failing_create_info = create_info(
+        10,
+        vec![failing_stage::<0>(true, &updates)],
+        vec![],
+        Some(Arc::new(ManualClock::new())),
+    );
+    let mut controller = EngineController::from(Engine::from(failing_create_info));
+    let mut platform = HeadlessPlatform::default();
+
+    // Failed initializations leave the engine uninitialized instead of aborting.
+    match platform.start(&mut controller) {
+        EngineUpdateResult::Error(e) => {
+            assert_eq!(e.stage(), Some("physics"));
+            assert_eq!(e.phase(), Some(StagePhase::Initialize));
+        }
+        result => panic!("Expected an initialization error, got {:?}.", result),
+    }
+    let mut is_uninitialized = false;
+    controller.as_uninitialized(|_| is_uninitialized = true);
+    assert!(is_uninitialized);
+
+    // Disabled stages are skipped for the remaining lifetime of the engine.
+    let clock = Arc::new(ManualClock::new());
+    let mut disabling_create_info = create_info(
+        10,
+        vec![failing_stage::<0>(true, &updates)],
+        vec![],
+        Some(clock.clone()),
+    );
+    disabling_create_info.stage_error_policies.default = StageErrorPolicy::Disable;
+    let mut controller = EngineController::from(Engine::from(disabling_create_info));
+    assert_eq!(platform.start(&mut controller), EngineUpdateResult::Ok);
+    clock.advance(Duration::from_millis(100));
+    assert_eq!(platform.step(&mut controller, 1), EngineUpdateResult::Ok);
+    assert_eq!(updates.load(Ordering::Acquire), 0);
+}
+
+#[test]
+fn test_headless_restart_during_initialization() {
+    let events = Arc::new(Mutex::new(vec![]));
+    let updates = Arc::new(AtomicU64::new(0));
+    let mut create_info = create_info(
+        10,
+        vec![
+            LifecycleStage::constructor("first", EngineUpdateResult::Ok, &events),
+            failing_stage::<0>(true, &updates),
+        ],
+        vec![],
+        Some(Arc::new(ManualClock::new())),
+    );
+    create_info.stage_error_policies.default = StageErrorPolicy::Restart;
+    let mut controller = EngineController::from(Engine::from(create_info));
+    let mut platform = HeadlessPlatform::default();
+
+    // Restarting would fail the same way, so the restart stops the engine instead of looping.
+    match platform.start(&mut controller) {
+        EngineUpdateResult::Error(e) => {
+            assert_eq!(e.stage(), Some("physics"));
+            assert_eq!(e.phase(), Some(StagePhase::Initialize));
+        }
+        result => panic!("Expected an initialization error, got {:?}.", result),
+    }
+
+    // Stages initialized before the failing one are shut down.
+    let events = events.lock().unwrap();
+    let hooks = events
+        .iter()
+        .filter(|e| !e.ends_with("job"))
+        .collect::<Vec<_>>();
+    assert_eq!(hooks, vec!["first shutdown"]);
+}
+
+#[test]
+fn test_headless_platform_initialization_error() {
+    let events = Arc::new(Mutex::new(vec![]));
+    let create_info = {
+        let render_events = Arc::clone(&events);
+        create_info(
+            10,
+            vec![LifecycleStage::constructor(
+                "first",
+                EngineUpdateResult::Ok,
+                &events,
+            )],
+            vec![Box::new(move |_input| {
+                Box::from(RenderStageContainer::from(AlphaRecordingStage {
+                    events: Arc::clone(&render_events),
+                }))
+            })],
+            Some(Arc::new(ManualClock::new())),
+        )
+    };
+    let mut controller = EngineController::from(Engine::from(create_info));
+
+    match controller.initialize(&mut FailingPlatform::default()) {
+        EngineUpdateResult::Error(e) => {
+            assert_eq!(e.stage(), Some("Platform"));
+            assert_eq!(e.phase(), Some(StagePhase::PlatformInitialize));
+        }
+        result => panic!("Expected an initialization error, got {:?}.", result),
+    }
+    let mut is_uninitialized = false;
+    controller.as_uninitialized(|_| is_uninitialized = true);
+    assert!(is_uninitialized);
+
+    // No stage was initialized, so none of them is shut down.
+    assert!(events.lock().unwrap().is_empty());
+}
+
+#[test]
+fn test_headless_runtime_stages() {
+    let clock = Arc::new(ManualClock::new());
+    let create_info = create_info(10, vec![], vec![], Some(clock.clone()));
+    let mut controller = EngineController::from(Engine::from(create_info));
+    let mut platform = HeadlessPlatform::default();
+    platform.start(&mut controller);
+
+    let updates = Arc::new(AtomicU64::new(0));
+    let update_events = Arc::new(Mutex::new(vec![]));
+    let render_events = Arc::new(Mutex::new(vec![]));
+    let stage_queue = controller.stage_queue();
+    {
+        let updates = Arc::clone(&updates);
+        let events = Arc::clone(&update_events);
+        stage_queue.add_update_stage(move |_input| Box::from(RecordingStage { updates, events }));
+    }
+    {
+        let events = Arc::clone(&render_events);
+        stage_queue.add_render_stage(move |_input| {
+            Box::from(RenderStageContainer::from(AlphaRecordingStage { events }))
+        });
+    }
+
+    // Stages added between ticks run in the next tick and receive messages sent afterwards.
+    clock.advance(Duration::from_millis(100));
+    assert_eq!(platform.step(&mut controller, 1), EngineUpdateResult::Ok);
+    let window = platform.open_window(800, 600, "Runtime", None).unwrap();
+    clock.advance(Duration::from_millis(100));
+    assert_eq!(platform.step(&mut controller, 1), EngineUpdateResult::Ok);
+    assert_eq!(updates.load(Ordering::Acquire), 2);
+    assert_eq!(*update_events.lock().unwrap(), vec!["open"]);
+    assert_eq!(
+        *render_events.lock().unwrap(),
+        vec!["update", "render 0.00", "update", "render 0.00"]
+    );
+
+    // Removed stages are shut down and no longer receive messages.
+    stage_queue.remove_update_stage::<RecordingStage>();
+    stage_queue.remove_render_stage::<AlphaRecordingStage>();
+    clock.advance(Duration::from_millis(100));
+    assert_eq!(platform.step(&mut controller, 1), EngineUpdateResult::Ok);
+    platform.resize_window(window, 1024, 768);
+    clock.advance(Duration::from_millis(100));
+    assert_eq!(platform.step(&mut controller, 1), EngineUpdateResult::Ok);
+    assert_eq!(updates.load(Ordering::Acquire), 2);
+    assert_eq!(*update_events.lock().unwrap(), vec!["open"]);
+    assert_eq!(
+        render_events.lock().unwrap().last().map(|e| e.as_str()),
+        Some("render shutdown")
+    );
+    assert!(stage_queue.is_empty());
+}
+
+#[test]
+fn test_headless_message_replay() {
+    fn message_bus(controller: &EngineController) -> Arc<MessageBus> {
+        controller.shared().resources.resource::<MessageBus>()
+    }
+
+    let clock = Arc::new(ManualClock::new());
+    let recorded_events = Arc::new(Mutex::new(vec![]));
+    let mut controller = create_stage_controller(
+        &clock,
+        RecordingStage::constructor(&Default::default(), &recorded_events),
+        Default::default(),
+    );
+    let mut platform = HeadlessPlatform::default();
+    platform.start(&mut controller);
+    message_bus(&controller).tracer().start_recording();
+    let window = platform.open_window(800, 600, "Recorded", None).unwrap();
+    for size in [1024, 1280] {
+        clock.advance(Duration::from_millis(100));
+        assert_eq!(platform.step(&mut controller, 1), EngineUpdateResult::Ok);
+        assert!(platform.resize_window(window, size, 768));
+    }
+    // The window closes while its last resize is still being delivered.
+    assert!(platform.close_window(window));
+    // Input events are struct and newtype variants, which need to be traced as well.
+    platform.send_input(InputEvent::key(KeyCode::Space, ButtonState::Pressed));
+    platform.send_input(InputEvent::CursorMoved { x: 10.0, y: 20.0 });
+    platform.send_input(InputEvent::Text('j'));
+    clock.advance(Duration::from_millis(100));
+    assert_eq!(platform.step(&mut controller, 1), EngineUpdateResult::Ok);
+    let trace = message_bus(&controller).tracer().stop_recording();
+    platform.shutdown(&mut controller);
+
+    let clock = Arc::new(ManualClock::new());
+    let replayed_events = Arc::new(Mutex::new(vec![]));
+    let mut controller = create_stage_controller(
+        &clock,
+        RecordingStage::constructor(&Default::default(), &replayed_events),
+        Default::default(),
+    );
+    let mut platform = HeadlessPlatform::default();
+    platform.start(&mut controller);
+    message_bus(&controller).tracer().replay(trace);
+    for _ in 0..3 {
+        clock.advance(Duration::from_millis(100));
+        assert_eq!(platform.step(&mut controller, 1), EngineUpdateResult::Ok);
+    }
+    platform.shutdown(&mut controller);
+
+    assert_eq!(
+        *recorded_events.lock().unwrap(),
+        vec![
+            String::from("open"),
+            String::from("resize 1024x768"),
+            String::from("resize 1280x768"),
+            String::from("close"),
+            format!(
+                "{:?}",
+                InputEvent::key(KeyCode::Space, ButtonState::Pressed)
+            ),
+            format!("{:?}", InputEvent::CursorMoved { x: 10.0, y: 20.0 }),
+            format!("{:?}", InputEvent::Text('j')),
+        ]
+    );
+    assert_eq!(
+        *replayed_events.lock().unwrap(),
+        *recorded_events.lock().unwrap()
+    );
+}
+
+#[test]
+fn test_headless_input() {
+    let clock = Arc::new(ManualClock::new());
+    let events = Arc::new(Mutex::new(vec![]));
+    let create_info = {
+        let events = Arc::clone(&events);
+        create_info(
+            10,
+            vec![Box::new(move |_input| {
+                Box::from(InputStage {
+                    events: Arc::clone(&events),
+                })
+            })],
+            vec![],
+            Some(clock.clone()),
+        )
+    };
+    let mut controller = EngineController::from(Engine::from(create_info));
+    let mut platform = HeadlessPlatform::default();
+    platform.start(&mut controller);
+    controller
+        .shared()
+        .resources
+        .resource::<InputManager>()
+        .bind_action("jump", vec![InputBinding::Key(KeyCode::Space)]);
+
+    let inputs = [
+        vec![
+            InputEvent::Key {
+                key: KeyCode::Space,
+                state: ButtonState::Pressed,
+            },
+            InputEvent::Text('j'),
+        ],
+        vec![],
+        vec![InputEvent::Key {
+            key: KeyCode::Space,
+            state: ButtonState::Released,
+        }],
+    ];
+    for tick_inputs in inputs {
+        tick_inputs.into_iter().for_each(|e| platform.send_input(e));
+        clock.advance(Duration::from_millis(100));
+        assert_eq!(platform.step(&mut controller, 1), EngineUpdateResult::Ok);
+    }
+    platform.shutdown(&mut controller);
+
+    assert_eq!(
+        *events.lock().unwrap(),
+        vec!["jump pressed", "text j", "jump released"]
+    );
+}
+
+#[test]
+fn test_headless_input_replay() {
+    let clock = Arc::new(ManualClock::new());
+    let recorded_positions = Arc::new(Mutex::new(vec![]));
+    let mut controller = create_stage_controller(
+        &clock,
+        PlayerStage::constructor(&recorded_positions),
+        Default::default(),
+    );
+    let mut platform = HeadlessPlatform::default();
+    platform.start(&mut controller);
+    let input_manager = controller.shared().resources.resource::<InputManager>();
+    input_manager.start_recording();
+    let inputs = [
+        vec![InputEvent::key(KeyCode::D, ButtonState::Pressed)],
+        vec![],
+        vec![InputEvent::key(KeyCode::A, ButtonState::Pressed)],
+        vec![InputEvent::key(KeyCode::D, ButtonState::Released)],
+        vec![],
+        vec![InputEvent::key(KeyCode::A, ButtonState::Released)],
+    ];
+    for tick_inputs in inputs {
+        tick_inputs.into_iter().for_each(|e| platform.send_input(e));
+        clock.advance(Duration::from_millis(100));
+        assert_eq!(platform.step(&mut controller, 1), EngineUpdateResult::Ok);
+    }
+    let recording = input_manager.stop_recording().unwrap();
+    platform.shutdown(&mut controller);
+    assert_eq!(
+        *recorded_positions.lock().unwrap(),
+        vec!["0", "1", "1", "0", "-1", "-1"]
+    );
+
+    let path = std::env::temp_dir().join(format!("headless_input_{}.rec", std::process::id()));
+    recording.save(&path).unwrap();
+    let recording = InputRecording::load(&path).unwrap();
+    let _ = std::fs::remove_file(&path);
+
+    let clock = Arc::new(ManualClock::new());
+    let replayed_positions = Arc::new(Mutex::new(vec![]));
+    let mut controller = create_stage_controller(
+        &clock,
+        PlayerStage::constructor(&replayed_positions),
+        Default::default(),
+    );
+    let mut platform = HeadlessPlatform::default().with_input_replay(recording);
+    platform.start(&mut controller);
+    while platform.is_replaying_input() {
+        // Live input does not interfere with the replay.
+        platform.send_input(InputEvent::key(KeyCode::A, ButtonState::Pressed));
+        clock.advance(Duration::from_millis(100));
+        assert_eq!(platform.step(&mut controller, 1), EngineUpdateResult::Ok);
+    }
+    platform.shutdown(&mut controller);
+
+    assert_eq!(
+        *replayed_positions.lock().unwrap(),
+        *recorded_positions.lock().unwrap()
+    );
+}
+
+#[test]
+fn test_headless_engine_commands() {
+    fn press(platform: &HeadlessPlatform, key: KeyCode) {
+        platform.send_input(InputEvent::Key {
+            key,
+            state: ButtonState::Pressed,
+        });
+    }
+
+    let clock = Arc::new(ManualClock::new());
+    let updates = Arc::new(AtomicU64::new(0));
+    let settings = EngineCommandSettings {
+        enabled: true,
+        bindings: Default::default(),
+    };
+    let mut controller = create_stage_controller(
+        &clock,
+        RecordingStage::constructor(&updates, &Default::default()),
+        settings,
+    );
+    let mut platform = HeadlessPlatform::default();
+    platform.start(&mut controller);
+    let commands = controller
+        .shared()
+        .resources
+        .resource::<MessageBus>()
+        .add_handler::<EngineCommand>(MessageHandlerType::Update);
+    let step = |platform: &mut HeadlessPlatform, controller: &mut EngineController| {
+        clock.advance(Duration::from_millis(100));
+        platform.step(controller, 1)
+    };
+
+    // Key repeats do not toggle the pause again.
+    press(&platform, KeyCode::F9);
+    press(&platform, KeyCode::F9);
+    assert_eq!(step(&mut platform, &mut controller), EngineUpdateResult::Ok);
+    assert_eq!(step(&mut platform, &mut controller), EngineUpdateResult::Ok);
+    assert_eq!(updates.load(Ordering::Acquire), 0);
+
+    platform.send_input(InputEvent::Key {
+        key: KeyCode::F9,
+        state: ButtonState::Released,
+    });
+    press(&platform, KeyCode::F9);
+    press(&platform, KeyCode::F12);
+    assert_eq!(step(&mut platform, &mut controller), EngineUpdateResult::Ok);
+    assert_eq!(updates.load(Ordering::Acquire), 1);
+    assert_eq!(
+        commands.try_iter().collect::<Vec<_>>(),
+        vec![
+            EngineCommand::TogglePause,
+            EngineCommand::TogglePause,
+            EngineCommand::Screenshot
+        ]
+    );
+
+    press(&platform, KeyCode::F10);
+    assert_eq!(
+        step(&mut platform, &mut controller),
+        EngineUpdateResult::Stop
+    );
+
+    // Disabled key bindings still execute commands sent through the message bus.
+    let settings = EngineCommandSettings {
+        enabled: false,
+        bindings: Default::default(),
+    };
+    let mut controller = create_stage_controller(
+        &clock,
+        RecordingStage::constructor(&updates, &Default::default()),
+        settings,
+    );
+    let mut platform = HeadlessPlatform::default();
+    platform.start(&mut controller);
+    press(&platform, KeyCode::F10);
+    assert_eq!(step(&mut platform, &mut controller), EngineUpdateResult::Ok);
+    controller
+        .shared()
+        .resources
+        .resource::<MessageBus>()
+        .get_sender::<EngineCommand>()
+        .send(EngineCommand::Quit);
+    assert_eq!(
+        step(&mut platform, &mut controller),
+        EngineUpdateResult::Stop
+    );
+}
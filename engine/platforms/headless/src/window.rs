@@ -0,0 +1,47 @@
+use engine::platform::*;
+
+/// A virtual window without any native surface backing it.
+/// Its raw window handle is an empty web handle, so render stages can not create surfaces for it.
+#[derive(Debug)]
+pub struct HeadlessPlatformWindow {
+    pub(crate) handle: PlatformWindowHandle,
+    pub(crate) tag: Option<String>,
+    pub(crate) title: String,
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+}
+
+unsafe impl HasRawWindowHandle for HeadlessPlatformWindow {
+    fn raw_window_handle(&self) -> RawWindowHandle {
+        RawWindowHandle::Web(WebHandle::empty())
+    }
+}
+
+impl HeadlessPlatformWindow {
+    /// Get a reference to the headless platform window's title.
+    pub fn title(&self) -> &str {
+        self.title.as_str()
+    }
+}
+
+impl PlatformWindow for HeadlessPlatformWindow {
+    fn tag(&self) -> Option<&str> {
+        self.tag.as_deref()
+    }
+
+    fn width(&self) -> u32 {
+        self.width
+    }
+
+    fn height(&self) -> u32 {
+        self.height
+    }
+
+    fn handle(&self) -> PlatformWindowHandle {
+        self.handle
+    }
+
+    fn pixels_per_point(&self) -> f32 {
+        1.0
+    }
+}
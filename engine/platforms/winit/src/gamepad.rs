@@ -0,0 +1,284 @@
+use crate::plugin::WinitPlatformPlugin;
+use crate::WinitPlatformInterface;
+use engine::engine_stages::PlatformInitInput;
+use engine::input::{
+    ButtonState, GamepadAxis, GamepadButton, GamepadId, InputEvent, RumbleRequest,
+};
+use engine::*;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Mutex};
+use utils::*;
+use winit::event::Event;
+use winit::event_loop::EventLoop;
+
+/// Raw gamepad state change reported by a [`GamepadBackend`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum GamepadBackendEvent {
+    Connected(GamepadId),
+    Disconnected(GamepadId),
+    Button(GamepadId, GamepadButton, ButtonState),
+    /// Raw axis value, before the dead zone is applied.
+    Axis(GamepadId, GamepadAxis, f32),
+}
+
+/// Source of gamepad events, e.g. the `GilrsGamepadBackend` of the `gilrs` feature or the [`VirtualGamepadBackend`].
+pub trait GamepadBackend: 'static {
+    /// Returns the state changes since the last poll, in the order they occurred.
+    fn poll(&mut self) -> Vec<GamepadBackendEvent>;
+    /// Starts vibrating the gamepad. Gamepads without rumble support ignore the request.
+    fn rumble(&mut self, request: &RumbleRequest);
+}
+
+#[derive(Debug, Default)]
+struct VirtualGamepads {
+    events: VecDeque<GamepadBackendEvent>,
+    rumble_requests: Vec<RumbleRequest>,
+}
+
+/// Gamepad backend driven from code, to test the gamepad handling without hardware.
+/// Clones share the same virtual gamepads.
+#[derive(Debug, Clone, Default)]
+pub struct VirtualGamepadBackend {
+    gamepads: Arc<Mutex<VirtualGamepads>>,
+}
+
+impl VirtualGamepadBackend {
+    pub fn connect(&self, gamepad: GamepadId) {
+        self.push(GamepadBackendEvent::Connected(gamepad));
+    }
+
+    pub fn disconnect(&self, gamepad: GamepadId) {
+        self.push(GamepadBackendEvent::Disconnected(gamepad));
+    }
+
+    pub fn press(&self, gamepad: GamepadId, button: GamepadButton) {
+        self.push(GamepadBackendEvent::Button(
+            gamepad,
+            button,
+            ButtonState::Pressed,
+        ));
+    }
+
+    pub fn release(&self, gamepad: GamepadId, button: GamepadButton) {
+        self.push(GamepadBackendEvent::Button(
+            gamepad,
+            button,
+            ButtonState::Released,
+        ));
+    }
+
+    pub fn move_axis(&self, gamepad: GamepadId, axis: GamepadAxis, value: f32) {
+        self.push(GamepadBackendEvent::Axis(gamepad, axis, value));
+    }
+
+    /// Returns the rumble requests received since the last call.
+    pub fn take_rumble_requests(&self) -> Vec<RumbleRequest> {
+        std::mem::take(&mut self.gamepads.lock().unwrap().rumble_requests)
+    }
+
+    fn push(&self, event: GamepadBackendEvent) {
+        self.gamepads.lock().unwrap().events.push_back(event);
+    }
+}
+
+impl GamepadBackend for VirtualGamepadBackend {
+    fn poll(&mut self) -> Vec<GamepadBackendEvent> {
+        self.gamepads.lock().unwrap().events.drain(..).collect()
+    }
+
+    fn rumble(&mut self, request: &RumbleRequest) {
+        self.gamepads
+            .lock()
+            .unwrap()
+            .rumble_requests
+            .push(request.clone());
+    }
+}
+
+/// Settings of the [`GamepadPlugin`].
+#[derive(Debug, Clone)]
+pub struct GamepadSettings {
+    /// Stick deflections below the dead zone are reported as 0, larger ones are rescaled to start at 0.
+    pub stick_dead_zone: f32,
+    pub trigger_dead_zone: f32,
+}
+
+impl Default for GamepadSettings {
+    fn default() -> Self {
+        Self {
+            stick_dead_zone: 0.15,
+            trigger_dead_zone: 0.05,
+        }
+    }
+}
+
+impl GamepadSettings {
+    fn apply_dead_zone(&self, axis: GamepadAxis, value: f32) -> f32 {
+        let dead_zone = match axis {
+            GamepadAxis::LeftTrigger | GamepadAxis::RightTrigger => self.trigger_dead_zone,
+            _ => self.stick_dead_zone,
+        };
+        let value = value.clamp(-1.0, 1.0);
+        match value.abs() <= dead_zone {
+            true => 0.0,
+            false => value.signum() * (value.abs() - dead_zone) / (1.0 - dead_zone),
+        }
+    }
+}
+
+/// Sends the state changes of a [`GamepadBackend`] as [`InputEvent`]s and forwards [`RumbleRequest`]s to it.
+/// The backend is polled once per iteration of the event loop, before the engine is ticked.
+pub struct GamepadPlugin {
+    backend: Box<dyn GamepadBackend>,
+    settings: GamepadSettings,
+    input_sender: Option<MessageSender<InputEvent>>,
+    rumble_receiver: Option<HandlerReceiver<RumbleRequest>>,
+    /// Connected gamepads, announced again to a restarted engine.
+    gamepads: BTreeMap<GamepadId, ConnectedGamepad>,
+}
+
+#[derive(Debug, Default)]
+struct ConnectedGamepad {
+    buttons: HashSet<GamepadButton>,
+    /// Last values sent, so that movement within the dead zone is not reported repeatedly.
+    axes: HashMap<GamepadAxis, f32>,
+}
+
+impl ConnectedGamepad {
+    fn input_events(&self, gamepad: GamepadId) -> Vec<InputEvent> {
+        let buttons = self.buttons.iter().map(|button| InputEvent::GamepadButton {
+            gamepad,
+            button: *button,
+            state: ButtonState::Pressed,
+        });
+        let axes = self
+            .axes
+            .iter()
+            .filter(|(_, value)| **value != 0.0)
+            .map(|(axis, value)| InputEvent::GamepadAxis {
+                gamepad,
+                axis: *axis,
+                value: *value,
+            });
+        std::iter::once(InputEvent::GamepadConnected(gamepad))
+            .chain(buttons)
+            .chain(axes)
+            .collect()
+    }
+}
+
+impl GamepadPlugin {
+    pub fn new(backend: impl GamepadBackend, settings: GamepadSettings) -> Self {
+        Self {
+            backend: Box::new(backend),
+            settings,
+            input_sender: None,
+            rumble_receiver: None,
+            gamepads: Default::default(),
+        }
+    }
+
+    /// Connects the plugin to the message bus of a newly initialized engine, announcing the connected gamepads.
+    pub fn connect(&mut self, message_bus: &MessageBus) {
+        let sender = message_bus.get_sender();
+        for (gamepad, state) in &self.gamepads {
+            state
+                .input_events(*gamepad)
+                .into_iter()
+                .for_each(|e| sender.send(e));
+        }
+        self.input_sender = Some(sender);
+        self.rumble_receiver = Some(message_bus.add_handler(MessageHandlerType::Render));
+    }
+
+    /// Sends the input events of the gamepad changes since the last poll and executes pending rumble requests.
+    pub fn poll(&mut self) {
+        for event in self.backend.poll() {
+            let Some(event) = self.input_event(event) else {
+                continue;
+            };
+            if let Some(sender) = &self.input_sender {
+                sender.send(event);
+            }
+        }
+        if let Some(receiver) = &self.rumble_receiver {
+            for request in receiver.try_iter() {
+                self.backend.rumble(&request);
+            }
+        }
+    }
+
+    fn input_event(&mut self, event: GamepadBackendEvent) -> Option<InputEvent> {
+        match event {
+            GamepadBackendEvent::Connected(gamepad) => {
+                t_info!("Gamepad {} connected.", gamepad.0);
+                self.gamepads.insert(gamepad, Default::default());
+                Some(InputEvent::GamepadConnected(gamepad))
+            }
+            GamepadBackendEvent::Disconnected(gamepad) => {
+                t_info!("Gamepad {} disconnected.", gamepad.0);
+                self.gamepads.remove(&gamepad)?;
+                Some(InputEvent::GamepadDisconnected(gamepad))
+            }
+            GamepadBackendEvent::Button(gamepad, button, state) => {
+                let buttons = &mut self.gamepads.get_mut(&gamepad)?.buttons;
+                match state {
+                    ButtonState::Pressed => buttons.insert(button),
+                    ButtonState::Released => buttons.remove(&button),
+                };
+                Some(InputEvent::GamepadButton {
+                    gamepad,
+                    button,
+                    state,
+                })
+            }
+            GamepadBackendEvent::Axis(gamepad, axis, value) => {
+                let value = self.settings.apply_dead_zone(axis, value);
+                let axes = &mut self.gamepads.get_mut(&gamepad)?.axes;
+                let previous = axes.insert(axis, value).unwrap_or(0.0);
+                (previous != value).then_some(InputEvent::GamepadAxis {
+                    gamepad,
+                    axis,
+                    value,
+                })
+            }
+        }
+    }
+}
+
+impl WinitPlatformPlugin for GamepadPlugin {
+    fn pre_run(&mut self, _event_loop: EventLoop<()>) {}
+
+    fn systems_will_init(
+        &mut self,
+        _platform_interface: &mut WinitPlatformInterface,
+        input: &mut PlatformInitInput,
+    ) -> EngineUpdateResult {
+        let message_bus = input
+            .resources
+            .get_resource::<MessageBus>()
+            .expect("Requires a message bus!");
+        self.connect(&message_bus);
+        EngineUpdateResult::Ok
+    }
+
+    fn systems_did_init(
+        &mut self,
+        _platform_interface: &mut WinitPlatformInterface,
+        _input: &mut PlatformInitInput,
+    ) -> EngineUpdateResult {
+        EngineUpdateResult::Ok
+    }
+
+    fn process_event(&mut self, event: &Event<()>) -> bool {
+        if let Event::MainEventsCleared = event {
+            self.poll();
+        }
+        false
+    }
+
+    fn engine_will_shutdown(&mut self, _platform_interface: &mut WinitPlatformInterface) {
+        self.input_sender = None;
+        self.rumble_receiver = None;
+    }
+}
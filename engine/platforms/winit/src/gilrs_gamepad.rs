@@ -0,0 +1,159 @@
+use crate::{GamepadBackend, GamepadBackendEvent};
+use engine::input::{ButtonState, GamepadAxis, GamepadButton, GamepadId, RumbleRequest};
+use gilrs::ff::{BaseEffect, BaseEffectType, Effect, EffectBuilder, Replay, Ticks};
+use gilrs::{Axis, Button, EventType, Gilrs};
+use std::collections::HashMap;
+use utils::*;
+
+/// Gamepad backend reading the gamepads connected to the system through gilrs.
+pub struct GilrsGamepadBackend {
+    gilrs: Gilrs,
+    /// Gamepads connected before the backend was created, which gilrs does not report as connected.
+    connected: Vec<GamepadId>,
+    /// Playing rumble effects, which stop when dropped. A new request replaces the previous effect.
+    rumble_effects: HashMap<GamepadId, Effect>,
+}
+
+impl GilrsGamepadBackend {
+    /// Falls back to a backend without gamepads if gilrs does not support the platform.
+    pub fn new() -> Result<Self, Box<gilrs::Error>> {
+        let gilrs = match Gilrs::new() {
+            Ok(v) => v,
+            Err(gilrs::Error::NotImplemented(dummy)) => {
+                t_warn!("Gamepads are not supported on this platform.");
+                dummy
+            }
+            Err(e) => return Err(Box::new(e)),
+        };
+        Ok(Self {
+            connected: gilrs.gamepads().map(|(id, _)| gamepad_id(id)).collect(),
+            gilrs,
+            rumble_effects: Default::default(),
+        })
+    }
+
+    fn backend_event(
+        &mut self,
+        gamepad: GamepadId,
+        event: EventType,
+    ) -> Option<GamepadBackendEvent> {
+        match event {
+            EventType::Connected => Some(GamepadBackendEvent::Connected(gamepad)),
+            EventType::Disconnected => {
+                self.rumble_effects.remove(&gamepad);
+                Some(GamepadBackendEvent::Disconnected(gamepad))
+            }
+            EventType::ButtonPressed(button, _) => gamepad_button(button)
+                .map(|button| GamepadBackendEvent::Button(gamepad, button, ButtonState::Pressed)),
+            EventType::ButtonReleased(button, _) => gamepad_button(button)
+                .map(|button| GamepadBackendEvent::Button(gamepad, button, ButtonState::Released)),
+            // Analog triggers are reported as buttons with a value.
+            EventType::ButtonChanged(button, value, _) => {
+                trigger_axis(button).map(|axis| GamepadBackendEvent::Axis(gamepad, axis, value))
+            }
+            EventType::AxisChanged(axis, value, _) => {
+                gamepad_axis(axis).map(|axis| GamepadBackendEvent::Axis(gamepad, axis, value))
+            }
+            EventType::ButtonRepeated(..) | EventType::Dropped => None,
+        }
+    }
+}
+
+impl GamepadBackend for GilrsGamepadBackend {
+    fn poll(&mut self) -> Vec<GamepadBackendEvent> {
+        let mut events: Vec<_> = self
+            .connected
+            .drain(..)
+            .map(GamepadBackendEvent::Connected)
+            .collect();
+        while let Some(gilrs::Event { id, event, .. }) = self.gilrs.next_event() {
+            events.extend(self.backend_event(gamepad_id(id), event));
+        }
+        events
+    }
+
+    fn rumble(&mut self, request: &RumbleRequest) {
+        let Some((id, _)) = self
+            .gilrs
+            .gamepads()
+            .find(|(id, gamepad)| gamepad_id(*id) == request.gamepad && gamepad.is_ff_supported())
+        else {
+            return;
+        };
+        let scheduling = Replay {
+            play_for: Ticks::from_ms(request.duration.as_millis().min(u32::MAX as u128) as u32),
+            ..Default::default()
+        };
+        let effect = |kind| BaseEffect {
+            kind,
+            scheduling,
+            envelope: Default::default(),
+        };
+        let effect = EffectBuilder::new()
+            .add_effect(effect(BaseEffectType::Strong {
+                magnitude: magnitude(request.strong_magnitude),
+            }))
+            .add_effect(effect(BaseEffectType::Weak {
+                magnitude: magnitude(request.weak_magnitude),
+            }))
+            .gamepads(&[id])
+            .finish(&mut self.gilrs)
+            .and_then(|effect| effect.play().map(|_| effect));
+        match effect {
+            Ok(effect) => {
+                self.rumble_effects.insert(request.gamepad, effect);
+            }
+            Err(e) => t_warn!("Could not rumble gamepad {}: {}", request.gamepad.0, e),
+        }
+    }
+}
+
+fn gamepad_id(id: gilrs::GamepadId) -> GamepadId {
+    GamepadId(usize::from(id) as u32)
+}
+
+fn magnitude(value: f32) -> u16 {
+    (value.clamp(0.0, 1.0) * u16::MAX as f32) as u16
+}
+
+/// Gilrs names the bumpers triggers and the analog triggers second triggers.
+fn gamepad_button(button: Button) -> Option<GamepadButton> {
+    Some(match button {
+        Button::South => GamepadButton::South,
+        Button::East => GamepadButton::East,
+        Button::North => GamepadButton::North,
+        Button::West => GamepadButton::West,
+        Button::LeftTrigger => GamepadButton::LeftBumper,
+        Button::RightTrigger => GamepadButton::RightBumper,
+        Button::LeftTrigger2 => GamepadButton::LeftTrigger,
+        Button::RightTrigger2 => GamepadButton::RightTrigger,
+        Button::Select => GamepadButton::Select,
+        Button::Start => GamepadButton::Start,
+        Button::Mode => GamepadButton::Mode,
+        Button::LeftThumb => GamepadButton::LeftStick,
+        Button::RightThumb => GamepadButton::RightStick,
+        Button::DPadUp => GamepadButton::DPadUp,
+        Button::DPadDown => GamepadButton::DPadDown,
+        Button::DPadLeft => GamepadButton::DPadLeft,
+        Button::DPadRight => GamepadButton::DPadRight,
+        Button::C | Button::Z | Button::Unknown => return None,
+    })
+}
+
+fn trigger_axis(button: Button) -> Option<GamepadAxis> {
+    match button {
+        Button::LeftTrigger2 => Some(GamepadAxis::LeftTrigger),
+        Button::RightTrigger2 => Some(GamepadAxis::RightTrigger),
+        _ => None,
+    }
+}
+
+fn gamepad_axis(axis: Axis) -> Option<GamepadAxis> {
+    match axis {
+        Axis::LeftStickX => Some(GamepadAxis::LeftStickX),
+        Axis::LeftStickY => Some(GamepadAxis::LeftStickY),
+        Axis::RightStickX => Some(GamepadAxis::RightStickX),
+        Axis::RightStickY => Some(GamepadAxis::RightStickY),
+        _ => None,
+    }
+}
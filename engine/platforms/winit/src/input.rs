@@ -0,0 +1,74 @@
+use engine::input::{ButtonState, InputEvent, KeyCode, MouseButton};
+use winit::event::{ElementState, MouseScrollDelta, VirtualKeyCode, WindowEvent};
+
+/// Pixel distance treated as one line when scrolling with touchpads.
+const PIXELS_PER_SCROLL_LINE: f32 = 20.0;
+
+/// Converts the input events of a window into engine [`InputEvent`]s.
+pub(crate) fn input_event(event: &WindowEvent) -> Option<InputEvent> {
+    match event {
+        WindowEvent::KeyboardInput { input, .. } => Some(InputEvent::Key {
+            key: key_code(input.virtual_keycode?)?,
+            state: button_state(input.state),
+        }),
+        WindowEvent::MouseInput { state, button, .. } => Some(InputEvent::MouseButton {
+            button: mouse_button(*button),
+            state: button_state(*state),
+        }),
+        WindowEvent::CursorMoved { position, .. } => Some(InputEvent::CursorMoved {
+            x: position.x as f32,
+            y: position.y as f32,
+        }),
+        WindowEvent::MouseWheel { delta, .. } => Some(match delta {
+            MouseScrollDelta::LineDelta(x, y) => InputEvent::Scrolled {
+                delta_x: *x,
+                delta_y: *y,
+            },
+            MouseScrollDelta::PixelDelta(position) => InputEvent::Scrolled {
+                delta_x: position.x as f32 / PIXELS_PER_SCROLL_LINE,
+                delta_y: position.y as f32 / PIXELS_PER_SCROLL_LINE,
+            },
+        }),
+        WindowEvent::ReceivedCharacter(character) if !character.is_control() => {
+            Some(InputEvent::Text(*character))
+        }
+        _ => None,
+    }
+}
+
+fn button_state(state: ElementState) -> ButtonState {
+    match state {
+        ElementState::Pressed => ButtonState::Pressed,
+        ElementState::Released => ButtonState::Released,
+    }
+}
+
+fn mouse_button(button: winit::event::MouseButton) -> MouseButton {
+    match button {
+        winit::event::MouseButton::Left => MouseButton::Left,
+        winit::event::MouseButton::Right => MouseButton::Right,
+        winit::event::MouseButton::Middle => MouseButton::Middle,
+        winit::event::MouseButton::Other(id) => MouseButton::Other(id),
+    }
+}
+
+/// Returns None for keys without an engine counterpart.
+pub(crate) fn key_code(key: VirtualKeyCode) -> Option<KeyCode> {
+    macro_rules! same_keys {
+        ($($key:ident),*) => {
+            match key {
+                $(VirtualKeyCode::$key => Some(KeyCode::$key),)*
+                VirtualKeyCode::Return => Some(KeyCode::Enter),
+                VirtualKeyCode::Back => Some(KeyCode::Backspace),
+                _ => None,
+            }
+        };
+    }
+    same_keys!(
+        A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z, Key0, Key1,
+        Key2, Key3, Key4, Key5, Key6, Key7, Key8, Key9, F1, F2, F3, F4, F5, F6, F7, F8, F9, F10,
+        F11, F12, Escape, Space, Tab, Delete, Insert, Home, End, PageUp, PageDown, Left, Right, Up,
+        Down, LShift, RShift, LControl, RControl, LAlt, RAlt, Minus, Equals, Comma, Period, Slash,
+        Grave, Snapshot
+    )
+}
@@ -1,4 +1,5 @@
 use crate::*;
+use engine::input::InputEvent;
 use engine::*;
 use utils::{as_any::*, *};
 use winit::{dpi::PhysicalSize, event_loop::EventLoopWindowTarget, window::WindowBuilder};
@@ -21,6 +22,22 @@ impl<'a> WinitPlatformInterface<'a> {
     pub fn clear_windows(&mut self) {
         self.platform.windows.clear();
     }
+
+    /// Shuts down the engine, then all plugins in reverse order of addition, and closes all windows.
+    pub fn shutdown(&mut self, controller: &mut EngineController) {
+        if controller.shutdown(self) {
+            self.shutdown_plugins();
+        }
+        self.clear_windows();
+    }
+
+    fn shutdown_plugins(&mut self) {
+        let mut plugins = self.platform.plugins.drain(..).collect::<Vec<_>>();
+        for plugin in plugins.iter_mut().rev() {
+            plugin.engine_will_shutdown(self);
+        }
+        self.platform.plugins = plugins;
+    }
 }
 
 impl PlatformInitalizationHandler for WinitPlatformInterface<'_> {
@@ -41,9 +58,10 @@ impl PlatformInitalizationHandler for WinitPlatformInterface<'_> {
             .get_resource::<MessageBus>()
             .expect("Requires a message bus!");
 
-        self.platform.window_did_resize_sender = message_bus.get_sender::<WindowDidResize>();
-        self.platform.window_did_close_sender = message_bus.get_sender::<WindowWillClose>();
-        self.window_open_sender = message_bus.get_sender::<WindowDidOpen>();
+        self.platform.window_did_resize_sender = Some(message_bus.get_sender::<WindowDidResize>());
+        self.platform.window_did_close_sender = Some(message_bus.get_sender::<WindowWillClose>());
+        self.platform.input_sender = Some(message_bus.get_sender::<InputEvent>());
+        self.window_open_sender = Some(message_bus.get_sender::<WindowDidOpen>());
         self.platform.plugins = plugins.drain(..).collect();
         EngineUpdateResult::Ok
     }
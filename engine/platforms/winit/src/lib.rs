@@ -1,11 +1,21 @@
+pub mod gamepad;
+#[cfg(feature = "gilrs")]
+mod gilrs_gamepad;
+mod input;
 pub mod interface;
 pub mod platform;
 pub mod plugin;
 pub mod window;
 
+pub use gamepad::*;
+#[cfg(feature = "gilrs")]
+pub use gilrs_gamepad::*;
 pub use interface::*;
 pub use platform::*;
 pub use window::*;
 
+#[cfg(test)]
+mod tests;
+
 #[allow(dead_code)]
 pub(crate) const IDENTIFIER: &'static str = "Winit Platform";
@@ -1,12 +1,14 @@
 use crate::plugin::*;
 use crate::*;
+use engine::input::InputEvent;
 use engine::*;
 use std::any::TypeId;
 use utils::defer_drop::{DeferDrop, WeakDeferDrop};
 use utils::*;
+use winit::platform::run_return::EventLoopExtRunReturn;
 use winit::window::WindowId;
 use winit::{
-    event::{ElementState, Event, KeyboardInput, VirtualKeyCode, WindowEvent},
+    event::{Event, WindowEvent},
     event_loop::{ControlFlow, EventLoop},
 };
 
@@ -16,6 +18,7 @@ pub struct WinitPlatform {
     pub(crate) windows: Vec<WinitPlatformWindow>,
     pub(crate) window_did_resize_sender: Option<MessageSender<WindowDidResize>>,
     pub(crate) window_did_close_sender: Option<MessageSender<WindowWillClose>>,
+    pub(crate) input_sender: Option<MessageSender<InputEvent>>,
     pub(crate) plugins: Vec<Box<dyn AnyWinitPlatformPlugin>>,
     pub(crate) windows_which_close: Vec<WeakDeferDrop>,
 }
@@ -27,6 +30,7 @@ impl Default for WinitPlatform {
             window_did_resize_sender: None,
             window_id_counter: 0,
             window_did_close_sender: None,
+            input_sender: None,
             plugins: vec![],
             windows_which_close: vec![],
         }
@@ -95,18 +99,73 @@ fn find_window(
     }
 }
 
+/// Initializes the engine and runs it if initialization succeeded.
+fn start(
+    interface: &mut WinitPlatformInterface,
+    controller: &mut EngineController,
+) -> EngineUpdateResult {
+    let result = controller.initialize(interface);
+    if result.is_ok() {
+        controller.run();
+    }
+    result
+}
+
+/// Stops or restarts the engine if requested by the result of an engine call.
+/// Errors stop the engine and are stored in `error`, to be returned from [`Platform::run`].
+fn handle_update_result(
+    result: EngineUpdateResult,
+    interface: &mut WinitPlatformInterface,
+    controller: &mut EngineController,
+    control_flow: &mut ControlFlow,
+    error: &mut Option<StageError>,
+) {
+    match result {
+        EngineUpdateResult::Ok => (),
+        EngineUpdateResult::Stop => {
+            interface.shutdown(controller);
+            *control_flow = ControlFlow::Exit;
+        }
+        EngineUpdateResult::Restart => {
+            interface.shutdown(controller);
+            controller.reset();
+            let result = start(interface, controller);
+            handle_update_result(result, interface, controller, control_flow, error);
+        }
+        EngineUpdateResult::Error(e) => {
+            interface.shutdown(controller);
+            *error = Some(e);
+            *control_flow = ControlFlow::Exit;
+        }
+    }
+}
+
 impl Platform for WinitPlatform {
-    fn run(mut self, controller: EngineController) {
+    fn run(mut self, controller: EngineController) -> Result<(), StageError> {
         let mut controller = controller;
-        let event_loop = EventLoop::new();
-        controller.initialize(&mut WinitPlatformInterface::new(&mut self, &event_loop));
-        controller.run();
+        let mut event_loop = EventLoop::new();
+        let mut error = None;
+
+        let mut control_flow = ControlFlow::Poll;
+        let mut interface = WinitPlatformInterface::new(&mut self, &event_loop);
+        let result = start(&mut interface, &mut controller);
+        handle_update_result(
+            result,
+            &mut interface,
+            &mut controller,
+            &mut control_flow,
+            &mut error,
+        );
+        if control_flow == ControlFlow::Exit {
+            return error.map_or(Ok(()), Err);
+        }
 
-        event_loop.run(move |event, window_target, control_flow| {
+        event_loop.run_return(|event, window_target, control_flow| {
             *control_flow = ControlFlow::Poll;
 
             self.windows_which_close.retain(|e| !e.is_dropped());
             if self.windows_which_close.is_empty() && self.windows.is_empty() {
+                WinitPlatformInterface::new(&mut self, &window_target).shutdown(&mut controller);
                 *control_flow = ControlFlow::Exit;
                 return;
             }
@@ -121,14 +180,36 @@ impl Platform for WinitPlatform {
                 return;
             }
 
+            if let (Event::WindowEvent { event, .. }, Some(sender)) = (&event, &self.input_sender) {
+                if let Some(input_event) = crate::input::input_event(event) {
+                    sender.send(input_event);
+                }
+            }
+
             match event {
                 Event::Suspended => {
                     t_info!("Suspending game engine...");
-                    controller.suspend();
+                    let mut interface = WinitPlatformInterface::new(&mut self, &window_target);
+                    let result = controller.suspend(&mut interface);
+                    handle_update_result(
+                        result,
+                        &mut interface,
+                        &mut controller,
+                        control_flow,
+                        &mut error,
+                    );
                 }
                 Event::Resumed => {
                     t_info!("Resuming game engine...");
-                    controller.resume();
+                    let mut interface = WinitPlatformInterface::new(&mut self, &window_target);
+                    let result = controller.resume(&mut interface);
+                    handle_update_result(
+                        result,
+                        &mut interface,
+                        &mut controller,
+                        control_flow,
+                        &mut error,
+                    );
                 }
                 Event::WindowEvent {
                     event: WindowEvent::Resized(size),
@@ -162,62 +243,21 @@ impl Platform for WinitPlatform {
                         }
                     });
                 }
-                #[allow(unused_variables)]
-                #[allow(deprecated)]
-                Event::WindowEvent {
-                    event:
-                        WindowEvent::KeyboardInput {
-                            device_id,
-                            is_synthetic,
-                            input:
-                                KeyboardInput {
-                                    scancode,
-                                    state: ElementState::Released,
-                                    virtual_keycode,
-                                    modifiers,
-                                },
-                        },
-                    window_id,
-                } => {
-                    if let Some(key) = virtual_keycode {
-                        if key == VirtualKeyCode::R {
-                            let mut interface =
-                                WinitPlatformInterface::new(&mut self, &window_target);
-                            interface.clear_windows();
-                            controller.reset();
-                            controller.initialize(&mut interface);
-                            controller.run();
-                        }
-                        if key == VirtualKeyCode::Q {
-                            let mut interface =
-                                WinitPlatformInterface::new(&mut self, &window_target);
-                            interface.clear_windows();
-                            *control_flow = ControlFlow::Exit;
-                            return;
-                        }
-                    }
-                }
                 Event::MainEventsCleared => {
                     let mut result = EngineUpdateResult::Ok;
                     let mut interface = WinitPlatformInterface::new(&mut self, &window_target);
                     controller.as_running(|s| result = s.tick(&mut interface));
-                    match result {
-                        EngineUpdateResult::Stop => {
-                            interface.clear_windows();
-                            *control_flow = ControlFlow::Exit;
-                            return;
-                        }
-                        EngineUpdateResult::Restart => {
-                            interface.clear_windows();
-                            controller.reset();
-                            controller.initialize(&mut interface);
-                            controller.run();
-                        }
-                        _ => (),
-                    }
+                    handle_update_result(
+                        result,
+                        &mut interface,
+                        &mut controller,
+                        control_flow,
+                        &mut error,
+                    );
                 }
                 _ => (),
             }
         });
+        error.map_or(Ok(()), Err)
     }
 }
@@ -30,6 +30,7 @@ pub(crate) trait AnyWinitPlatformPlugin {
         input: &mut PlatformInitInput,
     ) -> EngineUpdateResult;
     fn process_event(&mut self, event: &Event<()>) -> bool;
+    fn engine_will_shutdown(&mut self, platform_interface: &mut WinitPlatformInterface);
 }
 
 impl<P> AnyWinitPlatformPlugin for WinitPlatformPluginContainer<P>
@@ -67,6 +68,10 @@ where
     fn process_event(&mut self, event: &Event<()>) -> bool {
         self.item.process_event(event)
     }
+
+    fn engine_will_shutdown(&mut self, platform_interface: &mut WinitPlatformInterface) {
+        self.item.engine_will_shutdown(platform_interface)
+    }
 }
 
 pub trait WinitPlatformPlugin: 'static {
@@ -82,4 +87,7 @@ pub trait WinitPlatformPlugin: 'static {
         input: &mut PlatformInitInput,
     ) -> EngineUpdateResult;
     fn process_event(&mut self, event: &Event<()>) -> bool;
+    /// Executed after all engine stages were shut down, plugins are shut down in reverse order of addition.
+    #[allow(unused_variables)]
+    fn engine_will_shutdown(&mut self, platform_interface: &mut WinitPlatformInterface) {}
 }
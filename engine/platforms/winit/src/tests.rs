@@ -0,0 +1,109 @@
+use crate::gamepad::*;
+use engine::input::*;
+use engine::*;
+use std::time::Duration;
+
+const PAD: GamepadId = GamepadId(3);
+
+fn received(receiver: &HandlerReceiver<InputEvent>) -> Vec<InputEvent> {
+    receiver.try_iter().collect()
+}
+
+#[test]
+fn test_gamepad_events() {
+    let backend = VirtualGamepadBackend::default();
+    let settings = GamepadSettings {
+        stick_dead_zone: 0.5,
+        trigger_dead_zone: 0.0,
+    };
+    let mut plugin = GamepadPlugin::new(backend.clone(), settings);
+    let bus = MessageBus::default();
+    let receiver = bus.add_handler::<InputEvent>(MessageHandlerType::Update);
+    plugin.connect(&bus);
+
+    backend.connect(PAD);
+    backend.press(PAD, GamepadButton::South);
+    backend.move_axis(PAD, GamepadAxis::LeftStickX, 0.25);
+    backend.move_axis(PAD, GamepadAxis::LeftStickX, -0.75);
+    backend.move_axis(PAD, GamepadAxis::RightTrigger, 2.0);
+    plugin.poll();
+    assert_eq!(
+        received(&receiver),
+        vec![
+            InputEvent::GamepadConnected(PAD),
+            InputEvent::GamepadButton {
+                gamepad: PAD,
+                button: GamepadButton::South,
+                state: ButtonState::Pressed,
+            },
+            InputEvent::GamepadAxis {
+                gamepad: PAD,
+                axis: GamepadAxis::LeftStickX,
+                value: -0.5,
+            },
+            InputEvent::GamepadAxis {
+                gamepad: PAD,
+                axis: GamepadAxis::RightTrigger,
+                value: 1.0,
+            },
+        ]
+    );
+
+    // Movement within the dead zone is not reported again.
+    backend.move_axis(PAD, GamepadAxis::LeftStickY, 0.25);
+    backend.release(PAD, GamepadButton::South);
+    backend.disconnect(PAD);
+    backend.press(PAD, GamepadButton::North);
+    plugin.poll();
+    assert_eq!(
+        received(&receiver),
+        vec![
+            InputEvent::GamepadButton {
+                gamepad: PAD,
+                button: GamepadButton::South,
+                state: ButtonState::Released,
+            },
+            InputEvent::GamepadDisconnected(PAD),
+        ]
+    );
+}
+
+#[test]
+fn test_gamepad_rumble_and_restart() {
+    let backend = VirtualGamepadBackend::default();
+    let mut plugin = GamepadPlugin::new(backend.clone(), GamepadSettings::default());
+    let bus = MessageBus::default();
+    plugin.connect(&bus);
+
+    backend.connect(PAD);
+    backend.press(PAD, GamepadButton::South);
+    backend.move_axis(PAD, GamepadAxis::LeftTrigger, 0.05);
+    plugin.poll();
+
+    let request = RumbleRequest {
+        gamepad: PAD,
+        strong_magnitude: 1.0,
+        weak_magnitude: 0.5,
+        duration: Duration::from_millis(200),
+    };
+    bus.get_sender::<RumbleRequest>().send(request.clone());
+    plugin.poll();
+    assert_eq!(backend.take_rumble_requests(), vec![request]);
+    assert!(backend.take_rumble_requests().is_empty());
+
+    // A restarted engine is told about the connected gamepads and held buttons.
+    let bus = MessageBus::default();
+    let receiver = bus.add_handler::<InputEvent>(MessageHandlerType::Update);
+    plugin.connect(&bus);
+    assert_eq!(
+        received(&receiver),
+        vec![
+            InputEvent::GamepadConnected(PAD),
+            InputEvent::GamepadButton {
+                gamepad: PAD,
+                button: GamepadButton::South,
+                state: ButtonState::Pressed,
+            },
+        ]
+    );
+}
@@ -0,0 +1,58 @@
+use assets::{AssetCache, AssetIdentifier, AssetSerializationFormat};
+use serde::de::DeserializeOwned;
+use std::fmt::{Display, Formatter};
+
+/// Decodes CBOR for [`AssetSerializationFormat::Binary`] or TOML.
+pub(crate) fn decode<T: DeserializeOwned>(
+    bytes: &[u8],
+    format: AssetSerializationFormat,
+) -> Result<T, AssetDecodingError> {
+    match format {
+        AssetSerializationFormat::Binary => serde_cbor::from_slice(bytes)
+            .map_err(|e| AssetDecodingError::Decoding(format, e.to_string())),
+        AssetSerializationFormat::Toml => std::str::from_utf8(bytes)
+            .map_err(|e| e.to_string())
+            .and_then(|e| toml::from_str(e).map_err(|e| e.to_string()))
+            .map_err(|e| AssetDecodingError::Decoding(format, e)),
+        AssetSerializationFormat::Unknown => Err(AssetDecodingError::UnsupportedFormat),
+    }
+}
+
+/// Loads and decodes an asset, blocking until it is available.
+pub(crate) fn load_asset<T: DeserializeOwned>(
+    asset_cache: &AssetCache,
+    asset_id: AssetIdentifier,
+) -> Result<T, AssetDecodingError> {
+    let format = asset_cache
+        .asset_format(asset_id)
+        .ok_or(AssetDecodingError::AssetUnavailable)?;
+    let handle = asset_cache
+        .request_binary_synchronous(asset_id)
+        .map_err(|_| AssetDecodingError::AssetUnavailable)?;
+    let bytes = handle.read().ok_or(AssetDecodingError::AssetUnavailable)?;
+    decode(bytes, format)
+}
+
+/// Errors of [`decode`] and [`load_asset`], converted into the error type of the decoded asset.
+#[derive(Debug)]
+pub(crate) enum AssetDecodingError {
+    Decoding(AssetSerializationFormat, String),
+    UnsupportedFormat,
+    /// The asset could not be loaded from the asset cache.
+    AssetUnavailable,
+}
+
+impl std::error::Error for AssetDecodingError {}
+impl Display for AssetDecodingError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AssetDecodingError::Decoding(format, e) => {
+                write!(f, "Could not decode asset from {:?}: {}", format, e)
+            }
+            AssetDecodingError::UnsupportedFormat => {
+                write!(f, "Unsupported asset serialization format.")
+            }
+            AssetDecodingError::AssetUnavailable => write!(f, "Asset could not be loaded."),
+        }
+    }
+}
@@ -0,0 +1,18 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
+
+/// Writes the value to the file as CBOR, replacing the file if it exists.
+pub(crate) fn save_cbor<T: Serialize>(value: &T, path: impl AsRef<Path>) -> std::io::Result<()> {
+    let writer = BufWriter::new(File::create(path)?);
+    serde_cbor::to_writer(writer, value).map_err(std::io::Error::other)
+}
+
+/// Reads a value written by [`save_cbor`].
+pub(crate) fn load_cbor<T: DeserializeOwned>(path: impl AsRef<Path>) -> std::io::Result<T> {
+    let reader = BufReader::new(File::open(path)?);
+    serde_cbor::from_reader(reader)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+}
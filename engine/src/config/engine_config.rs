@@ -0,0 +1,265 @@
+use super::*;
+use crate::value_merge::merge_toml_values;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use toml::value::Table;
+use toml::Value;
+use utils::*;
+
+/// Layered configuration of the engine and its stages.
+///
+/// Values are organized in sections, e.g. `engine` or `graphics`, each of which is deserialized into the settings
+/// struct of its owner through [`EngineConfig::section`]. Layers are applied in the order they were added to the
+/// [`EngineConfigBuilder`], later layers override earlier ones. The defaults passed to [`EngineConfig::section`]
+/// form the lowest layer.
+#[derive(Debug, Default, Clone)]
+pub struct EngineConfig {
+    values: Table,
+    /// Layer which last set each key or table, keyed by the dotted key path.
+    sources: BTreeMap<String, ConfigSource>,
+}
+
+impl EngineConfig {
+    pub fn builder() -> EngineConfigBuilder {
+        EngineConfigBuilder::default()
+    }
+
+    /// Merges the configured values of the section over the given defaults.
+    /// Fails if a configured key does not exist in `T`.
+    pub fn section<T: Serialize + DeserializeOwned>(
+        &self,
+        name: &str,
+        defaults: &T,
+    ) -> Result<T, ConfigError> {
+        let mut value = Value::try_from(defaults)
+            .map_err(|e| ConfigError::InvalidValue(String::from(name), e.to_string()))?;
+        let overrides = match self.values.get(name) {
+            Some(v) => v,
+            None => {
+                return value
+                    .try_into()
+                    .map_err(|e| ConfigError::InvalidValue(String::from(name), e.to_string()))
+            }
+        };
+        merge_toml_values(&mut value, overrides.clone());
+
+        let settings: T = value
+            .try_into()
+            .map_err(|e| ConfigError::InvalidValue(String::from(name), e.to_string()))?;
+
+        // Keys which were ignored during deserialization do not survive the round trip.
+        let round_trip = Value::try_from(&settings)
+            .map_err(|e| ConfigError::InvalidValue(String::from(name), e.to_string()))?;
+        match self.find_unknown_key(name, overrides, &round_trip) {
+            Some(e) => Err(e),
+            None => Ok(settings),
+        }
+    }
+
+    /// Fails if a section other than the given ones was configured.
+    pub fn ensure_known_sections(&self, sections: &[&str]) -> Result<(), ConfigError> {
+        match self.values.keys().find(|e| !sections.contains(&e.as_str())) {
+            Some(section) => Err(self.unknown_key(section)),
+            None => Ok(()),
+        }
+    }
+
+    fn find_unknown_key(
+        &self,
+        path: &str,
+        configured: &Value,
+        known: &Value,
+    ) -> Option<ConfigError> {
+        let (configured, known) = match (configured, known) {
+            (Value::Table(configured), Value::Table(known)) => (configured, known),
+            _ => return None,
+        };
+        for (key, value) in configured {
+            let path = format!("{}.{}", path, key);
+            match known.get(key) {
+                Some(known) => {
+                    if let Some(e) = self.find_unknown_key(&path, value, known) {
+                        return Some(e);
+                    }
+                }
+                None => return Some(self.unknown_key(&path)),
+            }
+        }
+        None
+    }
+
+    fn unknown_key(&self, path: &str) -> ConfigError {
+        ConfigError::UnknownKey(String::from(path), self.sources[path].clone())
+    }
+
+    fn apply(&mut self, table: Table, source: &ConfigSource) {
+        Self::record_sources(&mut self.sources, "", &table, source);
+        let mut values = Value::Table(std::mem::take(&mut self.values));
+        merge_toml_values(&mut values, Value::Table(table));
+        if let Value::Table(values) = values {
+            self.values = values;
+        }
+    }
+
+    fn record_sources(
+        sources: &mut BTreeMap<String, ConfigSource>,
+        prefix: &str,
+        table: &Table,
+        source: &ConfigSource,
+    ) {
+        for (key, value) in table {
+            let path = if prefix.is_empty() {
+                key.clone()
+            } else {
+                format!("{}.{}", prefix, key)
+            };
+            if let Value::Table(table) = value {
+                Self::record_sources(sources, &path, table, source);
+            }
+            sources.insert(path, source.clone());
+        }
+    }
+}
+
+enum ConfigLayer {
+    File(PathBuf, bool),
+    Toml(String, String),
+    Environment(String, Vec<(String, String)>),
+    CommandLine(Vec<String>),
+}
+
+/// Collects the layers of an [`EngineConfig`].
+#[derive(Default)]
+pub struct EngineConfigBuilder {
+    layers: Vec<ConfigLayer>,
+}
+
+impl EngineConfigBuilder {
+    /// Adds a TOML file, failing to build if it does not exist.
+    pub fn file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.layers.push(ConfigLayer::File(path.into(), true));
+        self
+    }
+
+    /// Adds a TOML file, which is skipped if it does not exist.
+    pub fn optional_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.layers.push(ConfigLayer::File(path.into(), false));
+        self
+    }
+
+    /// Adds TOML contents loaded elsewhere, e.g. from an asset. `name` is used for error reporting.
+    pub fn toml(mut self, name: impl Into<String>, contents: impl Into<String>) -> Self {
+        self.layers
+            .push(ConfigLayer::Toml(name.into(), contents.into()));
+        self
+    }
+
+    /// Adds the environment variables of the process starting with `{prefix}_`.
+    /// Sections and keys are separated by double underscores, `ZIRCON_ENGINE__UPDATE_TICK_RATE=60`
+    /// sets `engine.update_tick_rate` for the prefix `ZIRCON`.
+    pub fn env(self, prefix: &str) -> Self {
+        self.env_vars(prefix, std::env::vars())
+    }
+
+    /// Adds the given environment variables, see [`EngineConfigBuilder::env`].
+    pub fn env_vars(
+        mut self,
+        prefix: &str,
+        vars: impl IntoIterator<Item = (String, String)>,
+    ) -> Self {
+        let prefix = format!("{}_", prefix);
+        let vars = vars
+            .into_iter()
+            .filter(|(key, _)| key.starts_with(&prefix))
+            .collect();
+        self.layers.push(ConfigLayer::Environment(prefix, vars));
+        self
+    }
+
+    /// Adds command-line overrides of the form `--section.key=value`. Arguments not of the form `--key=value` are
+    /// ignored, which allows passing all arguments of the process.
+    /// Values are parsed as TOML, falling back to a plain string, e.g. `--engine.update_tick_rate=60`.
+    pub fn args(mut self, args: impl IntoIterator<Item = String>) -> Self {
+        self.layers
+            .push(ConfigLayer::CommandLine(args.into_iter().collect()));
+        self
+    }
+
+    pub fn build(self) -> Result<EngineConfig, ConfigError> {
+        let mut config = EngineConfig::default();
+        for layer in self.layers {
+            match layer {
+                ConfigLayer::File(path, required) => {
+                    let contents = match std::fs::read_to_string(&path) {
+                        Ok(v) => v,
+                        Err(e) if !required && e.kind() == std::io::ErrorKind::NotFound => continue,
+                        Err(e) => return Err(ConfigError::InputOutput(path, e)),
+                    };
+                    let source = ConfigSource::File(path);
+                    config.apply(Self::parse_table(&contents, &source)?, &source);
+                }
+                ConfigLayer::Toml(name, contents) => {
+                    let source = ConfigSource::Toml(name);
+                    config.apply(Self::parse_table(&contents, &source)?, &source);
+                }
+                ConfigLayer::Environment(prefix, vars) => {
+                    for (name, value) in vars {
+                        let key = name[prefix.len()..].to_lowercase().replace("__", ".");
+                        let source = ConfigSource::Environment(name);
+                        if let Some(table) = Self::override_table(&key, &value, &source) {
+                            config.apply(table, &source);
+                        }
+                    }
+                }
+                ConfigLayer::CommandLine(args) => {
+                    for arg in args {
+                        let (key, value) =
+                            match arg.strip_prefix("--").and_then(|e| e.split_once('=')) {
+                                Some(v) => v,
+                                None => continue,
+                            };
+                        let source = ConfigSource::CommandLine(arg.clone());
+                        if let Some(table) = Self::override_table(key, value, &source) {
+                            config.apply(table, &source);
+                        }
+                    }
+                }
+            }
+        }
+        Ok(config)
+    }
+
+    fn parse_table(contents: &str, source: &ConfigSource) -> Result<Table, ConfigError> {
+        toml::from_str(contents).map_err(|e| ConfigError::Parse(source.clone(), e.to_string()))
+    }
+
+    /// Builds a table only containing the dotted `key`, set to the parsed `value`.
+    /// Keys not of the form `section.key` are skipped with a warning, as they are likely meant for someone else.
+    fn override_table(key: &str, value: &str, source: &ConfigSource) -> Option<Table> {
+        let segments = key.split('.').collect::<Vec<_>>();
+        if segments.len() < 2 || segments.iter().any(|e| e.is_empty()) {
+            t_warn!(
+                "Ignoring {}, `{}` is not of the form section.key.",
+                source,
+                key
+            );
+            return None;
+        }
+
+        let mut value = toml::from_str::<Table>(&format!("value = {}", value))
+            .ok()
+            .and_then(|mut e| e.remove("value"))
+            .unwrap_or_else(|| Value::String(String::from(value)));
+        for segment in segments.iter().rev() {
+            let mut table = Table::new();
+            table.insert(String::from(*segment), value);
+            value = Value::Table(table);
+        }
+        match value {
+            Value::Table(table) => Some(table),
+            _ => unreachable!(),
+        }
+    }
+}
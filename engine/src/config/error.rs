@@ -0,0 +1,59 @@
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+
+/// Layer a configuration value originates from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigSource {
+    File(PathBuf),
+    /// TOML contents loaded from elsewhere, e.g. an asset. Contains a human readable name.
+    Toml(String),
+    /// Contains the name of the environment variable.
+    Environment(String),
+    /// Contains the command-line argument.
+    CommandLine(String),
+}
+
+impl Display for ConfigSource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigSource::File(path) => write!(f, "file {}", path.display()),
+            ConfigSource::Toml(name) => write!(f, "{}", name),
+            ConfigSource::Environment(name) => write!(f, "environment variable {}", name),
+            ConfigSource::CommandLine(argument) => write!(f, "command-line argument {}", argument),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    /// A configuration file could not be read.
+    InputOutput(PathBuf, std::io::Error),
+    /// A configuration layer is not valid TOML.
+    Parse(ConfigSource, String),
+    /// A key does not exist in the settings it is applied to.
+    UnknownKey(String, ConfigSource),
+    /// The merged values of a section could not be converted into its settings.
+    InvalidValue(String, String),
+}
+
+impl std::error::Error for ConfigError {}
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigError::InputOutput(path, e) => {
+                write!(f, "Could not read config file {}: {}", path.display(), e)
+            }
+            ConfigError::Parse(source, e) => write!(f, "Could not parse {}: {}", source, e),
+            ConfigError::UnknownKey(key, source) => {
+                write!(f, "Unknown configuration key `{}` set by {}.", key, source)
+            }
+            ConfigError::InvalidValue(section, e) => {
+                write!(
+                    f,
+                    "Invalid value in configuration section `{}`: {}",
+                    section, e
+                )
+            }
+        }
+    }
+}
@@ -0,0 +1,7 @@
+mod engine_config;
+mod error;
+#[cfg(test)]
+mod tests;
+
+pub use engine_config::*;
+pub use error::*;
@@ -0,0 +1,194 @@
+use super::*;
+use crate::EngineSettings;
+use serde::{Deserialize, Serialize};
+use std::ffi::CString;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct TestOptions {
+    device_name: Option<CString>,
+    frames_in_flight: u32,
+    layers: Vec<CString>,
+}
+
+impl Default for TestOptions {
+    fn default() -> Self {
+        Self {
+            device_name: None,
+            frames_in_flight: 2,
+            layers: vec![CString::new("validation").unwrap()],
+        }
+    }
+}
+
+fn vars(vars: &[(&str, &str)]) -> Vec<(String, String)> {
+    vars.iter()
+        .map(|(key, value)| (String::from(*key), String::from(*value)))
+        .collect()
+}
+
+fn args(args: &[&str]) -> Vec<String> {
+    args.iter().map(|e| String::from(*e)).collect()
+}
+
+#[test]
+fn test_config_defaults() {
+    let config = EngineConfig::builder().build().unwrap();
+    let settings = EngineSettings::from_config(&config).unwrap();
+    assert_eq!(settings.update_tick_rate, 20);
+    assert_eq!(settings.max_frame_rate, None);
+    assert_eq!(
+        config.section("test", &TestOptions::default()).unwrap(),
+        TestOptions::default()
+    );
+}
+
+#[test]
+fn test_config_layer_precedence() {
+    let config = EngineConfig::builder()
+        .toml(
+            "config asset",
+            r#"
+            [engine]
+            update_tick_rate = 30
+            max_skipped_frames = 4
+
+            [engine.concurrency_settings]
+            max_worker_thread = 3
+
+            [test]
+            device_name = "Asset GPU"
+            frames_in_flight = 3
+            "#,
+        )
+        .env_vars(
+            "ZIRCON",
+            vars(&[
+                ("ZIRCON_ENGINE__UPDATE_TICK_RATE", "40"),
+                ("ZIRCON_TEST__DEVICE_NAME", "Environment GPU"),
+                ("OTHER_ENGINE__UPDATE_TICK_RATE", "10"),
+            ]),
+        )
+        .args(args(&[
+            "game",
+            "--engine.update_tick_rate=60",
+            "--engine.max_frame_rate=144",
+            "--test.layers=[\"a\", \"b\"]",
+        ]))
+        .build()
+        .unwrap();
+
+    let settings = EngineSettings::from_config(&config).unwrap();
+    assert_eq!(settings.update_tick_rate, 60);
+    assert_eq!(settings.max_skipped_frames, 4);
+    assert_eq!(settings.max_frame_rate, Some(144));
+    assert_eq!(
+        settings
+            .concurrency_settings
+            .max_worker_thread
+            .map(|e| e.get()),
+        Some(3)
+    );
+    assert_eq!(
+        settings.concurrency_settings.fallback_worker_threads.get(),
+        8
+    );
+
+    let options = config.section("test", &TestOptions::default()).unwrap();
+    assert_eq!(
+        options.device_name,
+        Some(CString::new("Environment GPU").unwrap())
+    );
+    assert_eq!(options.frames_in_flight, 3);
+    assert_eq!(
+        options.layers,
+        vec![CString::new("a").unwrap(), CString::new("b").unwrap()]
+    );
+    assert!(config.ensure_known_sections(&["engine", "test"]).is_ok());
+}
+
+#[test]
+fn test_config_unknown_keys() {
+    let config = EngineConfig::builder()
+        .toml("config asset", "[engine.concurrency_settings]\nworkers = 4")
+        .args(args(&[
+            "--engine.update_tickrate=60",
+            "--graphic.vsync=true",
+        ]))
+        .build()
+        .unwrap();
+
+    match EngineSettings::from_config(&config) {
+        Err(ConfigError::UnknownKey(key, source)) => {
+            assert_eq!(key, "engine.concurrency_settings.workers");
+            assert_eq!(source, ConfigSource::Toml(String::from("config asset")));
+        }
+        _ => panic!("Expected an unknown key."),
+    }
+
+    let config = EngineConfig::builder()
+        .args(args(&[
+            "--engine.update_tickrate=60",
+            "--graphic.vsync=true",
+        ]))
+        .build()
+        .unwrap();
+    let error = EngineSettings::from_config(&config).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Unknown configuration key `engine.update_tickrate` set by command-line argument --engine.update_tickrate=60."
+    );
+
+    match config.ensure_known_sections(&["engine"]) {
+        Err(ConfigError::UnknownKey(key, _)) => assert_eq!(key, "graphic"),
+        _ => panic!("Expected an unknown section."),
+    }
+}
+
+#[test]
+fn test_config_foreign_overrides_are_ignored() {
+    let config = EngineConfig::builder()
+        .env_vars(
+            "ZIRCON",
+            vars(&[("ZIRCON_TICK_RATE", "60"), ("ZIRCON_ENGINE__", "60")]),
+        )
+        .args(args(&[
+            "./game",
+            "--verbose",
+            "--engine.update_tick_rate",
+            "--tick_rate=60",
+            "--engine..update_tick_rate=60",
+        ]))
+        .build()
+        .unwrap();
+    let settings = EngineSettings::from_config(&config).unwrap();
+    assert_eq!(settings.update_tick_rate, 20);
+    config.ensure_known_sections(&["engine"]).unwrap();
+}
+
+#[test]
+fn test_config_invalid_layers() {
+    let error = EngineConfig::builder()
+        .toml("config asset", "[engine")
+        .build()
+        .unwrap_err();
+    assert!(matches!(error, ConfigError::Parse(..)));
+
+    let error = EngineConfig::builder()
+        .file("./does/not/exist.toml")
+        .build()
+        .unwrap_err();
+    assert!(matches!(error, ConfigError::InputOutput(..)));
+    assert!(EngineConfig::builder()
+        .optional_file("./does/not/exist.toml")
+        .build()
+        .is_ok());
+
+    let config = EngineConfig::builder()
+        .args(args(&["--engine.update_tick_rate=fast"]))
+        .build()
+        .unwrap();
+    match EngineSettings::from_config(&config) {
+        Err(ConfigError::InvalidValue(section, _)) => assert_eq!(section, "engine"),
+        _ => panic!("Expected an invalid value."),
+    }
+}
@@ -0,0 +1,67 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Source of time for the game loop.
+/// Replacing it allows the game loop to be driven deterministically.
+pub trait Clock: Send + Sync + 'static {
+    /// Returns the current instant.
+    fn now(&self) -> Instant;
+    /// Blocks for the given duration, used for frame limiting.
+    fn sleep(&self, duration: Duration);
+}
+
+/// Clock backed by the operating system's monotonic clock.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        std::thread::sleep(duration);
+    }
+}
+
+/// Clock which only moves when it is advanced by hand.
+/// Sleeping advances the clock instead of blocking.
+#[derive(Debug)]
+pub struct ManualClock {
+    origin: Instant,
+    elapsed: Mutex<Duration>,
+}
+
+impl Default for ManualClock {
+    fn default() -> Self {
+        Self {
+            origin: Instant::now(),
+            elapsed: Mutex::new(Duration::new(0, 0)),
+        }
+    }
+}
+
+impl ManualClock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        *self.elapsed.lock().unwrap() += duration;
+    }
+
+    /// Total time the clock has been advanced by.
+    pub fn elapsed(&self) -> Duration {
+        *self.elapsed.lock().unwrap()
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        self.origin + self.elapsed()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        self.advance(duration);
+    }
+}
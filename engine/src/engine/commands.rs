@@ -0,0 +1,127 @@
+use crate::input::{ButtonState, InputEvent, KeyCode};
+use crate::message_bus::{HandlerReceiver, MessageBus, MessageHandlerType, MessageSender};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Command executed by the engine itself, triggered through a key binding or by sending it through the [`MessageBus`].
+///
+/// `Restart`, `Quit`, `TogglePause` and `ToggleStats` are executed at the start of the next frame.
+/// `Screenshot` is left to the render stages, which receive every command as a message,
+/// e.g. the graphics stage saves the next frame of every window.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EngineCommand {
+    Restart,
+    Quit,
+    /// Suspends update ticks while frames are still rendered.
+    TogglePause,
+    Screenshot,
+    /// Toggles logging the frame stats every second.
+    ToggleStats,
+}
+
+/// Keys triggering each [`EngineCommand`].
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct EngineCommandBindings {
+    pub restart: Vec<KeyCode>,
+    pub quit: Vec<KeyCode>,
+    pub toggle_pause: Vec<KeyCode>,
+    pub screenshot: Vec<KeyCode>,
+    pub toggle_stats: Vec<KeyCode>,
+}
+
+impl Default for EngineCommandBindings {
+    fn default() -> Self {
+        Self {
+            restart: vec![KeyCode::F5],
+            quit: vec![KeyCode::F10],
+            toggle_pause: vec![KeyCode::F9],
+            screenshot: vec![KeyCode::F12, KeyCode::Snapshot],
+            toggle_stats: vec![KeyCode::F3],
+        }
+    }
+}
+
+impl EngineCommandBindings {
+    /// Returns the command bound to the key, if any.
+    pub fn command(&self, key: KeyCode) -> Option<EngineCommand> {
+        [
+            (EngineCommand::Restart, &self.restart),
+            (EngineCommand::Quit, &self.quit),
+            (EngineCommand::TogglePause, &self.toggle_pause),
+            (EngineCommand::Screenshot, &self.screenshot),
+            (EngineCommand::ToggleStats, &self.toggle_stats),
+        ]
+        .into_iter()
+        .find(|(_, keys)| keys.contains(&key))
+        .map(|(command, _)| command)
+    }
+}
+
+/// Settings of the engine command key bindings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EngineCommandSettings {
+    /// Whether the key bindings trigger commands. Defaults to false in release builds.
+    /// Commands sent through the [`MessageBus`] are executed regardless.
+    pub enabled: bool,
+    pub bindings: EngineCommandBindings,
+}
+
+impl Default for EngineCommandSettings {
+    fn default() -> Self {
+        Self {
+            enabled: cfg!(debug_assertions),
+            bindings: Default::default(),
+        }
+    }
+}
+
+/// Translates key presses into [`EngineCommand`]s and collects the commands to execute.
+pub(crate) struct EngineCommands {
+    bindings: EngineCommandBindings,
+    /// None if the key bindings are disabled.
+    input: Option<HandlerReceiver<InputEvent>>,
+    /// Keys held down, so that key repeats do not trigger commands again.
+    held_keys: HashSet<KeyCode>,
+    sender: MessageSender<EngineCommand>,
+    commands: HandlerReceiver<EngineCommand>,
+}
+
+impl EngineCommands {
+    pub(crate) fn new(settings: EngineCommandSettings, message_bus: &MessageBus) -> Self {
+        Self {
+            bindings: settings.bindings,
+            input: settings
+                .enabled
+                .then(|| message_bus.add_handler(MessageHandlerType::Render)),
+            held_keys: Default::default(),
+            sender: message_bus.get_sender(),
+            commands: message_bus.add_handler(MessageHandlerType::Render),
+        }
+    }
+
+    /// Returns the commands sent since the last call, including those triggered by keys pressed in the meantime.
+    pub(crate) fn receive(&mut self) -> Vec<EngineCommand> {
+        if let Some(input) = &self.input {
+            for event in input.try_iter() {
+                let InputEvent::Key { key, state } = event else {
+                    continue;
+                };
+                let is_pressed = match state {
+                    ButtonState::Pressed => self.held_keys.insert(key),
+                    ButtonState::Released => {
+                        self.held_keys.remove(&key);
+                        false
+                    }
+                };
+                if let Some(command) = is_pressed
+                    .then_some(key)
+                    .and_then(|key| self.bindings.command(key))
+                {
+                    self.sender.send(command);
+                }
+            }
+        }
+        self.commands.try_iter().collect()
+    }
+}
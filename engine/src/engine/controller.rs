@@ -1,4 +1,6 @@
+use crate::engine_stages::StageQueue;
 use crate::*;
+use std::sync::Arc;
 use utils::*;
 
 use super::engine_states::{
@@ -20,20 +22,33 @@ impl EngineController {
     pub fn shared(&self) -> &EngineSharedState {
         self.engine.state.shared()
     }
-    pub fn suspend(&mut self) {
-        self.engine.state.suspend();
+    /// Queue to add or remove stages, applied before the next tick of the running engine.
+    /// Each engine instance, including restarted ones, has its own queue.
+    pub fn stage_queue(&self) -> Arc<StageQueue> {
+        match self.shared().resources.get_resource::<StageQueue>() {
+            Some(v) => v,
+            None => t_fatal!("Internal engine inconsistency! StageQueue should be added to the resource systems!"),
+        }
+    }
+    pub fn suspend(&mut self, interface: &mut dyn PlatformInterface) -> EngineUpdateResult {
+        self.engine.state.suspend(interface)
     }
     pub fn run(&mut self) {
         self.engine.state.run();
     }
-    pub fn resume(&mut self) {
-        self.engine.state.resume();
+    pub fn resume(&mut self, interface: &mut dyn PlatformInterface) -> EngineUpdateResult {
+        self.engine.state.resume(interface)
     }
     pub fn initialize<P: PlatformInterface + PlatformInitalizationHandler>(
         &mut self,
         interface: &mut P,
-    ) {
-        self.engine.state.initialize(interface);
+    ) -> EngineUpdateResult {
+        self.engine.state.initialize(interface)
+    }
+    /// Tears down all stages of a running or suspended engine.
+    /// Returns false if the engine was in neither state, in which case nothing happens.
+    pub fn shutdown(&mut self, interface: &mut dyn PlatformInterface) -> bool {
+        self.engine.state.shutdown(interface)
     }
     pub fn reset(&mut self) {
         self.engine.state.reset();
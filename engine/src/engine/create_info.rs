@@ -1,6 +1,12 @@
-use std::{ffi::CString, num::NonZeroUsize, sync::Arc};
+use std::{ffi::CString, num::NonZeroUsize, path::PathBuf, sync::Arc};
 
+use super::clock::*;
+use super::commands::EngineCommandSettings;
+use super::frame_stats::EngineFrameStatsSettings;
+use super::stage_error::StageErrorPolicies;
+use crate::config::{ConfigError, EngineConfig};
 use crate::engine_stages::{RenderStageConstructor, UpdateStageConstructor};
+use crate::message_bus::MessageDeliveryPolicies;
 use assets::{AssetCache, AssetRegistry};
 use serde::*;
 use utils::dispatcher::*;
@@ -14,6 +20,55 @@ pub struct EngineConcurrencySettings {
     pub fallback_async_threads: NonZeroUsize,
 }
 
+impl Default for EngineConcurrencySettings {
+    fn default() -> Self {
+        Self {
+            max_async_threads: None,
+            max_worker_thread: None,
+            fallback_worker_threads: NonZeroUsize::new(8).unwrap(),
+            fallback_async_threads: NonZeroUsize::new(2).unwrap(),
+        }
+    }
+}
+
+/// Settings of [`EngineCreateInfo`] which can be loaded from the `engine` section of an [`EngineConfig`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EngineSettings {
+    pub update_tick_rate: u32,
+    pub max_skipped_frames: u32,
+    pub max_frame_rate: Option<u32>,
+    pub concurrency_settings: EngineConcurrencySettings,
+    pub frame_stats: EngineFrameStatsSettings,
+    pub stage_error_policies: StageErrorPolicies,
+    /// File the input of the session is recorded to, written when the engine shuts down.
+    pub input_recording: Option<PathBuf>,
+    pub commands: EngineCommandSettings,
+}
+
+impl Default for EngineSettings {
+    fn default() -> Self {
+        Self {
+            update_tick_rate: 20,
+            max_skipped_frames: 1,
+            max_frame_rate: None,
+            concurrency_settings: Default::default(),
+            frame_stats: Default::default(),
+            stage_error_policies: Default::default(),
+            input_recording: None,
+            commands: Default::default(),
+        }
+    }
+}
+
+impl EngineSettings {
+    pub const SECTION: &'static str = "engine";
+
+    /// Loads the settings from the `engine` section of the config, using [`EngineSettings::default`] as defaults.
+    pub fn from_config(config: &EngineConfig) -> Result<Self, ConfigError> {
+        config.section(Self::SECTION, &Self::default())
+    }
+}
+
 /// Information required to construct an instance of [`Engine`].
 pub struct EngineCreateInfo {
     pub asset_registry: Box<AssetRegistryConstructor>,
@@ -24,6 +79,43 @@ pub struct EngineCreateInfo {
     pub concurrency_settings: EngineConcurrencySettings,
     pub update_stages: Vec<Box<UpdateStageConstructor>>,
     pub render_stages: Vec<Box<RenderStageConstructor>>,
+    /// Clock driving the game loop. Defaults to [`SystemClock`] when `None`.
+    pub clock: Option<Arc<dyn Clock>>,
+    pub frame_stats: EngineFrameStatsSettings,
+    /// How errors returned by each stage are handled.
+    pub stage_error_policies: StageErrorPolicies,
+    /// How messages are queued for their handlers, per message type.
+    pub message_delivery_policies: MessageDeliveryPolicies,
+    /// Records the input of the session to the file, see [`crate::input::InputManager::start_recording`].
+    pub input_recording: Option<PathBuf>,
+    /// Key bindings of the [`crate::EngineCommand`]s.
+    pub commands: EngineCommandSettings,
+}
+
+impl EngineCreateInfo {
+    /// Creates the info from settings, without any stages.
+    pub fn from_settings(
+        settings: EngineSettings,
+        asset_registry: Box<AssetRegistryConstructor>,
+        application_info: Box<ApplicationInfoConstructor>,
+    ) -> Self {
+        Self {
+            asset_registry,
+            application_info,
+            update_tick_rate: settings.update_tick_rate,
+            max_skipped_frames: settings.max_skipped_frames,
+            max_frame_rate: settings.max_frame_rate,
+            concurrency_settings: settings.concurrency_settings,
+            update_stages: vec![],
+            render_stages: vec![],
+            clock: None,
+            frame_stats: settings.frame_stats,
+            stage_error_policies: settings.stage_error_policies,
+            message_delivery_policies: Default::default(),
+            input_recording: settings.input_recording,
+            commands: settings.commands,
+        }
+    }
 }
 
 pub type ApplicationInfoConstructor = dyn Fn(Arc<AssetCache>) -> ApplicationInfo;
@@ -4,8 +4,10 @@ use utils::dispatcher::Dispatcher;
 
 use super::*;
 use crate::engine_stages::*;
+use crate::render_world::RenderWorld;
 use crate::resource_manager::ThreadLocalResourceManager;
 use crate::scene_manager::SceneManager;
+use crate::PlatformInterface;
 use utils::*;
 
 pub struct Initialized {
@@ -24,6 +26,18 @@ impl Into<EngineStateMachine<Running>> for EngineStateMachine<Initialized> {
                 t_fatal!("Internal engine inconsistency! DispatchSystem should be added to the resource systems!");
             }
         };
+        let render_world = match self.shared.resources.get_resource::<RenderWorld>() {
+            Some(v) => v,
+            None => {
+                t_fatal!("Internal engine inconsistency! RenderWorld should be added to the resource systems!");
+            }
+        };
+        let stage_queue = match self.shared.resources.get_resource::<StageQueue>() {
+            Some(v) => v,
+            None => {
+                t_fatal!("Internal engine inconsistency! StageQueue should be added to the resource systems!");
+            }
+        };
         EngineStateMachine {
             shared: self.shared,
             state: Running {
@@ -34,9 +48,43 @@ impl Into<EngineStateMachine<Running>> for EngineStateMachine<Initialized> {
                     self.state.render_stage_update_handlers,
                     self.state.update_thread_resources,
                     dispatch_system,
+                    render_world,
                 ),
                 render_stages: self.state.render_stages,
+                stage_queue,
             },
         }
     }
 }
+
+impl EngineStateMachine<Initialized> {
+    /// Shuts down all stages in reverse construction order without ever running the engine.
+    /// Used to tear down partially initialized engines, mirroring [`EngineStateMachine::<Running>::shutdown`].
+    pub(super) fn shutdown(mut self, interface: &mut dyn PlatformInterface) -> EngineSharedState {
+        let dispatch_system = match self.shared.resources.get_resource::<Dispatcher>() {
+            Some(v) => Arc::clone(&v),
+            None => {
+                t_fatal!("Internal engine inconsistency! DispatchSystem should be added to the resource systems!");
+            }
+        };
+        self.state.render_stage_update_handlers.clear();
+
+        let timings = &self.shared.internal_resources.timings;
+        while let Some(stage) = self.state.render_stages.pop() {
+            shut_down_render_stage(stage, &self.shared.resources, interface, timings);
+        }
+
+        while let Some(stage) = self.state.update_stages.pop() {
+            shut_down_update_stage(
+                stage,
+                &self.shared.resources,
+                &dispatch_system,
+                &mut self.state.scene_manager,
+                &mut self.state.update_thread_resources,
+                timings,
+            );
+        }
+        dispatch_system.wait_for_spawned_jobs();
+        self.shared
+    }
+}
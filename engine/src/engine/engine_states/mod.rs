@@ -1,17 +1,23 @@
 mod initialized;
 mod running;
+mod shutdown;
 mod suspended;
 mod uninitialized;
 
 mod shared_state;
+mod stage_shutdown;
 mod statemachine;
+mod update_stage_schedule;
 mod update_stages_runner;
 
 pub use shared_state::*;
+use stage_shutdown::*;
 pub use statemachine::*;
+use update_stage_schedule::*;
 use update_stages_runner::*;
 
 pub use initialized::*;
 pub use running::*;
+pub use shutdown::*;
 pub use suspended::*;
 pub use uninitialized::*;
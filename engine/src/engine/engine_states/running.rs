@@ -1,14 +1,19 @@
 use super::*;
-use crate::{engine::result::*, engine_stages::*, PlatformInterface};
+use crate::engine::commands::EngineCommand;
+use crate::input::InputManager;
+use crate::message_bus::{MessageBus, MessageHandlerType};
+use crate::{engine::result::*, engine::stage_error::*, engine_stages::*, PlatformInterface};
 use std::sync::Arc;
-use std::time::*;
+use std::time::Duration;
 use utils::dispatcher::Dispatcher;
 use utils::split_view::*;
+use utils::*;
 
 pub struct Running {
     pub(crate) dispatch_system: Arc<Dispatcher>,
     pub(super) update_stages_runner: UpdateStagesRunner,
     pub(crate) render_stages: Vec<Box<dyn AnyRenderStage>>,
+    pub(super) stage_queue: Arc<StageQueue>,
 }
 
 impl Into<EngineStateMachine<Suspended>> for EngineStateMachine<Running> {
@@ -19,6 +24,7 @@ impl Into<EngineStateMachine<Suspended>> for EngineStateMachine<Running> {
                 dispatch_system: self.state.dispatch_system,
                 update_stages_runner: self.state.update_stages_runner,
                 render_stages: self.state.render_stages,
+                stage_queue: self.state.stage_queue,
             },
         }
     }
@@ -27,12 +33,29 @@ impl Into<EngineStateMachine<Suspended>> for EngineStateMachine<Running> {
 impl EngineStateMachine<Running> {
     pub fn tick(&mut self, interface: &mut dyn PlatformInterface) -> EngineUpdateResult {
         self.shared.internal_resources.timings.frame_start();
+        match self.execute_commands() {
+            EngineUpdateResult::Ok => {}
+            result => return result,
+        }
+        if self.shared.internal_resources.timings.paused {
+            self.shared.internal_resources.timings.accumulated_time = Duration::new(0, 0);
+        }
 
         let tick_rate = self.shared.internal_resources.timings.update_tick_rate;
         let alpha = self.shared.internal_resources.timings.alpha;
 
-        let fixed_update_step_duration = Duration::from_millis(1000)
-            / (self.shared.internal_resources.timings.update_tick_rate as u32);
+        let fixed_update_step_duration = self
+            .shared
+            .internal_resources
+            .timings
+            .fixed_update_step_duration();
+
+        if !self.state.stage_queue.is_empty() {
+            match self.apply_stage_changes(interface) {
+                EngineUpdateResult::Ok => {}
+                result => return result,
+            }
+        }
 
         {
             let frame_counter_past_second = self.shared.internal_resources.timings.frame_counter;
@@ -47,6 +70,10 @@ impl EngineStateMachine<Running> {
                     update_counter_past_second,
                 ));
             });
+            self.shared
+                .resources
+                .resource::<MessageBus>()
+                .receive_subscribed_messages(MessageHandlerType::Render);
         }
 
         // Trigger the update thread if necessary.
@@ -64,6 +91,7 @@ impl EngineStateMachine<Running> {
             self.shared.internal_resources.timings.accumulated_time -= fixed_update_step_duration;
             n_loops += 1;
             self.shared.internal_resources.timings.update_counter += 1;
+            self.shared.internal_resources.timings.update_tick += 1;
             self.shared
                 .internal_resources
                 .timings
@@ -73,19 +101,25 @@ impl EngineStateMachine<Running> {
             let frame_counter_past_second = self.shared.internal_resources.timings.frame_counter;
             let update_counter_past_second = self.shared.internal_resources.timings.update_counter;
 
+            let policies = &self.shared.internal_resources.stage_error_policies;
             if let Err(e) = SplitViewMut::for_each_until_error(
                 &mut self.state.render_stages,
-                |mut split_view| match split_view.item_mut().update_thread_did_run(
-                    RenderStageUpdateInput::new(
+                |mut split_view| {
+                    let item = split_view.item_mut();
+                    let result = item.update_thread_did_run(RenderStageUpdateInput::new(
                         interface,
                         tick_rate,
                         alpha,
                         frame_counter_past_second,
                         update_counter_past_second,
-                    ),
-                ) {
-                    EngineUpdateResult::Ok => Ok(()),
-                    result => Err(result),
+                    ));
+                    let identifier = item.identifier();
+                    match policies.apply(identifier, StagePhase::UpdateThreadDidRun, result, || {
+                        item.disable()
+                    }) {
+                        EngineUpdateResult::Ok => Ok(()),
+                        result => Err(result),
+                    }
                 },
             ) {
                 return e;
@@ -93,20 +127,31 @@ impl EngineStateMachine<Running> {
         }
 
         // Trigger the render thread.
+        self.shared.internal_resources.timings.update_alpha();
+        let alpha = self.shared.internal_resources.timings.alpha;
         let frame_counter_past_second = self.shared.internal_resources.timings.frame_counter;
         let update_counter_past_second = self.shared.internal_resources.timings.update_counter;
+        let timings = &self.shared.internal_resources.timings;
+        let policies = &self.shared.internal_resources.stage_error_policies;
 
         if let Err(e) =
             SplitViewMut::for_each_until_error(&mut self.state.render_stages, |mut split_view| {
                 let (before, item, after) = split_view.components_mut();
                 let _manager = RenderStageManager::from_slices(before, after);
-                match item.render(RenderStageUpdateInput::new(
+                let render_start = timings.clock.now();
+                let result = item.render(RenderStageUpdateInput::new(
                     interface,
                     tick_rate,
                     alpha,
                     frame_counter_past_second,
                     update_counter_past_second,
-                )) {
+                ));
+                let identifier = item.identifier();
+                timings.stats.record_render_stage_time(
+                    identifier,
+                    timings.clock.now().duration_since(render_start),
+                );
+                match policies.apply(identifier, StagePhase::Render, result, || item.disable()) {
                     EngineUpdateResult::Ok => Ok(()),
                     result => Err(result),
                 }
@@ -118,4 +163,154 @@ impl EngineStateMachine<Running> {
 
         EngineUpdateResult::Ok
     }
+
+    /// Waits for the in-flight update job and applies the changes queued in the [`StageQueue`].
+    fn apply_stage_changes(&mut self, interface: &mut dyn PlatformInterface) -> EngineUpdateResult {
+        match self.state.update_stages_runner.synchronize() {
+            EngineUpdateResult::Ok => {}
+            result => return result,
+        }
+        let changes = self.state.stage_queue.take();
+        self.state.update_stages_runner.apply_stage_changes(
+            changes,
+            &self.shared,
+            &mut self.state.render_stages,
+            interface,
+        )
+    }
+
+    /// Executes `n_ticks` engine ticks, waiting for the update thread after each one.
+    /// Combined with a [`crate::ManualClock`] this makes the interleaving of updates and renders deterministic.
+    pub fn step(
+        &mut self,
+        interface: &mut dyn PlatformInterface,
+        n_ticks: u32,
+    ) -> EngineUpdateResult {
+        for _ in 0..n_ticks {
+            match self.tick(interface) {
+                EngineUpdateResult::Ok => {}
+                result => return result,
+            }
+            match self.state.update_stages_runner.synchronize() {
+                EngineUpdateResult::Ok => {}
+                result => return result,
+            }
+        }
+        EngineUpdateResult::Ok
+    }
+
+    /// Drains the in-flight update job and notifies all stages, update stages first, that the engine will suspend.
+    /// The engine should only be suspended if [`EngineUpdateResult::Ok`] is returned.
+    pub fn engine_will_suspend(
+        &mut self,
+        interface: &mut dyn PlatformInterface,
+    ) -> EngineUpdateResult {
+        match self.state.update_stages_runner.synchronize() {
+            EngineUpdateResult::Ok => {}
+            result => return result,
+        }
+        match self
+            .state
+            .update_stages_runner
+            .engine_will_suspend(&self.shared)
+        {
+            EngineUpdateResult::Ok => {}
+            result => return result,
+        }
+
+        let timings = &self.shared.internal_resources.timings;
+        let policies = &self.shared.internal_resources.stage_error_policies;
+        for stage in &mut self.state.render_stages {
+            let result = stage.engine_will_suspend(RenderStageUpdateInput::new(
+                interface,
+                timings.update_tick_rate,
+                timings.alpha,
+                timings.frame_counter,
+                timings.update_counter,
+            ));
+            let identifier = stage.identifier();
+            match policies.apply(identifier, StagePhase::Suspend, result, || stage.disable()) {
+                EngineUpdateResult::Ok => {}
+                result => return result,
+            }
+        }
+        EngineUpdateResult::Ok
+    }
+
+    /// Tears down the engine in reverse construction order.
+    /// Waits for the in-flight update job, shuts down and drops render stages and their update thread handlers,
+    /// then the update stages, and finally waits for outstanding dispatcher jobs.
+    pub fn shutdown(
+        mut self,
+        interface: &mut dyn PlatformInterface,
+    ) -> EngineStateMachine<Shutdown> {
+        match self.state.update_stages_runner.synchronize() {
+            EngineUpdateResult::Ok => {}
+            result => t_info!("Update thread returned {:?} while shutting down.", result),
+        }
+        self.state
+            .update_stages_runner
+            .drop_render_stage_update_thread_handlers();
+
+        let timings = &self.shared.internal_resources.timings;
+        while let Some(stage) = self.state.render_stages.pop() {
+            shut_down_render_stage(stage, &self.shared.resources, interface, timings);
+        }
+
+        self.state
+            .update_stages_runner
+            .shutdown_update_stages(&self.shared);
+        self.state.dispatch_system.wait_for_spawned_jobs();
+        self.save_input_recording();
+
+        EngineStateMachine {
+            shared: self.shared,
+            state: Shutdown {},
+        }
+    }
+
+    /// Executes the [`EngineCommand`]s sent since the last frame.
+    fn execute_commands(&mut self) -> EngineUpdateResult {
+        let internal_resources = &mut self.shared.internal_resources;
+        for command in internal_resources.commands.receive() {
+            let timings = &mut internal_resources.timings;
+            match command {
+                EngineCommand::Restart => {
+                    t_info!("Restarting engine...");
+                    return EngineUpdateResult::Restart;
+                }
+                EngineCommand::Quit => {
+                    t_info!("Quitting engine...");
+                    return EngineUpdateResult::Stop;
+                }
+                EngineCommand::TogglePause => {
+                    timings.paused = !timings.paused;
+                    t_info!("Engine paused: {}", timings.paused);
+                }
+                EngineCommand::ToggleStats => timings.log_frame_stats = !timings.log_frame_stats,
+                // Taken by the render stages handling the command message.
+                EngineCommand::Screenshot => (),
+            }
+        }
+        EngineUpdateResult::Ok
+    }
+
+    /// Saves the input recorded since initialization, if requested by [`crate::EngineCreateInfo::input_recording`].
+    fn save_input_recording(&self) {
+        let Some(path) = &self.shared.create_info.input_recording else {
+            return;
+        };
+        let Some(recording) = self
+            .shared
+            .resources
+            .resource::<InputManager>()
+            .stop_recording()
+        else {
+            return;
+        };
+        match recording.save(path) {
+            Ok(()) => t_info!("Saved input recording to {:?}.", path),
+            Err(e) => t_warn!("Could not save input recording to {:?}: {}", path, e),
+        }
+    }
 }
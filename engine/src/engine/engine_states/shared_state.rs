@@ -1,7 +1,10 @@
 use std::sync::Arc;
 
 use crate::resource_manager::EngineResourceManager;
-use crate::{engine::gameloop_timer::EngineGameloopTimer, EngineCreateInfo};
+use crate::{
+    engine::commands::EngineCommands, engine::gameloop_timer::EngineGameloopTimer,
+    engine::stage_error::StageErrorPolicies, EngineCreateInfo,
+};
 
 pub struct EngineSharedState {
     pub resources: Arc<EngineResourceManager>,
@@ -11,4 +14,6 @@ pub struct EngineSharedState {
 
 pub struct EngineInternalResources {
     pub timings: EngineGameloopTimer,
+    pub stage_error_policies: Arc<StageErrorPolicies>,
+    pub(crate) commands: EngineCommands,
 }
@@ -0,0 +1,2 @@
+/// The engine has torn down all stages. Only a reset is possible from this state.
+pub struct Shutdown {}
@@ -0,0 +1,51 @@
+use crate::engine::gameloop_timer::EngineGameloopTimer;
+use crate::engine_stages::*;
+use crate::resource_manager::{EngineResourceManager, ThreadLocalResourceManager};
+use crate::scene_manager::SceneManager;
+use crate::PlatformInterface;
+use std::sync::Arc;
+use utils::dispatcher::Dispatcher;
+use utils::*;
+
+/// Executes the shutdown hook of the update stage, then drops it together with its scoped resources.
+pub(super) fn shut_down_update_stage(
+    mut stage: Box<dyn AnyUpdateStage>,
+    resources: &Arc<EngineResourceManager>,
+    dispatcher: &Arc<Dispatcher>,
+    scene_manager: &mut SceneManager,
+    thread_local_resources: &mut ThreadLocalResourceManager,
+    timings: &EngineGameloopTimer,
+) {
+    let scene_target = stage.dependencies().scene_target().clone();
+    stage.engine_will_shutdown(
+        UpdateStageUpdateInput::new(
+            Arc::clone(resources),
+            Arc::clone(dispatcher),
+            scene_manager,
+            thread_local_resources,
+            timings.update_tick_rate,
+            timings.update_counter,
+        )
+        .with_scene_target(scene_target),
+    );
+    resources.remove_scoped_resources(stage.identifier());
+    t_info!("Shut down update stage: {}", stage.identifier());
+}
+
+/// Executes the shutdown hook of the render stage, then drops it together with its scoped resources.
+pub(super) fn shut_down_render_stage(
+    mut stage: Box<dyn AnyRenderStage>,
+    resources: &EngineResourceManager,
+    interface: &mut dyn PlatformInterface,
+    timings: &EngineGameloopTimer,
+) {
+    stage.engine_will_shutdown(RenderStageUpdateInput::new(
+        interface,
+        timings.update_tick_rate,
+        timings.alpha,
+        timings.frame_counter,
+        timings.update_counter,
+    ));
+    resources.remove_scoped_resources(stage.identifier());
+    t_info!("Shut down render stage: {}", stage.identifier());
+}
@@ -23,9 +23,10 @@ impl<State, SharedState> From<(State, SharedState)> for StateMachine<State, Shar
 pub type EngineStateMachine<T> = StateMachine<T, EngineSharedState>;
 pub enum EngineState {
     Uninitialized(EngineStateMachine<Uninitialized>),
-    Initialized(EngineStateMachine<Initialized>),
+    Initialized(Box<EngineStateMachine<Initialized>>),
     Running(EngineStateMachine<Running>),
     Suspended(EngineStateMachine<Suspended>),
+    Shutdown(EngineStateMachine<Shutdown>),
     Invalid,
 }
 
@@ -36,6 +37,7 @@ impl EngineState {
             EngineState::Initialized(e) => &e.shared,
             EngineState::Running(e) => &e.shared,
             EngineState::Suspended(e) => &e.shared,
+            EngineState::Shutdown(e) => &e.shared,
             EngineState::Invalid => t_fatal!("Cannot get shared state from invalid engine state."),
         };
     }
@@ -44,19 +46,31 @@ impl EngineState {
     /// This function is executed before the did init handlers are executed.
     /// It is intended to set up platform specific event handling and such.
     /// This is so the platform/interface can integrate event handling before anything starts executing!
+    /// If initialization fails, the engine is reset to a fresh Uninitialized state and the failure is returned.
+    /// Restarts requested during initialization are turned into errors, as restarting would fail the same way.
     pub fn initialize<P: PlatformInterface + PlatformInitalizationHandler>(
         &mut self,
         interface: &mut P,
-    ) {
-        *self = match std::mem::replace(self, EngineState::Invalid) {
-            EngineState::Uninitialized(s) => {
-                let s = EngineState::Initialized((s, interface).into());
-                t_info!("EngineState changed: Initialized");
-                s
-            }
+    ) -> EngineUpdateResult {
+        match std::mem::replace(self, EngineState::Invalid) {
+            EngineState::Uninitialized(s) => match s.initialize(interface) {
+                Ok(s) => {
+                    *self = EngineState::Initialized(Box::new(s));
+                    t_info!("EngineState changed: Initialized");
+                    EngineUpdateResult::Ok
+                }
+                Err(failure) => {
+                    let (shared, result) = *failure;
+                    *self = EngineState::Uninitialized(EngineStateMachine::<Uninitialized>::new(
+                        shared.create_info,
+                    ));
+                    result
+                }
+            },
             s => {
                 t_warn!("Cannot initialize game engine while not in Uninitialized state!");
-                s
+                *self = s;
+                EngineUpdateResult::Ok
             }
         }
     }
@@ -64,7 +78,7 @@ impl EngineState {
     pub fn run(&mut self) {
         *self = match std::mem::replace(self, EngineState::Invalid) {
             EngineState::Initialized(s) => {
-                let s = EngineState::Running(s.into());
+                let s = EngineState::Running((*s).into());
                 t_info!("EngineState changed: Running");
                 s
             }
@@ -75,20 +89,47 @@ impl EngineState {
         }
     }
 
-    pub fn suspend(&mut self) {
-        *self = match std::mem::replace(self, EngineState::Invalid) {
-            EngineState::Running(s) => {
-                let s = EngineState::Suspended(s.into());
-                t_info!("EngineState changed: Suspended");
-                s
-            }
+    /// Suspends the engine, draining the update thread and executing the suspend hooks of all stages.
+    /// If a hook returns anything other than [`EngineUpdateResult::Ok`], the engine keeps running
+    /// and the result is returned so the platform can act on it.
+    pub fn suspend(&mut self, interface: &mut dyn PlatformInterface) -> EngineUpdateResult {
+        match std::mem::replace(self, EngineState::Invalid) {
+            EngineState::Running(mut s) => match s.engine_will_suspend(interface) {
+                EngineUpdateResult::Ok => {
+                    *self = EngineState::Suspended(s.into());
+                    t_info!("EngineState changed: Suspended");
+                    EngineUpdateResult::Ok
+                }
+                result => {
+                    *self = EngineState::Running(s);
+                    result
+                }
+            },
             s => {
                 t_warn!("Cannot suspend game engine while not in Running state!");
-                s
+                *self = s;
+                EngineUpdateResult::Ok
             }
         }
     }
 
+    /// Shuts down a running or suspended engine, tearing down all stages in reverse construction order.
+    /// Suspended engines are shut down without being resumed.
+    /// Returns false if the engine was in neither state, in which case nothing happens.
+    pub fn shutdown(&mut self, interface: &mut dyn PlatformInterface) -> bool {
+        let running = match std::mem::replace(self, EngineState::Invalid) {
+            EngineState::Running(s) => s,
+            EngineState::Suspended(s) => s.into(),
+            s => {
+                *self = s;
+                return false;
+            }
+        };
+        *self = EngineState::Shutdown(running.shutdown(interface));
+        t_info!("EngineState changed: Shutdown");
+        true
+    }
+
     pub fn reset(&mut self) {
         *self = match std::mem::replace(self, EngineState::Invalid) {
             EngineState::Uninitialized(s) => {
@@ -111,22 +152,37 @@ impl EngineState {
                     s.shared.create_info,
                 ))
             }
+            EngineState::Shutdown(s) => {
+                EngineState::Uninitialized(EngineStateMachine::<Uninitialized>::new(
+                    s.shared.create_info,
+                ))
+            }
             EngineState::Invalid => {
                 t_fatal!("Cannot take shared state on invalid enum!");
             }
         }
     }
 
-    pub fn resume(&mut self) {
-        *self = match std::mem::replace(self, EngineState::Invalid) {
-            EngineState::Suspended(s) => {
-                let s = EngineState::Running(s.into());
-                t_info!("EngineState changed: Running");
-                s
-            }
+    /// Resumes the engine, executing the resume hooks of all stages.
+    /// If a hook returns anything other than [`EngineUpdateResult::Ok`], the engine stays suspended
+    /// and the result is returned so the platform can act on it.
+    pub fn resume(&mut self, interface: &mut dyn PlatformInterface) -> EngineUpdateResult {
+        match std::mem::replace(self, EngineState::Invalid) {
+            EngineState::Suspended(mut s) => match s.engine_will_resume(interface) {
+                EngineUpdateResult::Ok => {
+                    *self = EngineState::Running(s.into());
+                    t_info!("EngineState changed: Running");
+                    EngineUpdateResult::Ok
+                }
+                result => {
+                    *self = EngineState::Suspended(s);
+                    result
+                }
+            },
             s => {
                 t_warn!("Cannot resume game engine while not in Suspended state!");
-                s
+                *self = s;
+                EngineUpdateResult::Ok
             }
         }
     }
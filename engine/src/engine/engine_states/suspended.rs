@@ -1,5 +1,5 @@
 use super::*;
-use crate::engine_stages::*;
+use crate::{engine::result::*, engine::stage_error::*, engine_stages::*, PlatformInterface};
 use std::sync::Arc;
 use utils::dispatcher::Dispatcher;
 
@@ -7,6 +7,7 @@ pub struct Suspended {
     pub(crate) dispatch_system: Arc<Dispatcher>,
     pub(super) update_stages_runner: UpdateStagesRunner,
     pub(crate) render_stages: Vec<Box<dyn AnyRenderStage>>,
+    pub(super) stage_queue: Arc<StageQueue>,
 }
 
 impl Into<EngineStateMachine<Running>> for EngineStateMachine<Suspended> {
@@ -17,7 +18,50 @@ impl Into<EngineStateMachine<Running>> for EngineStateMachine<Suspended> {
                 dispatch_system: self.state.dispatch_system,
                 update_stages_runner: self.state.update_stages_runner,
                 render_stages: self.state.render_stages,
+                stage_queue: self.state.stage_queue,
             },
         }
     }
 }
+
+impl EngineStateMachine<Suspended> {
+    /// Notifies all stages, update stages first, that the engine will resume.
+    /// The engine should only be resumed if [`EngineUpdateResult::Ok`] is returned.
+    pub fn engine_will_resume(
+        &mut self,
+        interface: &mut dyn PlatformInterface,
+    ) -> EngineUpdateResult {
+        match self
+            .state
+            .update_stages_runner
+            .engine_will_resume(&self.shared)
+        {
+            EngineUpdateResult::Ok => {}
+            result => return result,
+        }
+
+        let timings = &self.shared.internal_resources.timings;
+        let policies = &self.shared.internal_resources.stage_error_policies;
+        for stage in &mut self.state.render_stages {
+            let result = stage.engine_will_resume(RenderStageUpdateInput::new(
+                interface,
+                timings.update_tick_rate,
+                timings.alpha,
+                timings.frame_counter,
+                timings.update_counter,
+            ));
+            let identifier = stage.identifier();
+            match policies.apply(identifier, StagePhase::Resume, result, || stage.disable()) {
+                EngineUpdateResult::Ok => {}
+                result => return result,
+            }
+        }
+
+        // Time spent while suspended should not be caught up on.
+        self.shared
+            .internal_resources
+            .timings
+            .discard_elapsed_time();
+        EngineUpdateResult::Ok
+    }
+}
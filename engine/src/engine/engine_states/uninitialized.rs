@@ -1,12 +1,14 @@
 use super::*;
-use crate::message_bus::{AnyMessageRegisterer, MessageBusBuilder, MessageHandlerType};
-use crate::scene_manager::SceneManager;
-use crate::{engine::gameloop_timer::*, engine_stages::*, resource_manager::*, *};
-use assets::AssetCache;
-use std::{
-    sync::Arc,
-    time::{Duration, Instant},
+use crate::input::{InputEvent, InputManager, RumbleRequest};
+use crate::message_bus::{AnyMessageRegisterer, MessageBus, MessageHandlerType};
+use crate::render_world::RenderWorld;
+use crate::scene_manager::*;
+use crate::{
+    engine::clock::*, engine::commands::*, engine::gameloop_timer::*, engine_stages::*,
+    resource_manager::*, *,
 };
+use assets::AssetCache;
+use std::{sync::Arc, time::Duration};
 use utils::dispatcher::Dispatcher;
 use utils::split_view::SplitViewMut;
 use utils::*;
@@ -15,7 +17,8 @@ pub struct Uninitialized {}
 
 impl EngineStateMachine<Uninitialized> {
     pub fn new(info: EngineCreateInfo) -> Self {
-        let instant = Instant::now();
+        let clock = info.clock.clone().unwrap_or_else(|| Arc::new(SystemClock));
+        let instant = clock.now();
         let resources = EngineResourceManager::default();
         let dispatcher = Dispatcher::new(
             info.concurrency_settings.max_async_threads,
@@ -32,7 +35,34 @@ impl EngineStateMachine<Uninitialized> {
         resources.add_resource(asset_cache);
         let application_info = (info.application_info)(resources.get_resource().unwrap());
 
+        resources.add_resource(MessageBus::default());
+        let message_bus = resources.resource::<MessageBus>();
+        info.message_delivery_policies.apply(&message_bus);
+        message_bus.tracer().register_payload::<WindowDidOpen>();
+        message_bus.tracer().register_payload::<WindowDidResize>();
+        message_bus.tracer().register_payload::<WindowWillClose>();
+        message_bus.tracer().register_payload::<InputEvent>();
+        message_bus.tracer().register_payload::<RumbleRequest>();
+        message_bus.tracer().register_payload::<EngineCommand>();
+        let commands = EngineCommands::new(info.commands.clone(), &message_bus);
+        resources.publish_messages(&message_bus);
+        let input_manager = InputManager::new(&message_bus);
+        if info.input_recording.is_some() {
+            input_manager.start_recording();
+        }
+        resources.add_resource(input_manager);
+        resources.add_resource(StageQueue::default());
         resources.add_resource(SceneManager::default());
+        let scene_components = SceneComponentRegistry::default();
+        scene_components.register_component::<Transform>("transform");
+        scene_components.register_component::<GlobalTransform>("global_transform");
+        scene_components.register_component::<Parent>("parent");
+        scene_components.register_component::<Children>("children");
+        resources.add_resource(scene_components);
+        resources.add_resource(PrefabLibrary::new(resources.get_resource().unwrap()));
+        resources.add_resource(RenderWorld::default());
+        resources.add_resource(EngineFrameStats::new(info.frame_stats.window_size));
+        let frame_stats = resources.get_resource::<EngineFrameStats>().unwrap();
 
         t_info!(
             "Initializing {:#?} version {:#?}.{:#?}.{:#?}",
@@ -54,9 +84,13 @@ impl EngineStateMachine<Uninitialized> {
                 resources: Arc::new(resources),
                 internal_resources: EngineInternalResources {
                     timings: EngineGameloopTimer {
+                        clock,
+                        stats: frame_stats,
+                        log_frame_stats: info.frame_stats.log_every_second,
                         update_tick_rate: info.update_tick_rate,
                         max_skipped_frames: info.max_skipped_frames,
                         max_frame_rate: info.max_frame_rate.clone(),
+                        paused: false,
                         previous_frame_instant: instant,
                         previous_second_instant: instant,
                         last_fixed_update_instant: instant,
@@ -66,11 +100,14 @@ impl EngineStateMachine<Uninitialized> {
                         previous_sleep_time: Duration::new(0, 0),
                         negative_sleep_time: Duration::new(0, 0),
                         update_counter: 0,
+                        update_tick: 0,
                         frame_counter: 0,
                         total_sleep_time_last_second: Duration::new(0, 0),
                         total_frame_time_last_second: Duration::new(0, 0),
                         alpha: 0.0,
                     },
+                    stage_error_policies: Arc::new(info.stage_error_policies.clone()),
+                    commands,
                 },
                 create_info: info,
             },
@@ -79,11 +116,18 @@ impl EngineStateMachine<Uninitialized> {
     }
 }
 
-impl<P: PlatformInterface + PlatformInitalizationHandler> Into<EngineStateMachine<Initialized>>
-    for (EngineStateMachine<Uninitialized>, &mut P)
-{
-    fn into(self) -> EngineStateMachine<Initialized> {
-        let (uninit, interface) = self;
+impl EngineStateMachine<Uninitialized> {
+    /// Constructs and initializes all stages.
+    /// On failure, the shared state is returned alongside the result which caused it.
+    /// Stages which were already initialized are shut down in reverse construction order,
+    /// the failing stage and the stages after it are dropped without being shut down.
+    /// If the platform fails before any stage initialized, all stages are dropped.
+    pub fn initialize<P: PlatformInterface + PlatformInitalizationHandler>(
+        self,
+        interface: &mut P,
+    ) -> Result<EngineStateMachine<Initialized>, Box<(EngineSharedState, EngineUpdateResult)>> {
+        let uninit = self;
+        let policies = Arc::clone(&uninit.shared.internal_resources.stage_error_policies);
         let dispatch_system = match uninit.shared.resources.get_resource::<Dispatcher>() {
             Some(v) => Arc::clone(&v),
             None => {
@@ -122,16 +166,21 @@ impl<P: PlatformInterface + PlatformInitalizationHandler> Into<EngineStateMachin
             (update_stages, render_stages)
         };
 
-        let mut builder = MessageBusBuilder::default();
+        let message_bus = match uninit.shared.resources.get_resource::<MessageBus>() {
+            Some(v) => v,
+            None => {
+                t_fatal!("Internal engine inconsistency! MessageBus should be added to the resource systems!");
+            }
+        };
         update_stages.iter_mut().for_each(|stage| {
             stage.register_message_handlers(AnyMessageRegisterer::new(
-                &mut builder,
+                &message_bus,
                 MessageHandlerType::Update,
             ));
         });
         render_stages.iter_mut().for_each(|stage| {
             stage.register_message_handlers(AnyMessageRegisterer::new(
-                &mut builder,
+                &message_bus,
                 MessageHandlerType::Render,
             ));
         });
@@ -142,13 +191,14 @@ impl<P: PlatformInterface + PlatformInitalizationHandler> Into<EngineStateMachin
                     RenderStageUpdateThreadHandlerCreateInfo::new(
                         &mut update_thread_local_resources,
                     ),
-                    AnyMessageRegisterer::new(&mut builder, MessageHandlerType::Update),
+                    AnyMessageRegisterer::new(&message_bus, MessageHandlerType::Update),
                 )
             })
             .collect::<Vec<_>>();
 
-        uninit.shared.resources.add_resource(builder.build());
-        let mut scene_manager = SceneManager::default();
+        let mut scene_manager = SceneManager::new(&message_bus);
+        let mut initialized_update_stages = 0;
+        let mut initialized_render_stages = 0;
 
         // Run the platform pre did init function.
         match interface.systems_will_init(PlatformInitInput {
@@ -160,7 +210,23 @@ impl<P: PlatformInterface + PlatformInitalizationHandler> Into<EngineStateMachin
             render_stage_manager: RenderStageManager::from_slice(&mut render_stages),
         }) {
             EngineUpdateResult::Ok => (),
-            e => t_fatal!("Engine initialization failed: {:#?}", e),
+            e => {
+                // No stage has been initialized yet, so none of them is shut down.
+                update_stages.clear();
+                render_stages.clear();
+                return Err(Self::shut_down_initialized(
+                    uninit.shared,
+                    Initialized {
+                        update_stages,
+                        render_stages,
+                        scene_manager,
+                        update_thread_resources: update_thread_local_resources,
+                        render_stage_update_handlers,
+                    },
+                    interface,
+                    Self::platform_init_failed(e),
+                ));
+            }
         }
 
         // Run the did init function for all update stages.
@@ -168,7 +234,7 @@ impl<P: PlatformInterface + PlatformInitalizationHandler> Into<EngineStateMachin
             let (before, stage, after) = split_view.components_mut();
             let update_stage_manager = UpdateStageManager::from_slices(before, after);
             let render_stage_manager = RenderStageManager::from_slice(&mut render_stages);
-            match stage.engine_did_initialize(EngineDidInitInput {
+            let result = stage.engine_did_initialize(EngineDidInitInput {
                 platform_interface: interface,
                 scene_manager: &mut scene_manager,
                 resources: Arc::clone(&uninit.shared.resources),
@@ -176,12 +242,37 @@ impl<P: PlatformInterface + PlatformInitalizationHandler> Into<EngineStateMachin
                 dispatcher: Arc::clone(&dispatch_system),
                 update_stage_manager,
                 render_stage_manager,
+            });
+            let identifier = stage.identifier();
+            match policies.apply(identifier, StagePhase::Initialize, result, || {
+                stage.disable()
             }) {
-                EngineUpdateResult::Ok => Ok(()),
-                value => Err(value),
+                EngineUpdateResult::Ok => {
+                    initialized_update_stages += 1;
+                    Ok(())
+                }
+                value => Err(Self::restart_is_fatal(
+                    identifier,
+                    StagePhase::Initialize,
+                    value,
+                )),
             }
         }) {
-            t_fatal!("Engine initialization failed: {:#?}", e);
+            t_error!("Engine initialization failed.");
+            update_stages.truncate(initialized_update_stages);
+            render_stages.clear();
+            return Err(Self::shut_down_initialized(
+                uninit.shared,
+                Initialized {
+                    update_stages,
+                    render_stages,
+                    scene_manager,
+                    update_thread_resources: update_thread_local_resources,
+                    render_stage_update_handlers,
+                },
+                interface,
+                e,
+            ));
         }
 
         // Run the did init function for all render stages.
@@ -190,7 +281,7 @@ impl<P: PlatformInterface + PlatformInitalizationHandler> Into<EngineStateMachin
             let update_stage_manager = UpdateStageManager::from_slice(&mut update_stages);
             let render_stage_manager = RenderStageManager::from_slices(before, after);
 
-            match stage.engine_did_initialize(EngineDidInitInput {
+            let result = stage.engine_did_initialize(EngineDidInitInput {
                 platform_interface: interface,
                 scene_manager: &mut scene_manager,
                 resources: Arc::clone(&uninit.shared.resources),
@@ -198,13 +289,37 @@ impl<P: PlatformInterface + PlatformInitalizationHandler> Into<EngineStateMachin
                 dispatcher: Arc::clone(&dispatch_system),
                 update_stage_manager,
                 render_stage_manager,
+            });
+            let identifier = stage.identifier();
+            match policies.apply(identifier, StagePhase::Initialize, result, || {
+                stage.disable()
             }) {
-                EngineUpdateResult::Ok => Ok(()),
-                value => Err(value),
+                EngineUpdateResult::Ok => {
+                    initialized_render_stages += 1;
+                    Ok(())
+                }
+                value => Err(Self::restart_is_fatal(
+                    identifier,
+                    StagePhase::Initialize,
+                    value,
+                )),
             }
         }) {
-            t_fatal!("Engine initialization failed: {:#?}", e);
-        };
+            t_error!("Engine initialization failed.");
+            render_stages.truncate(initialized_render_stages);
+            return Err(Self::shut_down_initialized(
+                uninit.shared,
+                Initialized {
+                    update_stages,
+                    render_stages,
+                    scene_manager,
+                    update_thread_resources: update_thread_local_resources,
+                    render_stage_update_handlers,
+                },
+                interface,
+                e,
+            ));
+        }
 
         // Run the platform post did init function.
         match interface.systems_did_init(PlatformInitInput {
@@ -216,11 +331,24 @@ impl<P: PlatformInterface + PlatformInitalizationHandler> Into<EngineStateMachin
             render_stage_manager: RenderStageManager::from_slice(&mut render_stages),
         }) {
             EngineUpdateResult::Ok => (),
-            e => t_fatal!("Engine initialization failed: {:#?}", e),
+            e => {
+                return Err(Self::shut_down_initialized(
+                    uninit.shared,
+                    Initialized {
+                        update_stages,
+                        render_stages,
+                        scene_manager,
+                        update_thread_resources: update_thread_local_resources,
+                        render_stage_update_handlers,
+                    },
+                    interface,
+                    Self::platform_init_failed(e),
+                ))
+            }
         }
 
         t_info!("Initialized engine.");
-        EngineStateMachine {
+        Ok(EngineStateMachine {
             shared: uninit.shared,
             state: Initialized {
                 update_stages,
@@ -229,6 +357,53 @@ impl<P: PlatformInterface + PlatformInitalizationHandler> Into<EngineStateMachin
                 update_thread_resources: update_thread_local_resources,
                 render_stage_update_handlers,
             },
+        })
+    }
+
+    fn platform_init_failed(result: EngineUpdateResult) -> EngineUpdateResult {
+        t_error!("Engine initialization failed during platform initialization.");
+        match Self::restart_is_fatal("Platform", StagePhase::PlatformInitialize, result) {
+            EngineUpdateResult::Error(e) => {
+                EngineUpdateResult::Error(e.in_stage("Platform", StagePhase::PlatformInitialize))
+            }
+            result => result,
+        }
+    }
+
+    /// Restarting would initialize the same stages again, likely failing the same way.
+    /// Restarts requested during initialization are therefore turned into errors, stopping the engine.
+    fn restart_is_fatal(
+        stage: &'static str,
+        phase: StagePhase,
+        result: EngineUpdateResult,
+    ) -> EngineUpdateResult {
+        match result {
+            EngineUpdateResult::Restart => {
+                t_error!(
+                    "Cannot restart the engine during initialization of stage {}.",
+                    stage
+                );
+                EngineUpdateResult::Error(
+                    StageError::new("Restart requested during initialization.")
+                        .in_stage(stage, phase),
+                )
+            }
+            result => result,
+        }
+    }
+
+    /// Shuts down the stages of a partially initialized engine and returns the failure of its initialization.
+    fn shut_down_initialized(
+        shared: EngineSharedState,
+        initialized: Initialized,
+        interface: &mut dyn PlatformInterface,
+        result: EngineUpdateResult,
+    ) -> Box<(EngineSharedState, EngineUpdateResult)> {
+        let shared = EngineStateMachine {
+            shared,
+            state: initialized,
         }
+        .shutdown(interface);
+        Box::new((shared, result))
     }
 }
@@ -0,0 +1,137 @@
+use crate::engine_stages::*;
+use std::collections::BTreeSet;
+use utils::*;
+
+/// Groups update stages into waves. Stages within a wave do not conflict and may run in parallel,
+/// waves run one after another.
+#[derive(Debug, Default)]
+pub(super) struct UpdateStageSchedule {
+    /// Stage indices per wave, ordered by construction order.
+    waves: Vec<Vec<usize>>,
+    /// Declared dependencies per stage index.
+    dependencies: Vec<UpdateStageDependencies>,
+}
+
+impl UpdateStageSchedule {
+    pub fn new(stages: &[Box<dyn AnyUpdateStage>]) -> Self {
+        let identifiers = stages.iter().map(|e| e.identifier()).collect::<Vec<_>>();
+        let dependencies = stages.iter().map(|e| e.dependencies()).collect::<Vec<_>>();
+        let n_stages = stages.len();
+
+        // Explicit ordering constraints.
+        let mut edges = vec![BTreeSet::<usize>::new(); n_stages];
+        for (idx, deps) in dependencies.iter().enumerate() {
+            for identifier in deps.runs_before() {
+                Self::find_stages(&identifiers, identifier)
+                    .filter(|other| *other != idx)
+                    .for_each(|other| {
+                        edges[idx].insert(other);
+                    });
+            }
+            for identifier in deps.runs_after() {
+                Self::find_stages(&identifiers, identifier)
+                    .filter(|other| *other != idx)
+                    .for_each(|other| {
+                        edges[other].insert(idx);
+                    });
+            }
+        }
+
+        let order = match Self::topological_order(&edges) {
+            Some(order) => order,
+            None => {
+                t_warn!("Update stage ordering constraints contain a cycle, falling back to construction order.");
+                edges.iter_mut().for_each(|e| e.clear());
+                (0..n_stages).collect()
+            }
+        };
+        let mut position = vec![0; n_stages];
+        order
+            .iter()
+            .enumerate()
+            .for_each(|(pos, idx)| position[*idx] = pos);
+
+        // Conflicting stages keep the order decided above.
+        for a in 0..n_stages {
+            for b in (a + 1)..n_stages {
+                if dependencies[a].conflicts_with(&dependencies[b]) {
+                    if position[a] < position[b] {
+                        edges[a].insert(b);
+                    } else {
+                        edges[b].insert(a);
+                    }
+                }
+            }
+        }
+
+        // Every edge points forward in `order`, so a single pass assigns the waves.
+        let mut wave_of = vec![0; n_stages];
+        for idx in &order {
+            for next in &edges[*idx] {
+                wave_of[*next] = wave_of[*next].max(wave_of[*idx] + 1);
+            }
+        }
+        let n_waves = wave_of.iter().max().map_or(0, |e| e + 1);
+        let mut waves = vec![vec![]; n_waves];
+        for idx in 0..n_stages {
+            waves[wave_of[idx]].push(idx);
+        }
+
+        for (wave_idx, wave) in waves.iter().enumerate() {
+            t_info!(
+                "Update stage wave {}: {:?}",
+                wave_idx,
+                wave.iter().map(|e| identifiers[*e]).collect::<Vec<_>>()
+            );
+        }
+
+        Self {
+            waves,
+            dependencies,
+        }
+    }
+
+    pub fn waves(&self) -> &[Vec<usize>] {
+        &self.waves
+    }
+
+    pub fn dependencies(&self, stage_idx: usize) -> &UpdateStageDependencies {
+        &self.dependencies[stage_idx]
+    }
+
+    fn find_stages<'a>(
+        identifiers: &'a [&'static str],
+        identifier: &'a str,
+    ) -> impl Iterator<Item = usize> + 'a {
+        identifiers
+            .iter()
+            .enumerate()
+            .filter(move |(_, e)| **e == identifier)
+            .map(|(idx, _)| idx)
+    }
+
+    /// Kahn's algorithm, preferring construction order among stages which are ready.
+    /// Returns `None` if the graph contains a cycle.
+    fn topological_order(edges: &[BTreeSet<usize>]) -> Option<Vec<usize>> {
+        let mut in_degree = vec![0; edges.len()];
+        edges
+            .iter()
+            .flatten()
+            .for_each(|next| in_degree[*next] += 1);
+
+        let mut ready = (0..edges.len())
+            .filter(|idx| in_degree[*idx] == 0)
+            .collect::<BTreeSet<_>>();
+        let mut order = Vec::with_capacity(edges.len());
+        while let Some(idx) = ready.pop_first() {
+            order.push(idx);
+            for next in &edges[idx] {
+                in_degree[*next] -= 1;
+                if in_degree[*next] == 0 {
+                    ready.insert(*next);
+                }
+            }
+        }
+        (order.len() == edges.len()).then_some(order)
+    }
+}
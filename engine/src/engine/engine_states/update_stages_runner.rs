@@ -1,15 +1,23 @@
 use super::*;
-use crate::resource_manager::ThreadLocalResourceManager;
-use crate::scene_manager::SceneManager;
-use crate::{engine::result::*, engine_stages::*};
+use crate::engine::{clock::Clock, frame_stats::EngineFrameStats};
+use crate::input::InputManager;
+use crate::message_bus::{AnyMessageRegisterer, MessageBus, MessageHandlerType};
+use crate::render_world::RenderWorld;
+use crate::resource_manager::{EngineResourceManager, ThreadLocalResourceManager};
+use crate::scene_manager::{SceneComponentRegistry, SceneManager, TransformHierarchy};
+use crate::PlatformInterface;
+use crate::{engine::result::*, engine::stage_error::*, engine_stages::*};
 use std::sync::{Arc, Condvar, Mutex};
 use utils::dispatcher::Dispatcher;
+use utils::*;
 
 pub(super) struct UpdateStagesThreadedState {
     scene_manager: SceneManager,
     thread_local_resources: ThreadLocalResourceManager,
     /// The update stages.
     stages: Vec<Box<dyn AnyUpdateStage>>,
+    /// Decides which update stages run in parallel.
+    schedule: UpdateStageSchedule,
     /// Last result of the threaded loop.
     /// If None, it has not yet been executed.
     last_result: Option<EngineUpdateResult>,
@@ -17,9 +25,40 @@ pub(super) struct UpdateStagesThreadedState {
     render_stage_update_thread_handlers: Vec<Box<dyn AnyRenderStageUpdateThreadHandler>>,
 }
 
+/// State shared by all update stages during an update tick.
+struct UpdateTickContext<'a> {
+    policies: &'a StageErrorPolicies,
+    resources: &'a Arc<EngineResourceManager>,
+    dispatcher: &'a Arc<Dispatcher>,
+    clock: &'a dyn Clock,
+    stats: &'a EngineFrameStats,
+    update_tick_rate: u32,
+    update_counter_past_second: u64,
+}
+
+impl UpdateTickContext<'_> {
+    fn stage_input<'a>(
+        &self,
+        scene_manager: &'a mut SceneManager,
+        thread_local_resources: &'a mut ThreadLocalResourceManager,
+    ) -> UpdateStageUpdateInput<'a> {
+        UpdateStageUpdateInput::new(
+            self.resources.clone(),
+            self.dispatcher.clone(),
+            scene_manager,
+            thread_local_resources,
+            self.update_tick_rate,
+            self.update_counter_past_second,
+        )
+    }
+}
+
 pub(super) struct UpdateStagesRunner {
     pub(super) threaded_state: Arc<(Mutex<(bool, UpdateStagesThreadedState)>, Condvar)>,
     dispatch_system: Arc<Dispatcher>,
+    render_world: Arc<RenderWorld>,
+    /// Whether an update job was spawned whose result has not been collected yet.
+    update_in_flight: bool,
 }
 
 impl UpdateStagesRunner {
@@ -29,6 +68,7 @@ impl UpdateStagesRunner {
         render_stage_update_thread_handlers: Vec<Box<dyn AnyRenderStageUpdateThreadHandler>>,
         thread_local_resources: ThreadLocalResourceManager,
         dispatch_system: Arc<Dispatcher>,
+        render_world: Arc<RenderWorld>,
     ) -> Self {
         Self {
             threaded_state: Arc::new((
@@ -37,6 +77,7 @@ impl UpdateStagesRunner {
                     UpdateStagesThreadedState {
                         scene_manager,
                         thread_local_resources,
+                        schedule: UpdateStageSchedule::new(&stages),
                         stages,
                         last_result: None,
                         render_stage_update_thread_handlers,
@@ -45,6 +86,8 @@ impl UpdateStagesRunner {
                 Condvar::new(),
             )),
             dispatch_system,
+            render_world,
+            update_in_flight: false,
         }
     }
 
@@ -55,111 +98,482 @@ impl UpdateStagesRunner {
         let update_counter_past_second = shared_state.internal_resources.timings.update_counter;
 
         // Possibly wait for previous iteration, getting it's message as well.
-        let previous_message = self.wait_for_previous_update_completed();
+        let previous_message = self.synchronize();
 
-        if previous_message != EngineUpdateResult::Restart {
+        if !matches!(
+            previous_message,
+            EngineUpdateResult::Restart | EngineUpdateResult::Error(_)
+        ) {
             // Enqueue new  update job!
             let state = Arc::clone(&self.threaded_state);
             let resources = shared_state.resources.clone();
             let dispatcher = Arc::clone(&self.dispatch_system);
+            let clock = Arc::clone(&shared_state.internal_resources.timings.clock);
+            let stats = Arc::clone(&shared_state.internal_resources.timings.stats);
+            let render_world = Arc::clone(&self.render_world);
+            let policies = Arc::clone(&shared_state.internal_resources.stage_error_policies);
+            let scene_components = shared_state
+                .resources
+                .get_resource::<SceneComponentRegistry>();
+            let update_tick = shared_state.internal_resources.timings.update_tick;
+            shared_state
+                .resources
+                .resource::<MessageBus>()
+                .begin_update_tick(update_tick);
+            shared_state
+                .resources
+                .resource::<InputManager>()
+                .begin_tick(update_tick);
             self.dispatch_system.spawn(move || {
                 let &(ref mtx, ref cnd) = &*state;
 
                 let mut guard = mtx.lock().unwrap();
-                let threaded_state = &mut guard.1;
-
-                // Update events
-                threaded_state.stages.iter_mut().for_each(|s| {
-                    s.process_events();
-                });
-                let scene_manager = &mut threaded_state.scene_manager;
-                let thread_local_resources = &mut threaded_state.thread_local_resources;
-                threaded_state
-                    .render_stage_update_thread_handlers
-                    .iter_mut()
-                    .for_each(|e| {
-                        e.process_events(UpdateStageUpdateInput::new(
-                            resources.clone(),
-                            dispatcher.clone(),
-                            scene_manager,
-                            thread_local_resources,
-                            update_tick_rate,
-                            update_counter_past_second,
-                        ))
-                    });
-
-                // Update render stage pre update fns.
-                for update_handler in &mut threaded_state.render_stage_update_thread_handlers {
-                    let msg = update_handler.pre_update(UpdateStageUpdateInput::new(
-                        resources.clone(),
-                        dispatcher.clone(),
-                        scene_manager,
-                        thread_local_resources,
-                        update_tick_rate,
-                        update_counter_past_second,
-                    ));
-                    if msg == EngineUpdateResult::Ok {
-                        continue;
-                    };
-                    threaded_state.last_result = Some(msg);
-                    return;
+                let update_start = clock.now();
+                if let Some(components) = &scene_components {
+                    guard
+                        .1
+                        .scene_manager
+                        .update_streaming(components, &dispatcher);
                 }
-
-                // Update
-                for system in &mut threaded_state.stages {
-                    let msg = system.update(UpdateStageUpdateInput::new(
-                        resources.clone(),
-                        dispatcher.clone(),
-                        scene_manager,
-                        thread_local_resources,
-                        update_tick_rate,
-                        update_counter_past_second,
-                    ));
-                    if msg == EngineUpdateResult::Ok {
-                        continue;
-                    }
-                    threaded_state.last_result = Some(msg);
-                    return;
-                }
-
-                // Update render stage post update fns.
-                for update_handler in &mut threaded_state.render_stage_update_thread_handlers {
-                    let msg = update_handler.post_update(UpdateStageUpdateInput::new(
-                        resources.clone(),
-                        dispatcher.clone(),
-                        scene_manager,
-                        thread_local_resources,
-                        update_tick_rate,
-                        update_counter_past_second,
-                    ));
-                    if msg == EngineUpdateResult::Ok {
-                        continue;
-                    };
-                    threaded_state.last_result = Some(msg);
-                    return;
+                let context = UpdateTickContext {
+                    policies: &policies,
+                    resources: &resources,
+                    dispatcher: &dispatcher,
+                    clock: clock.as_ref(),
+                    stats: &stats,
+                    update_tick_rate,
+                    update_counter_past_second,
+                };
+                let result = Self::run_update_stages(&mut guard.1, &context);
+                if result.is_ok() {
+                    render_world.extract(&guard.1.scene_manager);
                 }
-
+                stats.record_update_time(clock.now().duration_since(update_start));
+                guard.1.last_result = Some(result);
                 guard.0 = true;
                 cnd.notify_one();
             });
+            self.update_in_flight = true;
         }
 
         return previous_message;
     }
 
-    fn wait_for_previous_update_completed(&mut self) -> EngineUpdateResult {
-        let mut previous_message = EngineUpdateResult::Ok;
+    /// Waits for the in-flight update job to complete, returning its result.
+    /// Publishes the render snapshot extracted by the job.
+    /// Returns immediately if no update job is in flight.
+    pub fn synchronize(&mut self) -> EngineUpdateResult {
+        if !self.update_in_flight {
+            return EngineUpdateResult::Ok;
+        }
         let &(ref mtx, ref cnd) = &*self.threaded_state;
         let mut guard = mtx.lock().unwrap();
-        if let Some(_) = &guard.1.last_result {
-            while guard.0 == false {
-                guard = cnd.wait(guard).unwrap();
+        while guard.0 == false {
+            guard = cnd.wait(guard).unwrap();
+        }
+        guard.0 = false;
+        self.update_in_flight = false;
+        self.render_world.publish();
+        guard.1.last_result.take().unwrap_or(EngineUpdateResult::Ok)
+    }
+
+    /// Notifies all update stages, in stage order, that the engine will suspend.
+    /// Runs on the calling thread, the in-flight update job must have been synchronized.
+    pub fn engine_will_suspend(&mut self, shared_state: &EngineSharedState) -> EngineUpdateResult {
+        self.for_each_stage_until_error(shared_state, StagePhase::Suspend, |stage, input| {
+            stage.engine_will_suspend(input)
+        })
+    }
+
+    /// Notifies all update stages, in stage order, that the engine will resume.
+    /// Runs on the calling thread.
+    pub fn engine_will_resume(&mut self, shared_state: &EngineSharedState) -> EngineUpdateResult {
+        self.for_each_stage_until_error(shared_state, StagePhase::Resume, |stage, input| {
+            stage.engine_will_resume(input)
+        })
+    }
+
+    /// Drops the update thread handlers of all render stages, in reverse construction order.
+    /// The in-flight update job must have been synchronized.
+    pub fn drop_render_stage_update_thread_handlers(&mut self) {
+        debug_assert!(!self.update_in_flight);
+        let &(ref mtx, _) = &*self.threaded_state;
+        let mut guard = mtx.lock().unwrap();
+        while let Some(handler) = guard.1.render_stage_update_thread_handlers.pop() {
+            drop(handler);
+        }
+    }
+
+    /// Executes the shutdown hook of every update stage and drops it, in reverse construction order.
+    /// The in-flight update job must have been synchronized.
+    pub fn shutdown_update_stages(&mut self, shared_state: &EngineSharedState) {
+        debug_assert!(!self.update_in_flight);
+        let &(ref mtx, _) = &*self.threaded_state;
+        let mut guard = mtx.lock().unwrap();
+        let threaded_state = &mut guard.1;
+        while let Some(stage) = threaded_state.stages.pop() {
+            shut_down_update_stage(
+                stage,
+                &shared_state.resources,
+                &self.dispatch_system,
+                &mut threaded_state.scene_manager,
+                &mut threaded_state.thread_local_resources,
+                &shared_state.internal_resources.timings,
+            );
+        }
+    }
+
+    /// Applies queued stage changes in order, see [`StageQueue`].
+    /// Added stages are initialized, applying their error policy. Stages failing to initialize are not added.
+    /// Returns the first result other than [`EngineUpdateResult::Ok`], after applying all changes.
+    /// The in-flight update job must have been synchronized.
+    pub fn apply_stage_changes(
+        &mut self,
+        changes: Vec<StageChange>,
+        shared_state: &EngineSharedState,
+        render_stages: &mut Vec<Box<dyn AnyRenderStage>>,
+        interface: &mut dyn PlatformInterface,
+    ) -> EngineUpdateResult {
+        debug_assert!(!self.update_in_flight);
+        let timings = &shared_state.internal_resources.timings;
+        let policies = &shared_state.internal_resources.stage_error_policies;
+        let resources = &shared_state.resources;
+        let message_bus = match resources.get_resource::<MessageBus>() {
+            Some(v) => v,
+            None => {
+                t_fatal!("Internal engine inconsistency! MessageBus should be added to the resource systems!");
+            }
+        };
+
+        let &(ref mtx, _) = &*self.threaded_state;
+        let mut guard = mtx.lock().unwrap();
+        let threaded_state = &mut guard.1;
+        let mut result = EngineUpdateResult::Ok;
+        for change in changes {
+            let change_result = match change {
+                StageChange::AddUpdate(constructor) => {
+                    let mut stage = constructor(UpdateStageConstructorInput::new(
+                        interface,
+                        Arc::clone(resources),
+                    ));
+                    stage.register_message_handlers(AnyMessageRegisterer::new(
+                        &message_bus,
+                        MessageHandlerType::Update,
+                    ));
+                    let init_result = stage.engine_did_initialize(EngineDidInitInput {
+                        platform_interface: interface,
+                        update_stage_manager: UpdateStageManager::from_slice(
+                            &mut threaded_state.stages,
+                        ),
+                        render_stage_manager: RenderStageManager::from_slice(render_stages),
+                        resources: Arc::clone(resources),
+                        scene_manager: &mut threaded_state.scene_manager,
+                        update_thread_resources: &mut threaded_state.thread_local_resources,
+                        dispatcher: Arc::clone(&self.dispatch_system),
+                    });
+                    let identifier = stage.identifier();
+                    let init_result =
+                        policies.apply(identifier, StagePhase::Initialize, init_result, || {
+                            stage.disable()
+                        });
+                    if init_result.is_ok() {
+                        threaded_state.stages.push(stage);
+                        threaded_state.schedule = UpdateStageSchedule::new(&threaded_state.stages);
+                        t_info!("Added update stage: {}", identifier);
+                    }
+                    init_result
+                }
+                StageChange::RemoveUpdate(type_id, identifier) => {
+                    match threaded_state
+                        .stages
+                        .iter()
+                        .position(|e| e.stage_as_any().type_id() == type_id)
+                    {
+                        Some(idx) => {
+                            let stage = threaded_state.stages.remove(idx);
+                            threaded_state.schedule =
+                                UpdateStageSchedule::new(&threaded_state.stages);
+                            shut_down_update_stage(
+                                stage,
+                                resources,
+                                &self.dispatch_system,
+                                &mut threaded_state.scene_manager,
+                                &mut threaded_state.thread_local_resources,
+                                timings,
+                            );
+                            t_info!("Removed update stage: {}", identifier);
+                        }
+                        None => t_warn!(
+                            "Cannot remove update stage {}, it is not running.",
+                            identifier
+                        ),
+                    }
+                    EngineUpdateResult::Ok
+                }
+                StageChange::AddRender(constructor) => {
+                    let mut stage = constructor(RenderStageConstructorInput::new(
+                        interface,
+                        Arc::clone(resources),
+                    ));
+                    stage.register_message_handlers(AnyMessageRegisterer::new(
+                        &message_bus,
+                        MessageHandlerType::Render,
+                    ));
+                    let handler = stage.create_update_thread_handler(
+                        RenderStageUpdateThreadHandlerCreateInfo::new(
+                            &mut threaded_state.thread_local_resources,
+                        ),
+                        AnyMessageRegisterer::new(&message_bus, MessageHandlerType::Update),
+                    );
+                    let init_result = stage.engine_did_initialize(EngineDidInitInput {
+                        platform_interface: interface,
+                        update_stage_manager: UpdateStageManager::from_slice(
+                            &mut threaded_state.stages,
+                        ),
+                        render_stage_manager: RenderStageManager::from_slice(render_stages),
+                        resources: Arc::clone(resources),
+                        scene_manager: &mut threaded_state.scene_manager,
+                        update_thread_resources: &mut threaded_state.thread_local_resources,
+                        dispatcher: Arc::clone(&self.dispatch_system),
+                    });
+                    let identifier = stage.identifier();
+                    let init_result =
+                        policies.apply(identifier, StagePhase::Initialize, init_result, || {
+                            stage.disable()
+                        });
+                    if init_result.is_ok() {
+                        render_stages.push(stage);
+                        threaded_state
+                            .render_stage_update_thread_handlers
+                            .push(handler);
+                        t_info!("Added render stage: {}", identifier);
+                    }
+                    init_result
+                }
+                StageChange::RemoveRender(type_id, identifier) => {
+                    match render_stages
+                        .iter()
+                        .position(|e| e.stage_as_any().type_id() == type_id)
+                    {
+                        Some(idx) => {
+                            // Handlers are stored in the same order as their render stages.
+                            drop(
+                                threaded_state
+                                    .render_stage_update_thread_handlers
+                                    .remove(idx),
+                            );
+                            let stage = render_stages.remove(idx);
+                            shut_down_render_stage(stage, resources, interface, timings);
+                            t_info!("Removed render stage: {}", identifier);
+                        }
+                        None => t_warn!(
+                            "Cannot remove render stage {}, it is not running.",
+                            identifier
+                        ),
+                    }
+                    EngineUpdateResult::Ok
+                }
+            };
+            if result.is_ok() {
+                result = change_result;
+            }
+        }
+        result
+    }
+
+    fn for_each_stage_until_error(
+        &mut self,
+        shared_state: &EngineSharedState,
+        phase: StagePhase,
+        mut f: impl FnMut(&mut dyn AnyUpdateStage, UpdateStageUpdateInput) -> EngineUpdateResult,
+    ) -> EngineUpdateResult {
+        let policies = &shared_state.internal_resources.stage_error_policies;
+        debug_assert!(!self.update_in_flight);
+        let update_tick_rate = shared_state.internal_resources.timings.update_tick_rate;
+        let update_counter_past_second = shared_state.internal_resources.timings.update_counter;
+
+        let &(ref mtx, _) = &*self.threaded_state;
+        let mut guard = mtx.lock().unwrap();
+        let threaded_state = &mut guard.1;
+        for (idx, stage) in threaded_state.stages.iter_mut().enumerate() {
+            let scene_target = threaded_state.schedule.dependencies(idx).scene_target();
+            let result = f(
+                stage.as_mut(),
+                UpdateStageUpdateInput::new(
+                    shared_state.resources.clone(),
+                    self.dispatch_system.clone(),
+                    &mut threaded_state.scene_manager,
+                    &mut threaded_state.thread_local_resources,
+                    update_tick_rate,
+                    update_counter_past_second,
+                )
+                .with_scene_target(scene_target.clone()),
+            );
+            let identifier = stage.identifier();
+            let msg = policies.apply(identifier, phase, result, || stage.disable());
+            if msg != EngineUpdateResult::Ok {
+                return msg;
+            }
+        }
+        EngineUpdateResult::Ok
+    }
+
+    fn run_update_stages(
+        threaded_state: &mut UpdateStagesThreadedState,
+        context: &UpdateTickContext,
+    ) -> EngineUpdateResult {
+        let policies = context.policies;
+        // Update events
+        threaded_state.stages.iter_mut().for_each(|s| {
+            s.process_events();
+        });
+        let scene_manager = &mut threaded_state.scene_manager;
+        let thread_local_resources = &mut threaded_state.thread_local_resources;
+        threaded_state
+            .render_stage_update_thread_handlers
+            .iter_mut()
+            .for_each(|e| {
+                e.process_events(context.stage_input(scene_manager, thread_local_resources))
+            });
+        context
+            .resources
+            .resource::<MessageBus>()
+            .receive_subscribed_messages(MessageHandlerType::Update);
+
+        // Update render stage pre update fns.
+        for update_handler in &mut threaded_state.render_stage_update_thread_handlers {
+            let result = update_handler
+                .pre_update(context.stage_input(scene_manager, thread_local_resources));
+            let identifier = update_handler.identifier();
+            let msg = policies.apply(identifier, StagePhase::PreUpdate, result, || {
+                update_handler.disable()
+            });
+            if msg != EngineUpdateResult::Ok {
+                return msg;
+            };
+        }
+
+        // Update, running the stages within each wave in parallel.
+        for wave in threaded_state.schedule.waves() {
+            let msg = Self::run_update_wave(
+                wave,
+                &threaded_state.schedule,
+                &mut threaded_state.stages,
+                scene_manager,
+                thread_local_resources,
+                context,
+            );
+            if msg != EngineUpdateResult::Ok {
+                return msg;
             }
-            previous_message = *guard.1.last_result.as_ref().unwrap();
-            //Guard boolean is true here
-            guard.0 = false;
-            guard.1.last_result = None;
         }
-        previous_message
+
+        // Propagate the transforms moved by the update stages through the hierarchies of all scenes.
+        for scene in scene_manager.scenes_mut() {
+            TransformHierarchy::propagate(scene.registry_mut());
+        }
+
+        // Update render stage post update fns.
+        for update_handler in &mut threaded_state.render_stage_update_thread_handlers {
+            let result = update_handler
+                .post_update(context.stage_input(scene_manager, thread_local_resources));
+            let identifier = update_handler.identifier();
+            let msg = policies.apply(identifier, StagePhase::PostUpdate, result, || {
+                update_handler.disable()
+            });
+            if msg != EngineUpdateResult::Ok {
+                return msg;
+            };
+        }
+
+        EngineUpdateResult::Ok
+    }
+
+    /// Runs the update of all stages in the wave, granting each stage only the accesses it declared.
+    /// Applies the error policy of each stage and returns the first result other than [`EngineUpdateResult::Ok`] in stage order.
+    fn run_update_wave(
+        wave: &[usize],
+        schedule: &UpdateStageSchedule,
+        stages: &mut [Box<dyn AnyUpdateStage>],
+        scene_manager: &mut SceneManager,
+        thread_local_resources: &mut ThreadLocalResourceManager,
+        context: &UpdateTickContext,
+    ) -> EngineUpdateResult {
+        let scene_manager_accesses = wave
+            .iter()
+            .map(|idx| schedule.dependencies(*idx).access::<SceneManager>())
+            .collect::<Vec<_>>();
+        let thread_local_resources_accesses = wave
+            .iter()
+            .map(|idx| {
+                schedule
+                    .dependencies(*idx)
+                    .access::<ThreadLocalResourceManager>()
+            })
+            .collect::<Vec<_>>();
+        let mut jobs = stages
+            .iter_mut()
+            .enumerate()
+            .filter(|(idx, _)| wave.contains(idx))
+            .zip(split_stage_access(scene_manager, &scene_manager_accesses))
+            .zip(split_exclusive_stage_access(
+                thread_local_resources,
+                &thread_local_resources_accesses,
+            ))
+            .map(|(((idx, stage), scene_manager), thread_local_resources)| {
+                let input = UpdateStageUpdateInput::with_access(
+                    context.resources.clone(),
+                    context.dispatcher.clone(),
+                    scene_manager,
+                    thread_local_resources,
+                    context.update_tick_rate,
+                    context.update_counter_past_second,
+                )
+                .with_scene_target(schedule.dependencies(idx).scene_target().clone());
+                (stage, input)
+            })
+            .collect::<Vec<_>>();
+
+        let (clock, stats) = (context.clock, context.stats);
+        let run_stage = |stage: &mut Box<dyn AnyUpdateStage>, input: UpdateStageUpdateInput| {
+            let stage_start = clock.now();
+            let msg = stage.update(input);
+            stats.record_update_stage_time(
+                stage.identifier(),
+                clock.now().duration_since(stage_start),
+            );
+            msg
+        };
+
+        let mut results = vec![EngineUpdateResult::Ok; jobs.len()];
+        if jobs.len() == 1 {
+            let (stage, input) = jobs.pop().unwrap();
+            results[0] = run_stage(stage, input);
+        } else {
+            let run_stage = &run_stage;
+            context.dispatcher.scope(|scope| {
+                for ((stage, input), result) in jobs.into_iter().zip(results.iter_mut()) {
+                    scope.spawn(move |_| *result = run_stage(stage, input));
+                }
+            });
+        }
+        // Every stage of the wave has its policy applied, even after an earlier one failed.
+        let results: Vec<_> = stages
+            .iter_mut()
+            .enumerate()
+            .filter(|(idx, _)| wave.contains(idx))
+            .map(|(_, stage)| stage)
+            .zip(results)
+            .map(|(stage, result)| {
+                let identifier = stage.identifier();
+                context
+                    .policies
+                    .apply(identifier, StagePhase::Update, result, || stage.disable())
+            })
+            .collect();
+        results
+            .into_iter()
+            .find(|e| *e != EngineUpdateResult::Ok)
+            .unwrap_or(EngineUpdateResult::Ok)
     }
 }
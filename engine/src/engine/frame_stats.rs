@@ -0,0 +1,191 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::RwLock;
+use std::time::Duration;
+
+/// Settings for the collection of [`EngineFrameStats`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EngineFrameStatsSettings {
+    /// Amount of samples kept in each rolling window.
+    pub window_size: usize,
+    /// Logs a summary of the stats once per second.
+    pub log_every_second: bool,
+}
+
+impl Default for EngineFrameStatsSettings {
+    fn default() -> Self {
+        Self {
+            window_size: 240,
+            log_every_second: false,
+        }
+    }
+}
+
+/// Rolling window over the most recent duration samples.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DurationWindow {
+    samples: VecDeque<Duration>,
+    capacity: usize,
+}
+
+impl DurationWindow {
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            samples: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Adds a sample, evicting the oldest one when the window is full.
+    pub fn push(&mut self, sample: Duration) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Iterates the samples from oldest to newest.
+    pub fn iter(&self) -> impl Iterator<Item = &Duration> {
+        self.samples.iter()
+    }
+
+    pub fn last(&self) -> Option<Duration> {
+        self.samples.back().copied()
+    }
+
+    pub fn min(&self) -> Option<Duration> {
+        self.samples.iter().min().copied()
+    }
+
+    pub fn max(&self) -> Option<Duration> {
+        self.samples.iter().max().copied()
+    }
+
+    pub fn average(&self) -> Option<Duration> {
+        if self.samples.is_empty() {
+            return None;
+        }
+        Some(self.samples.iter().sum::<Duration>() / (self.samples.len() as u32))
+    }
+
+    /// Nearest-rank percentile, `percentile` ranges from 0 to 100.
+    pub fn percentile(&self, percentile: f32) -> Option<Duration> {
+        if self.samples.is_empty() {
+            return None;
+        }
+        let mut sorted = self.samples.iter().copied().collect::<Vec<_>>();
+        sorted.sort_unstable();
+        let rank = ((percentile.clamp(0.0, 100.0) / 100.0) * sorted.len() as f32).ceil() as usize;
+        Some(sorted[rank.max(1) - 1])
+    }
+}
+
+/// Snapshot of the statistics gathered by the game loop.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FrameStats {
+    /// Time spent per frame, excluding time spent sleeping by the frame limiter.
+    pub frame_times: DurationWindow,
+    /// Time spent sleeping by the frame limiter per frame.
+    pub sleep_times: DurationWindow,
+    /// Time spent per update tick on the update thread.
+    pub update_times: DurationWindow,
+    /// Time spent per update tick in each update stage, keyed by stage identifier.
+    pub update_stage_times: BTreeMap<String, DurationWindow>,
+    /// Time spent per frame rendering in each render stage, keyed by stage identifier.
+    pub render_stage_times: BTreeMap<String, DurationWindow>,
+    /// Amount of update ticks executed during the last full second.
+    pub updates_last_second: u64,
+    /// Amount of frames rendered during the last full second.
+    pub frames_last_second: u64,
+}
+
+/// Engine resource containing rolling statistics of the game loop.
+/// Can be queried by stages through the [`crate::resource_manager::EngineResourceManager`].
+#[derive(Debug, Default)]
+pub struct EngineFrameStats {
+    window_size: usize,
+    stats: RwLock<FrameStats>,
+}
+
+impl EngineFrameStats {
+    pub fn new(window_size: usize) -> Self {
+        Self {
+            window_size,
+            stats: RwLock::new(FrameStats {
+                frame_times: DurationWindow::with_capacity(window_size),
+                sleep_times: DurationWindow::with_capacity(window_size),
+                update_times: DurationWindow::with_capacity(window_size),
+                ..Default::default()
+            }),
+        }
+    }
+
+    /// Returns a copy of the current statistics.
+    pub fn snapshot(&self) -> FrameStats {
+        self.stats.read().unwrap().clone()
+    }
+
+    /// Gives read access to the current statistics without copying them.
+    pub fn read<R>(&self, f: impl FnOnce(&FrameStats) -> R) -> R {
+        f(&self.stats.read().unwrap())
+    }
+
+    pub fn record_frame_time(&self, duration: Duration) {
+        self.stats.write().unwrap().frame_times.push(duration);
+    }
+
+    pub fn record_sleep_time(&self, duration: Duration) {
+        self.stats.write().unwrap().sleep_times.push(duration);
+    }
+
+    pub fn record_update_time(&self, duration: Duration) {
+        self.stats.write().unwrap().update_times.push(duration);
+    }
+
+    pub fn record_update_stage_time(&self, identifier: &str, duration: Duration) {
+        let mut stats = self.stats.write().unwrap();
+        Self::stage_window(&mut stats.update_stage_times, identifier, self.window_size)
+            .push(duration);
+    }
+
+    pub fn record_render_stage_time(&self, identifier: &str, duration: Duration) {
+        let mut stats = self.stats.write().unwrap();
+        Self::stage_window(&mut stats.render_stage_times, identifier, self.window_size)
+            .push(duration);
+    }
+
+    pub fn record_second(&self, updates: u64, frames: u64) {
+        let mut stats = self.stats.write().unwrap();
+        stats.updates_last_second = updates;
+        stats.frames_last_second = frames;
+    }
+
+    fn stage_window<'a>(
+        windows: &'a mut BTreeMap<String, DurationWindow>,
+        identifier: &str,
+        window_size: usize,
+    ) -> &'a mut DurationWindow {
+        if !windows.contains_key(identifier) {
+            windows.insert(
+                String::from(identifier),
+                DurationWindow::with_capacity(window_size),
+            );
+        }
+        windows.get_mut(identifier).unwrap()
+    }
+}
@@ -1,12 +1,23 @@
+use super::clock::Clock;
+use super::frame_stats::EngineFrameStats;
+use std::sync::Arc;
 use std::time::*;
 use utils::*;
 pub struct EngineGameloopTimer {
+    // Source of time, may be replaced for deterministic stepping.
+    pub clock: Arc<dyn Clock>,
+    // Rolling statistics, shared with the engine resources.
+    pub stats: Arc<EngineFrameStats>,
+    // Whether a summary of the stats is logged each second.
+    pub log_frame_stats: bool,
     // Amount of ticks the update is updated each second.
     pub update_tick_rate: u32,
     // Amount of frames that may be skipped.
     pub max_skipped_frames: u32,
     // Max framerate that the engine will be capped to.
     pub max_frame_rate: Option<u32>,
+    // Whether update ticks are suspended while frames are still rendered.
+    pub paused: bool,
 
     pub previous_frame_instant: Instant,
     pub previous_second_instant: Instant,
@@ -21,6 +32,8 @@ pub struct EngineGameloopTimer {
     pub negative_sleep_time: Duration,
     // Counts total amount of update ticks in the past second.
     pub update_counter: u64,
+    // Counts total amount of update ticks since the engine started.
+    pub update_tick: u64,
     // Counts total amount of frames in the past second.
     pub frame_counter: u64,
     // Progress towards next update tick from 0 to 1.
@@ -31,22 +44,17 @@ pub struct EngineGameloopTimer {
 }
 
 impl EngineGameloopTimer {
-    pub fn frame_start(&mut self) {
-        let fixed_update_step_duration =
-            Duration::from_millis(1000) / (self.update_tick_rate as u32);
+    pub fn fixed_update_step_duration(&self) -> Duration {
+        Duration::from_millis(1000) / (self.update_tick_rate as u32)
+    }
 
-        self.frame_start_instant = Instant::now();
+    pub fn frame_start(&mut self) {
+        self.frame_start_instant = self.clock.now();
         self.current_delta_time = self
             .frame_start_instant
             .duration_since(self.previous_frame_instant);
         self.accumulated_time += self.current_delta_time;
 
-        if self.frame_start_instant > self.last_fixed_update_instant {
-            let delta = self.frame_start_instant - self.last_fixed_update_instant;
-            self.alpha =
-                ((delta.as_nanos() as f64) / (fixed_update_step_duration.as_nanos() as f64)) as f32;
-        }
-
         if let Some(max_frame_rate) = self.max_frame_rate {
             // Frame limiting happens here.
             let targeted_update_step_duration =
@@ -58,18 +66,21 @@ impl EngineGameloopTimer {
             }
             let delta_time_without_sleep = self.current_delta_time - self.previous_sleep_time;
             self.total_frame_time_last_second += delta_time_without_sleep;
+            self.stats.record_frame_time(delta_time_without_sleep);
             if targeted_update_step_duration > delta_time_without_sleep {
                 // Our frame took less time than we want, so we need to sleep.
                 // Also correct for previous frame sleep time, since that's included in the delta_time.
                 let left_over = targeted_update_step_duration - delta_time_without_sleep;
                 if left_over > self.negative_sleep_time {
                     let sleep_time = left_over - self.negative_sleep_time;
-                    std::thread::sleep(sleep_time);
+                    self.clock.sleep(sleep_time);
+                    self.stats.record_sleep_time(sleep_time);
                     self.total_sleep_time_last_second += sleep_time;
                     self.previous_sleep_time = sleep_time;
                     self.negative_sleep_time = Duration::new(0, 0);
                 } else {
-                    std::thread::sleep(left_over);
+                    self.clock.sleep(left_over);
+                    self.stats.record_sleep_time(left_over);
                     self.total_sleep_time_last_second += left_over;
                     self.negative_sleep_time -= left_over;
                     self.previous_sleep_time = left_over;
@@ -79,14 +90,30 @@ impl EngineGameloopTimer {
                 self.negative_sleep_time +=
                     delta_time_without_sleep - targeted_update_step_duration;
                 self.previous_sleep_time = Duration::new(0, 0);
+                self.stats.record_sleep_time(Duration::new(0, 0));
             }
         } else {
             self.total_frame_time_last_second += self.current_delta_time;
+            self.stats.record_frame_time(self.current_delta_time);
         }
 
         self.previous_frame_instant = self.frame_start_instant;
     }
 
+    /// Updates the progress towards the next update tick from the time left in the accumulator.
+    /// Must be called after the update ticks of a frame were executed.
+    pub fn update_alpha(&mut self) {
+        let fixed_update_step_duration = self.fixed_update_step_duration();
+        self.alpha = ((self.accumulated_time.as_nanos() as f64)
+            / (fixed_update_step_duration.as_nanos() as f64))
+            .min(1.0) as f32;
+    }
+
+    /// Restarts the frame timing from the current instant, dropping any time that passed since the last frame.
+    pub fn discard_elapsed_time(&mut self) {
+        self.previous_frame_instant = self.clock.now();
+    }
+
     pub fn frame_end(&mut self) {
         self.frame_counter += 1;
 
@@ -95,23 +122,10 @@ impl EngineGameloopTimer {
             .duration_since(self.previous_second_instant)
             > Duration::from_millis(1000)
         {
-            t_info!("Total update count: {}", self.update_counter);
-            t_info!("Total frame count: {}", self.frame_counter);
-            t_info!(
-                "Avg. frametime: {:#?}",
-                self.total_frame_time_last_second / (self.frame_counter as u32)
-            );
-
-            if self.max_frame_rate.is_some() {
-                let sum = self.total_sleep_time_last_second + self.total_frame_time_last_second;
-                let perc =
-                    self.total_sleep_time_last_second.as_nanos() / (sum / 100 as u32).as_nanos();
-
-                info!(
-                    "Avg. sleep: {:#?} ({}%)",
-                    self.total_sleep_time_last_second / (self.frame_counter as u32),
-                    perc
-                );
+            self.stats
+                .record_second(self.update_counter, self.frame_counter);
+            if self.log_frame_stats {
+                self.log_stats();
             }
 
             self.total_frame_time_last_second = Duration::new(0, 0);
@@ -121,4 +135,31 @@ impl EngineGameloopTimer {
             self.frame_counter = 0;
         }
     }
+
+    fn log_stats(&self) {
+        t_info!("Total update count: {}", self.update_counter);
+        t_info!("Total frame count: {}", self.frame_counter);
+        t_info!(
+            "Avg. frametime: {:#?}",
+            self.total_frame_time_last_second / (self.frame_counter as u32)
+        );
+        self.stats.read(|stats| {
+            if let (Some(p99), Some(max)) =
+                (stats.frame_times.percentile(99.0), stats.frame_times.max())
+            {
+                t_info!("Frametime p99: {:#?}, max: {:#?}", p99, max);
+            }
+        });
+
+        if self.max_frame_rate.is_some() {
+            let sum = self.total_sleep_time_last_second + self.total_frame_time_last_second;
+            let perc = self.total_sleep_time_last_second.as_nanos() / (sum / 100 as u32).as_nanos();
+
+            info!(
+                "Avg. sleep: {:#?} ({}%)",
+                self.total_sleep_time_last_second / (self.frame_counter as u32),
+                perc
+            );
+        }
+    }
 }
@@ -1,13 +1,18 @@
+pub mod clock;
+pub mod commands;
 pub mod controller;
 pub mod create_info;
 pub mod engine_states;
+pub mod frame_stats;
 pub mod gameloop_timer;
 pub mod result;
+pub mod stage_error;
 
 use crate::platform::*;
 use controller::EngineController;
 use create_info::EngineCreateInfo;
 use engine_states::*;
+use stage_error::StageError;
 /// An instance of the game engine.
 pub struct Engine {
     state: EngineState,
@@ -23,8 +28,9 @@ impl From<EngineCreateInfo> for Engine {
 
 impl Engine {
     /// Runs the engine instance on the given platform.
-    pub fn run<P: Platform>(self, platform: P) {
+    /// Returns the error of the stage which stopped the engine, if any.
+    pub fn run<P: Platform>(self, platform: P) -> Result<(), StageError> {
         let controller = EngineController::from(self);
-        platform.run(controller);
+        platform.run(controller)
     }
 }
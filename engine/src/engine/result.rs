@@ -1,7 +1,21 @@
-#[repr(u8)]
-#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+use super::stage_error::StageError;
+use std::error::Error;
+
+#[derive(Clone, Eq, PartialEq, Debug)]
 pub enum EngineUpdateResult {
-    Ok = 0,
-    Stop = 1,
-    Restart = 2,
+    Ok,
+    Stop,
+    Restart,
+    /// The stage failed, handled according to its [`super::stage_error::StageErrorPolicy`].
+    Error(StageError),
+}
+
+impl EngineUpdateResult {
+    pub fn error(source: impl Into<Box<dyn Error + Send + Sync>>) -> Self {
+        EngineUpdateResult::Error(StageError::new(source))
+    }
+
+    pub fn is_ok(&self) -> bool {
+        *self == EngineUpdateResult::Ok
+    }
 }
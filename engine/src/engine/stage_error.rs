@@ -0,0 +1,182 @@
+use super::result::EngineUpdateResult;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt::{Debug, Display, Formatter};
+use std::sync::Arc;
+use utils::*;
+
+/// Stage function in which an error occurred.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum StagePhase {
+    PlatformInitialize,
+    Initialize,
+    PreUpdate,
+    Update,
+    PostUpdate,
+    UpdateThreadDidRun,
+    Render,
+    Suspend,
+    Resume,
+}
+
+impl Display for StagePhase {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            StagePhase::PlatformInitialize => "platform initialization",
+            StagePhase::Initialize => "initialization",
+            StagePhase::PreUpdate => "pre update",
+            StagePhase::Update => "update",
+            StagePhase::PostUpdate => "post update",
+            StagePhase::UpdateThreadDidRun => "update thread did run",
+            StagePhase::Render => "render",
+            StagePhase::Suspend => "suspend",
+            StagePhase::Resume => "resume",
+        })
+    }
+}
+
+/// Error returned by a stage through [`EngineUpdateResult::Error`].
+/// Stages only provide the source, the engine adds the stage identifier and phase.
+#[derive(Clone)]
+pub struct StageError {
+    stage: Option<&'static str>,
+    phase: Option<StagePhase>,
+    source: Arc<dyn Error + Send + Sync>,
+}
+
+impl StageError {
+    pub fn new(source: impl Into<Box<dyn Error + Send + Sync>>) -> Self {
+        Self {
+            stage: None,
+            phase: None,
+            source: Arc::from(source.into()),
+        }
+    }
+
+    /// Sets the stage and phase the error occurred in, unless they are already known.
+    pub fn in_stage(mut self, stage: &'static str, phase: StagePhase) -> Self {
+        if self.stage.is_none() {
+            self.stage = Some(stage);
+            self.phase = Some(phase);
+        }
+        self
+    }
+
+    pub fn stage(&self) -> Option<&'static str> {
+        self.stage
+    }
+
+    pub fn phase(&self) -> Option<StagePhase> {
+        self.phase
+    }
+}
+
+/// Errors are equal if they originate from the same source in the same stage and phase.
+impl PartialEq for StageError {
+    fn eq(&self, other: &Self) -> bool {
+        self.stage == other.stage
+            && self.phase == other.phase
+            && Arc::ptr_eq(&self.source, &other.source)
+    }
+}
+impl Eq for StageError {}
+
+impl Debug for StageError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StageError")
+            .field("stage", &self.stage)
+            .field("phase", &self.phase)
+            .field("source", &self.source)
+            .finish()
+    }
+}
+
+impl Display for StageError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match (self.stage, self.phase) {
+            (Some(stage), Some(phase)) => {
+                write!(
+                    f,
+                    "Stage {} failed during {}: {}",
+                    stage, phase, self.source
+                )
+            }
+            _ => write!(f, "Stage failed: {}", self.source),
+        }
+    }
+}
+
+impl Error for StageError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(self.source.as_ref())
+    }
+}
+
+/// Action taken by the engine when a stage returns an error.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StageErrorPolicy {
+    /// Shuts the engine down, the error is returned from [`crate::Engine::run`].
+    #[default]
+    Stop,
+    /// Restarts the engine.
+    Restart,
+    /// Stops executing the stage, including its update thread handler. It is still shut down with the engine.
+    Disable,
+    /// Logs the error and continues as if the stage succeeded.
+    LogAndContinue,
+}
+
+/// Error policies of all stages. Stages without an explicit policy use the default policy.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StageErrorPolicies {
+    pub default: StageErrorPolicy,
+    /// Policies keyed by stage identifier.
+    pub stages: BTreeMap<String, StageErrorPolicy>,
+}
+
+impl StageErrorPolicies {
+    pub fn with_policy(mut self, stage: &str, policy: StageErrorPolicy) -> Self {
+        self.stages.insert(String::from(stage), policy);
+        self
+    }
+
+    pub fn policy(&self, stage: &str) -> StageErrorPolicy {
+        self.stages.get(stage).copied().unwrap_or(self.default)
+    }
+
+    /// Applies the policy of the stage to the result of one of its functions.
+    /// `disable` is invoked if the stage has to be disabled.
+    pub(crate) fn apply(
+        &self,
+        stage: &'static str,
+        phase: StagePhase,
+        result: EngineUpdateResult,
+        disable: impl FnOnce(),
+    ) -> EngineUpdateResult {
+        let error = match result {
+            EngineUpdateResult::Error(e) => e.in_stage(stage, phase),
+            result => return result,
+        };
+        match self.policy(stage) {
+            StageErrorPolicy::Stop => {
+                t_error!("{}", error);
+                EngineUpdateResult::Error(error)
+            }
+            StageErrorPolicy::Restart => {
+                t_error!("{} Restarting engine.", error);
+                EngineUpdateResult::Restart
+            }
+            StageErrorPolicy::Disable => {
+                t_error!("{} Disabling stage.", error);
+                disable();
+                EngineUpdateResult::Ok
+            }
+            StageErrorPolicy::LogAndContinue => {
+                t_error!("{}", error);
+                EngineUpdateResult::Ok
+            }
+        }
+    }
+}
@@ -1,7 +1,8 @@
 use super::*;
 use crate::resource_manager::{EngineResourceManager, ThreadLocalResourceManager};
-use crate::scene_manager::SceneManager;
+use crate::scene_manager::{Scene, SceneManager, SceneTarget};
 use crate::PlatformInterface;
+use std::ops::DerefMut;
 use std::sync::Arc;
 use utils::dispatcher::Dispatcher;
 
@@ -85,12 +86,14 @@ impl<'a> UpdateStageConstructorInput<'a> {
 }
 
 pub struct UpdateStageUpdateInput<'a> {
-    pub scene_manager: &'a mut SceneManager,
+    pub scene_manager: StageAccess<'a, SceneManager>,
     pub resources: Arc<EngineResourceManager>,
-    pub update_thread_resources: &'a mut ThreadLocalResourceManager,
+    pub update_thread_resources: ExclusiveStageAccess<'a, ThreadLocalResourceManager>,
     pub dispatcher: Arc<Dispatcher>,
     pub update_tick_rate: u32,
     pub update_counter_past_second: u64,
+    /// Scenes the stage declared to operate on.
+    pub scene_target: SceneTarget,
 }
 
 impl<'a> UpdateStageUpdateInput<'a> {
@@ -101,6 +104,25 @@ impl<'a> UpdateStageUpdateInput<'a> {
         thread_local_resources: &'a mut ThreadLocalResourceManager,
         update_tick_rate: u32,
         update_counter_past_second: u64,
+    ) -> Self {
+        Self::with_access(
+            resources,
+            dispatcher,
+            StageAccess::Write(scene_manager),
+            ExclusiveStageAccess::Write(thread_local_resources),
+            update_tick_rate,
+            update_counter_past_second,
+        )
+    }
+
+    /// Creates an input which only grants the accesses an update stage declared.
+    pub fn with_access(
+        resources: Arc<EngineResourceManager>,
+        dispatcher: Arc<Dispatcher>,
+        scene_manager: StageAccess<'a, SceneManager>,
+        thread_local_resources: ExclusiveStageAccess<'a, ThreadLocalResourceManager>,
+        update_tick_rate: u32,
+        update_counter_past_second: u64,
     ) -> Self {
         Self {
             scene_manager,
@@ -109,6 +131,23 @@ impl<'a> UpdateStageUpdateInput<'a> {
             dispatcher,
             update_tick_rate,
             update_counter_past_second,
+            scene_target: SceneTarget::Active,
         }
     }
+
+    pub fn with_scene_target(mut self, scene_target: SceneTarget) -> Self {
+        self.scene_target = scene_target;
+        self
+    }
+
+    /// Iterates the scenes the stage targets. Requires read access to the [`SceneManager`].
+    pub fn target_scenes(&self) -> impl Iterator<Item = &Scene> {
+        self.scene_manager.target_scenes(&self.scene_target)
+    }
+
+    /// Iterates the scenes the stage targets. Requires write access to the [`SceneManager`].
+    pub fn target_scenes_mut(&mut self) -> impl Iterator<Item = &mut Scene> {
+        let target = &self.scene_target;
+        self.scene_manager.deref_mut().target_scenes_mut(target)
+    }
 }
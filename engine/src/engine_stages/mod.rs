@@ -2,14 +2,18 @@ mod inputs;
 mod render_stage;
 mod render_stage_container;
 mod stage_manager;
+mod stage_queue;
 mod update_stage;
 mod update_stage_container;
+mod update_stage_dependencies;
 mod update_thread_handler_container;
 
 pub use inputs::*;
 pub use render_stage::*;
 pub use render_stage_container::*;
 pub use stage_manager::*;
+pub use stage_queue::*;
 pub use update_stage::*;
 pub use update_stage_container::*;
+pub use update_stage_dependencies::*;
 pub use update_thread_handler_container::*;
@@ -40,6 +40,11 @@ pub trait RenderStageUpdateThreadHandler: Sized + Send + 'static {
 }
 
 pub trait AnyRenderStageUpdateThreadHandler: Send {
+    /// Identifier of the render stage owning the handler.
+    fn identifier(&self) -> &'static str;
+    fn is_disabled(&self) -> bool;
+    /// Disables the handler together with its render stage.
+    fn disable(&mut self);
     fn register_message_handlers(&mut self, registerer: AnyMessageRegisterer<'_>);
     fn process_events(&mut self, input: UpdateStageUpdateInput);
     fn pre_update(&mut self, input: UpdateStageUpdateInput) -> EngineUpdateResult;
@@ -66,6 +71,10 @@ pub trait RenderStage: Sized + 'static {
     fn engine_will_resume(&mut self, input: RenderStageUpdateInput) -> EngineUpdateResult {
         EngineUpdateResult::Ok
     }
+    /// Executed once before the stage is dropped during engine shutdown. Runs on the main thread.
+    /// Render stages are shut down in reverse construction order, before all update stages.
+    #[allow(unused_variables)]
+    fn engine_will_shutdown(&mut self, input: RenderStageUpdateInput) {}
 
     #[allow(unused_variables)]
     fn register_message_handlers(&self, registerer: RenderMessageRegisterer<'_, Self>) {}
@@ -85,6 +94,10 @@ pub trait RenderStage: Sized + 'static {
 /// TraitObject trait for Render Stages. Implemented for all T: RenderStage.
 pub trait AnyRenderStage: 'static {
     fn identifier(&self) -> &'static str;
+    fn is_disabled(&self) -> bool;
+    /// Disables the stage together with its update thread handler.
+    /// Disabled stages are skipped, except for [`AnyRenderStage::engine_will_shutdown`].
+    fn disable(&mut self);
     fn register_message_handlers(&mut self, _registerer: AnyMessageRegisterer<'_>);
     fn create_update_thread_handler(
         &mut self,
@@ -99,6 +112,7 @@ pub trait AnyRenderStage: 'static {
     fn engine_will_suspend(&mut self, input: RenderStageUpdateInput) -> EngineUpdateResult;
     #[allow(unused_variables)]
     fn engine_will_resume(&mut self, input: RenderStageUpdateInput) -> EngineUpdateResult;
+    fn engine_will_shutdown(&mut self, input: RenderStageUpdateInput);
 
     fn process_events(&mut self, input: RenderStageUpdateInput);
     fn update_thread_did_run(&mut self, input: RenderStageUpdateInput) -> EngineUpdateResult;
@@ -5,11 +5,15 @@ use crate::engine_stages::{
 use crate::message_bus::*;
 use crate::{EngineUpdateResult, PlatformInterface};
 use std::any::Any;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use utils::as_any::*;
 
 pub struct RenderStageContainer<T: RenderStage> {
     stage: T,
     receivers: Vec<Box<dyn AnyRenderMessageReceiver<T>>>,
+    /// Shared with the update thread handler of the stage.
+    disabled: Arc<AtomicBool>,
 }
 
 pub struct RenderStageMessageContext<'a> {
@@ -21,6 +25,7 @@ impl<T: RenderStage> From<T> for RenderStageContainer<T> {
         Self {
             stage,
             receivers: vec![],
+            disabled: Default::default(),
         }
     }
 }
@@ -30,6 +35,14 @@ impl<T: RenderStage> AnyRenderStage for RenderStageContainer<T> {
         <T as RenderStage>::IDENTIFIER
     }
 
+    fn is_disabled(&self) -> bool {
+        self.disabled.load(Ordering::Acquire)
+    }
+
+    fn disable(&mut self) {
+        self.disabled.store(true, Ordering::Release);
+    }
+
     fn register_message_handlers(&mut self, registerer: AnyMessageRegisterer<'_>) {
         self.receivers.clear();
         let registerer = RenderMessageRegisterer::new(registerer, &mut self.receivers);
@@ -41,8 +54,10 @@ impl<T: RenderStage> AnyRenderStage for RenderStageContainer<T> {
         create_info: RenderStageUpdateThreadHandlerCreateInfo<'_>,
         registerer: AnyMessageRegisterer<'_>,
     ) -> Box<dyn AnyRenderStageUpdateThreadHandler> {
-        let mut item = Box::new(UpdateThreadHandlerContainer::from(
+        let mut item = Box::new(UpdateThreadHandlerContainer::new(
+            <T as RenderStage>::IDENTIFIER,
             self.stage.create_update_thread_handler(create_info),
+            Arc::clone(&self.disabled),
         ));
         item.register_message_handlers(registerer);
         item
@@ -53,14 +68,27 @@ impl<T: RenderStage> AnyRenderStage for RenderStageContainer<T> {
     }
 
     fn engine_will_suspend(&mut self, input: RenderStageUpdateInput) -> EngineUpdateResult {
+        if self.is_disabled() {
+            return EngineUpdateResult::Ok;
+        }
         self.stage.engine_will_suspend(input)
     }
 
     fn engine_will_resume(&mut self, input: RenderStageUpdateInput) -> EngineUpdateResult {
+        if self.is_disabled() {
+            return EngineUpdateResult::Ok;
+        }
         self.stage.engine_will_resume(input)
     }
 
+    fn engine_will_shutdown(&mut self, input: RenderStageUpdateInput) {
+        self.stage.engine_will_shutdown(input)
+    }
+
     fn process_events(&mut self, input: RenderStageUpdateInput) {
+        if self.is_disabled() {
+            return;
+        }
         for receiver in self.receivers.iter_mut() {
             receiver.receive_messages(
                 &mut self.stage,
@@ -72,10 +100,16 @@ impl<T: RenderStage> AnyRenderStage for RenderStageContainer<T> {
     }
 
     fn update_thread_did_run(&mut self, input: RenderStageUpdateInput) -> EngineUpdateResult {
+        if self.is_disabled() {
+            return EngineUpdateResult::Ok;
+        }
         self.stage.update_thread_did_run(input)
     }
 
     fn render(&mut self, input: RenderStageUpdateInput) -> EngineUpdateResult {
+        if self.is_disabled() {
+            return EngineUpdateResult::Ok;
+        }
         self.stage.render(input)
     }
 
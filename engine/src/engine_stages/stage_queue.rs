@@ -0,0 +1,70 @@
+use crate::engine_stages::*;
+use std::any::TypeId;
+use std::sync::Mutex;
+
+pub(crate) type RuntimeUpdateStageConstructor =
+    dyn FnOnce(UpdateStageConstructorInput) -> Box<dyn AnyUpdateStage> + Send + 'static;
+pub(crate) type RuntimeRenderStageConstructor =
+    dyn FnOnce(RenderStageConstructorInput) -> Box<dyn AnyRenderStage> + Send + 'static;
+
+pub(crate) enum StageChange {
+    AddUpdate(Box<RuntimeUpdateStageConstructor>),
+    RemoveUpdate(TypeId, &'static str),
+    AddRender(Box<RuntimeRenderStageConstructor>),
+    RemoveRender(TypeId, &'static str),
+}
+
+/// Engine resource to add or remove stages while the engine is running.
+///
+/// Changes are applied in the order they were queued, on the main thread before the next tick.
+/// Added stages are constructed, register their message handlers and are initialized like stages
+/// passed to [`crate::EngineCreateInfo`]. Removed stages are shut down and dropped, which also
+/// removes their message handlers.
+#[derive(Default)]
+pub struct StageQueue {
+    changes: Mutex<Vec<StageChange>>,
+}
+
+impl StageQueue {
+    /// Appends an update stage. It runs after all existing stages it has no dependencies with.
+    pub fn add_update_stage(
+        &self,
+        constructor: impl FnOnce(UpdateStageConstructorInput) -> Box<dyn AnyUpdateStage>
+            + Send
+            + 'static,
+    ) {
+        self.push(StageChange::AddUpdate(Box::new(constructor)));
+    }
+
+    /// Removes the update stage of type `S`, if present.
+    pub fn remove_update_stage<S: UpdateStage>(&self) {
+        self.push(StageChange::RemoveUpdate(TypeId::of::<S>(), S::IDENTIFIER));
+    }
+
+    /// Appends a render stage, together with its update thread handler.
+    pub fn add_render_stage(
+        &self,
+        constructor: impl FnOnce(RenderStageConstructorInput) -> Box<dyn AnyRenderStage>
+            + Send
+            + 'static,
+    ) {
+        self.push(StageChange::AddRender(Box::new(constructor)));
+    }
+
+    /// Removes the render stage of type `S` and its update thread handler, if present.
+    pub fn remove_render_stage<S: RenderStage>(&self) {
+        self.push(StageChange::RemoveRender(TypeId::of::<S>(), S::IDENTIFIER));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.lock().unwrap().is_empty()
+    }
+
+    pub(crate) fn take(&self) -> Vec<StageChange> {
+        std::mem::take(&mut *self.changes.lock().unwrap())
+    }
+
+    fn push(&self, change: StageChange) {
+        self.changes.lock().unwrap().push(change);
+    }
+}
@@ -13,6 +13,11 @@ pub trait UpdateStage: Sized + Send + 'static {
     const IDENTIFIER: &'static str;
     #[allow(unused_variables)]
     fn register_message_handlers(&self, registerer: UpdateMessageRegisterer<'_, Self>) {}
+    /// Declares ordering constraints and resource accesses, used to run stages in parallel.
+    /// By default a stage is exclusive and never runs in parallel with other stages.
+    fn dependencies(&self) -> UpdateStageDependencies {
+        UpdateStageDependencies::exclusive()
+    }
     fn update(&mut self, input: UpdateStageUpdateInput) -> EngineUpdateResult;
 
     /// Executed after the engine is initialized but before running. Unlike other update functions, runs on the main thread.
@@ -28,6 +33,10 @@ pub trait UpdateStage: Sized + Send + 'static {
     fn engine_will_resume(&mut self, input: UpdateStageUpdateInput) -> EngineUpdateResult {
         EngineUpdateResult::Ok
     }
+    /// Executed once before the stage is dropped during engine shutdown. Runs on the main thread.
+    /// Update stages are shut down in reverse construction order, after all render stages.
+    #[allow(unused_variables)]
+    fn engine_will_shutdown(&mut self, input: UpdateStageUpdateInput) {}
 
     fn stage_as_any(&self) -> &dyn Any {
         self
@@ -41,8 +50,12 @@ pub trait UpdateStage: Sized + Send + 'static {
 /// TraitObject trait for Update Stages. Implemented for all T: UpdateStage.
 pub trait AnyUpdateStage: Send + 'static {
     fn identifier(&self) -> &'static str;
+    fn is_disabled(&self) -> bool;
+    /// Disabled stages are skipped, except for [`AnyUpdateStage::engine_will_shutdown`].
+    fn disable(&mut self);
     fn process_events(&mut self);
     fn register_message_handlers(&mut self, registerer: AnyMessageRegisterer<'_>);
+    fn dependencies(&self) -> UpdateStageDependencies;
     fn update(&mut self, input: UpdateStageUpdateInput) -> EngineUpdateResult;
 
     /// Executed after the engine is initialized but before running. Unlike other update functions, runs on the main thread.
@@ -52,6 +65,7 @@ pub trait AnyUpdateStage: Send + 'static {
     fn engine_will_suspend(&mut self, input: UpdateStageUpdateInput) -> EngineUpdateResult;
     #[allow(unused_variables)]
     fn engine_will_resume(&mut self, input: UpdateStageUpdateInput) -> EngineUpdateResult;
+    fn engine_will_shutdown(&mut self, input: UpdateStageUpdateInput);
 
     fn stage_as_any(&self) -> &dyn Any;
     fn stage_as_any_mut(&mut self) -> &mut dyn Any;
@@ -1,5 +1,6 @@
 use crate::engine_stages::{
-    AnyUpdateStage, EngineDidInitInput, UpdateStage, UpdateStageUpdateInput,
+    AnyUpdateStage, EngineDidInitInput, UpdateStage, UpdateStageDependencies,
+    UpdateStageUpdateInput,
 };
 use crate::message_bus::*;
 use crate::EngineUpdateResult;
@@ -9,6 +10,7 @@ use std::marker::PhantomData;
 pub struct UpdateStageContainer<T: UpdateStage> {
     stage: T,
     receivers: Vec<Box<dyn AnyUpdateMessageReceiver<T>>>,
+    disabled: bool,
 }
 
 pub struct UpdateStageMessageContext<'a> {
@@ -27,6 +29,7 @@ impl<T: UpdateStage> From<T> for UpdateStageContainer<T> {
         Self {
             stage,
             receivers: vec![],
+            disabled: false,
         }
     }
 }
@@ -36,7 +39,18 @@ impl<T: UpdateStage> AnyUpdateStage for UpdateStageContainer<T> {
         <T as UpdateStage>::IDENTIFIER
     }
 
+    fn is_disabled(&self) -> bool {
+        self.disabled
+    }
+
+    fn disable(&mut self) {
+        self.disabled = true;
+    }
+
     fn process_events(&mut self) {
+        if self.disabled {
+            return;
+        }
         for receiver in self.receivers.iter_mut() {
             receiver.receive_messages(
                 &mut self.stage,
@@ -53,7 +67,14 @@ impl<T: UpdateStage> AnyUpdateStage for UpdateStageContainer<T> {
         self.stage.register_message_handlers(registerer);
     }
 
+    fn dependencies(&self) -> UpdateStageDependencies {
+        self.stage.dependencies()
+    }
+
     fn update(&mut self, input: UpdateStageUpdateInput) -> EngineUpdateResult {
+        if self.disabled {
+            return EngineUpdateResult::Ok;
+        }
         self.stage.update(input)
     }
 
@@ -62,13 +83,23 @@ impl<T: UpdateStage> AnyUpdateStage for UpdateStageContainer<T> {
     }
 
     fn engine_will_suspend(&mut self, input: UpdateStageUpdateInput) -> EngineUpdateResult {
+        if self.disabled {
+            return EngineUpdateResult::Ok;
+        }
         self.stage.engine_will_suspend(input)
     }
 
     fn engine_will_resume(&mut self, input: UpdateStageUpdateInput) -> EngineUpdateResult {
+        if self.disabled {
+            return EngineUpdateResult::Ok;
+        }
         self.stage.engine_will_resume(input)
     }
 
+    fn engine_will_shutdown(&mut self, input: UpdateStageUpdateInput) {
+        self.stage.engine_will_shutdown(input)
+    }
+
     fn stage_as_any(&self) -> &dyn Any {
         T::stage_as_any(&self.stage)
     }
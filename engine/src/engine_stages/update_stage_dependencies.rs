@@ -0,0 +1,304 @@
+use crate::resource_manager::ThreadLocalResourceManager;
+use crate::scene_manager::SceneTarget;
+use crate::UpdateStage;
+use std::any::{type_name, TypeId};
+use std::ops::{Deref, DerefMut};
+use utils::*;
+
+/// Kind of access an update stage declared for a resource.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum StageAccessKind {
+    None,
+    Read,
+    Write,
+}
+
+/// Ordering constraints and resource accesses of an update stage.
+/// Stages which do not conflict with each other are run in parallel.
+///
+/// Resources may be any type, including the [`crate::scene_manager::SceneManager`] and [`ThreadLocalResourceManager`]
+/// handed to stages through [`crate::UpdateStageUpdateInput`]. The [`ThreadLocalResourceManager`] can only be accessed
+/// exclusively, reading it is treated as writing it.
+#[derive(Debug, Clone, Default)]
+pub struct UpdateStageDependencies {
+    exclusive: bool,
+    before: Vec<&'static str>,
+    after: Vec<&'static str>,
+    reads: Vec<TypeId>,
+    writes: Vec<TypeId>,
+    scene_target: SceneTarget,
+}
+
+impl UpdateStageDependencies {
+    /// Declares no constraints and no resource accesses.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Declares that the stage may access everything, so it never runs in parallel with other stages.
+    pub fn exclusive() -> Self {
+        Self {
+            exclusive: true,
+            ..Default::default()
+        }
+    }
+
+    /// The stage runs before the stage `S`, if present.
+    pub fn before<S: UpdateStage>(self) -> Self {
+        self.before_identifier(S::IDENTIFIER)
+    }
+
+    /// The stage runs before the stage with the given identifier, if present.
+    pub fn before_identifier(mut self, identifier: &'static str) -> Self {
+        self.before.push(identifier);
+        self
+    }
+
+    /// The stage runs after the stage `S`, if present.
+    pub fn after<S: UpdateStage>(self) -> Self {
+        self.after_identifier(S::IDENTIFIER)
+    }
+
+    /// The stage runs after the stage with the given identifier, if present.
+    pub fn after_identifier(mut self, identifier: &'static str) -> Self {
+        self.after.push(identifier);
+        self
+    }
+
+    pub fn reads<R: 'static>(mut self) -> Self {
+        if TypeId::of::<R>() == TypeId::of::<ThreadLocalResourceManager>() {
+            return self.writes::<R>();
+        }
+        self.reads.push(TypeId::of::<R>());
+        self
+    }
+
+    pub fn writes<R: 'static>(mut self) -> Self {
+        self.writes.push(TypeId::of::<R>());
+        self
+    }
+
+    /// Declares the scenes the stage operates on, see [`crate::UpdateStageUpdateInput::target_scenes`].
+    /// Defaults to the active scene. Accessing the scenes still requires declaring access to the
+    /// [`crate::scene_manager::SceneManager`].
+    pub fn targets(mut self, target: SceneTarget) -> Self {
+        self.scene_target = target;
+        self
+    }
+
+    pub fn scene_target(&self) -> &SceneTarget {
+        &self.scene_target
+    }
+
+    pub fn is_exclusive(&self) -> bool {
+        self.exclusive
+    }
+
+    pub fn runs_before(&self) -> &[&'static str] {
+        &self.before
+    }
+
+    pub fn runs_after(&self) -> &[&'static str] {
+        &self.after
+    }
+
+    pub fn access<R: 'static>(&self) -> StageAccessKind {
+        self.access_by_id(TypeId::of::<R>())
+    }
+
+    fn access_by_id(&self, id: TypeId) -> StageAccessKind {
+        if self.exclusive || self.writes.contains(&id) {
+            StageAccessKind::Write
+        } else if self.reads.contains(&id) {
+            StageAccessKind::Read
+        } else {
+            StageAccessKind::None
+        }
+    }
+
+    /// Whether both stages access a resource which at least one of them writes.
+    pub fn conflicts_with(&self, other: &UpdateStageDependencies) -> bool {
+        if self.exclusive || other.exclusive {
+            return true;
+        }
+        self.writes
+            .iter()
+            .any(|id| other.access_by_id(*id) != StageAccessKind::None)
+            || other
+                .writes
+                .iter()
+                .any(|id| self.access_by_id(*id) != StageAccessKind::None)
+    }
+}
+
+/// Access of an update stage to state owned by the update thread, as declared through [`UpdateStageDependencies`].
+/// Dereferencing panics if the stage did not declare the required access.
+pub enum StageAccess<'a, T> {
+    None,
+    Read(&'a T),
+    Write(&'a mut T),
+}
+
+impl<'a, T> StageAccess<'a, T> {
+    pub fn kind(&self) -> StageAccessKind {
+        match self {
+            StageAccess::None => StageAccessKind::None,
+            StageAccess::Read(_) => StageAccessKind::Read,
+            StageAccess::Write(_) => StageAccessKind::Write,
+        }
+    }
+
+    pub fn get(&self) -> Option<&T> {
+        match self {
+            StageAccess::None => None,
+            StageAccess::Read(v) => Some(v),
+            StageAccess::Write(v) => Some(v),
+        }
+    }
+
+    pub fn get_mut(&mut self) -> Option<&mut T> {
+        match self {
+            StageAccess::Write(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+impl<'a, T> Deref for StageAccess<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        match self.get() {
+            Some(v) => v,
+            None => t_fatal!(
+                "Accessed {} without declaring read access to it!",
+                type_name::<T>()
+            ),
+        }
+    }
+}
+
+impl<'a, T> DerefMut for StageAccess<'a, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        match self {
+            StageAccess::Write(v) => v,
+            _ => t_fatal!(
+                "Accessed {} without declaring write access to it!",
+                type_name::<T>()
+            ),
+        }
+    }
+}
+
+/// Access of an update stage to update thread state which can only be accessed exclusively,
+/// such as the [`ThreadLocalResourceManager`]. Dereferencing panics if the stage did not declare access.
+pub enum ExclusiveStageAccess<'a, T> {
+    None,
+    Write(&'a mut T),
+}
+
+impl<'a, T> ExclusiveStageAccess<'a, T> {
+    pub fn kind(&self) -> StageAccessKind {
+        match self {
+            ExclusiveStageAccess::None => StageAccessKind::None,
+            ExclusiveStageAccess::Write(_) => StageAccessKind::Write,
+        }
+    }
+
+    pub fn get(&self) -> Option<&T> {
+        match self {
+            ExclusiveStageAccess::None => None,
+            ExclusiveStageAccess::Write(v) => Some(v),
+        }
+    }
+
+    pub fn get_mut(&mut self) -> Option<&mut T> {
+        match self {
+            ExclusiveStageAccess::None => None,
+            ExclusiveStageAccess::Write(v) => Some(v),
+        }
+    }
+}
+
+impl<'a, T> Deref for ExclusiveStageAccess<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        match self.get() {
+            Some(v) => v,
+            None => t_fatal!(
+                "Accessed {} without declaring access to it!",
+                type_name::<T>()
+            ),
+        }
+    }
+}
+
+impl<'a, T> DerefMut for ExclusiveStageAccess<'a, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        match self.get_mut() {
+            Some(v) => v,
+            None => t_fatal!(
+                "Accessed {} without declaring access to it!",
+                type_name::<T>()
+            ),
+        }
+    }
+}
+
+/// Splits a value into the accesses requested by stages that run in parallel.
+/// The requested accesses must not conflict, i.e. there is either a single writer or only readers.
+pub(crate) fn split_stage_access<'a, T>(
+    value: &'a mut T,
+    kinds: &[StageAccessKind],
+) -> Vec<StageAccess<'a, T>> {
+    if let Some(writer) = kinds.iter().position(|e| *e == StageAccessKind::Write) {
+        debug_assert!(kinds
+            .iter()
+            .enumerate()
+            .all(|(idx, e)| idx == writer || *e == StageAccessKind::None));
+        let mut value = Some(value);
+        kinds
+            .iter()
+            .map(|e| match e {
+                StageAccessKind::Write => StageAccess::Write(value.take().unwrap()),
+                _ => StageAccess::None,
+            })
+            .collect()
+    } else {
+        let value: &'a T = value;
+        kinds
+            .iter()
+            .map(|e| match e {
+                StageAccessKind::Read => StageAccess::Read(value),
+                _ => StageAccess::None,
+            })
+            .collect()
+    }
+}
+
+/// Hands a value to the single stage which requested access to it, if any.
+pub(crate) fn split_exclusive_stage_access<'a, T>(
+    value: &'a mut T,
+    kinds: &[StageAccessKind],
+) -> Vec<ExclusiveStageAccess<'a, T>> {
+    debug_assert!(
+        kinds
+            .iter()
+            .filter(|e| **e != StageAccessKind::None)
+            .count()
+            <= 1
+    );
+    let mut value = Some(value);
+    kinds
+        .iter()
+        .map(|e| match (e, value.take()) {
+            (StageAccessKind::None, v) => {
+                value = v;
+                ExclusiveStageAccess::None
+            }
+            (_, Some(v)) => ExclusiveStageAccess::Write(v),
+            (_, None) => ExclusiveStageAccess::None,
+        })
+        .collect()
+}
@@ -1,16 +1,23 @@
 use crate::engine_stages::*;
 use crate::*;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 
 pub struct UpdateThreadHandlerContainer<T: RenderStageUpdateThreadHandler> {
+    identifier: &'static str,
     stage: T,
     receivers: Vec<Box<dyn AnyUpdateMessageReceiver<T>>>,
+    /// Shared with the render stage owning the handler.
+    disabled: Arc<AtomicBool>,
 }
 
-impl<T: RenderStageUpdateThreadHandler> From<T> for UpdateThreadHandlerContainer<T> {
-    fn from(stage: T) -> Self {
+impl<T: RenderStageUpdateThreadHandler> UpdateThreadHandlerContainer<T> {
+    pub fn new(identifier: &'static str, stage: T, disabled: Arc<AtomicBool>) -> Self {
         Self {
+            identifier,
             stage,
             receivers: vec![],
+            disabled,
         }
     }
 }
@@ -19,6 +26,18 @@ impl<T> AnyRenderStageUpdateThreadHandler for UpdateThreadHandlerContainer<T>
 where
     T: RenderStageUpdateThreadHandler,
 {
+    fn identifier(&self) -> &'static str {
+        self.identifier
+    }
+
+    fn is_disabled(&self) -> bool {
+        self.disabled.load(Ordering::Acquire)
+    }
+
+    fn disable(&mut self) {
+        self.disabled.store(true, Ordering::Release);
+    }
+
     fn register_message_handlers(&mut self, registerer: AnyMessageRegisterer<'_>) {
         self.receivers.clear();
         let registerer = UpdateMessageRegisterer::new(registerer, &mut self.receivers);
@@ -26,16 +45,25 @@ where
     }
 
     fn process_events(&mut self, _input: UpdateStageUpdateInput) {
+        if self.is_disabled() {
+            return;
+        }
         for receiver in self.receivers.iter_mut() {
             receiver.receive_messages(&mut self.stage, &mut UpdateStageMessageContext::default());
         }
     }
 
     fn pre_update(&mut self, input: UpdateStageUpdateInput) -> EngineUpdateResult {
+        if self.is_disabled() {
+            return EngineUpdateResult::Ok;
+        }
         self.stage.pre_update(input)
     }
 
     fn post_update(&mut self, input: UpdateStageUpdateInput) -> EngineUpdateResult {
+        if self.is_disabled() {
+            return EngineUpdateResult::Ok;
+        }
         self.stage.post_update(input)
     }
 }
@@ -0,0 +1,91 @@
+use super::*;
+use crate::asset_decoding::{decode, AssetDecodingError};
+use assets::AssetSerializationFormat;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+
+/// Physical input which triggers an action or drives an axis.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InputBinding {
+    Key(KeyCode),
+    MouseButton(MouseButton),
+    /// Button of any connected gamepad.
+    GamepadButton(GamepadButton),
+}
+
+/// Axis between -1 and 1, driven by the bindings pushing it in either direction
+/// and by the gamepad axes, of which the one deflected the most is added.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct AxisBinding {
+    #[serde(default)]
+    pub positive: Vec<InputBinding>,
+    #[serde(default)]
+    pub negative: Vec<InputBinding>,
+    #[serde(default)]
+    pub gamepad_axes: Vec<GamepadAxis>,
+}
+
+/// Named actions and axes, usually loaded from a TOML asset:
+///
+/// ```toml
+/// [actions]
+/// jump = [{ key = "Space" }, { mouse_button = "Left" }]
+///
+/// [axes.move_x]
+/// positive = [{ key = "D" }]
+/// negative = [{ key = "A" }]
+/// gamepad_axes = ["LeftStickX"]
+/// ```
+#[derive(Debug, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct InputBindings {
+    #[serde(default)]
+    pub actions: BTreeMap<String, Vec<InputBinding>>,
+    #[serde(default)]
+    pub axes: BTreeMap<String, AxisBinding>,
+}
+
+impl InputBindings {
+    /// Decodes CBOR for [`AssetSerializationFormat::Binary`] or TOML.
+    pub fn from_bytes(bytes: &[u8], format: AssetSerializationFormat) -> Result<Self, InputError> {
+        Ok(decode(bytes, format)?)
+    }
+}
+
+#[derive(Debug)]
+pub enum InputError {
+    Decoding(AssetSerializationFormat, String),
+    UnsupportedFormat,
+    /// The bindings asset could not be loaded from the asset cache.
+    AssetUnavailable,
+}
+
+impl From<AssetDecodingError> for InputError {
+    fn from(e: AssetDecodingError) -> Self {
+        match e {
+            AssetDecodingError::Decoding(format, e) => InputError::Decoding(format, e),
+            AssetDecodingError::UnsupportedFormat => InputError::UnsupportedFormat,
+            AssetDecodingError::AssetUnavailable => InputError::AssetUnavailable,
+        }
+    }
+}
+
+impl std::error::Error for InputError {}
+impl Display for InputError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InputError::Decoding(format, e) => {
+                write!(
+                    f,
+                    "Could not decode input bindings from {:?}: {}",
+                    format, e
+                )
+            }
+            InputError::UnsupportedFormat => {
+                write!(f, "Unsupported input bindings serialization format.")
+            }
+            InputError::AssetUnavailable => write!(f, "Input bindings asset could not be loaded."),
+        }
+    }
+}
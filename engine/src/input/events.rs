@@ -0,0 +1,189 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Platform independent key, named after the key on a US keyboard layout.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum KeyCode {
+    A,
+    B,
+    C,
+    D,
+    E,
+    F,
+    G,
+    H,
+    I,
+    J,
+    K,
+    L,
+    M,
+    N,
+    O,
+    P,
+    Q,
+    R,
+    S,
+    T,
+    U,
+    V,
+    W,
+    X,
+    Y,
+    Z,
+    Key0,
+    Key1,
+    Key2,
+    Key3,
+    Key4,
+    Key5,
+    Key6,
+    Key7,
+    Key8,
+    Key9,
+    F1,
+    F2,
+    F3,
+    F4,
+    F5,
+    F6,
+    F7,
+    F8,
+    F9,
+    F10,
+    F11,
+    F12,
+    Escape,
+    Space,
+    Enter,
+    Tab,
+    Backspace,
+    Delete,
+    Insert,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Left,
+    Right,
+    Up,
+    Down,
+    LShift,
+    RShift,
+    LControl,
+    RControl,
+    LAlt,
+    RAlt,
+    Minus,
+    Equals,
+    Comma,
+    Period,
+    Slash,
+    Grave,
+    Snapshot,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum MouseButton {
+    Left,
+    Right,
+    Middle,
+    Other(u16),
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum ButtonState {
+    Pressed,
+    Released,
+}
+
+/// Identifies a connected gamepad, assigned by the platform.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub struct GamepadId(pub u32);
+
+/// Gamepad button, named after its position on the gamepad.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum GamepadButton {
+    South,
+    East,
+    West,
+    North,
+    LeftBumper,
+    RightBumper,
+    LeftTrigger,
+    RightTrigger,
+    Select,
+    Start,
+    Mode,
+    LeftStick,
+    RightStick,
+    DPadUp,
+    DPadDown,
+    DPadLeft,
+    DPadRight,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum GamepadAxis {
+    LeftStickX,
+    LeftStickY,
+    RightStickX,
+    RightStickY,
+    LeftTrigger,
+    RightTrigger,
+}
+
+/// Requests a gamepad to vibrate, handled by the platform if the gamepad supports it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RumbleRequest {
+    pub gamepad: GamepadId,
+    /// Magnitude of the low frequency motor between 0 and 1.
+    pub strong_magnitude: f32,
+    /// Magnitude of the high frequency motor between 0 and 1.
+    pub weak_magnitude: f32,
+    pub duration: Duration,
+}
+
+/// Input received by the platform, sent through the [`crate::MessageBus`].
+/// The [`super::InputManager`] collects the events into the [`super::InputState`] of each update tick.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum InputEvent {
+    Key {
+        key: KeyCode,
+        state: ButtonState,
+    },
+    MouseButton {
+        button: MouseButton,
+        state: ButtonState,
+    },
+    /// Cursor position in physical pixels, relative to the top left corner of the window.
+    CursorMoved {
+        x: f32,
+        y: f32,
+    },
+    /// Scroll distance in lines.
+    Scrolled {
+        delta_x: f32,
+        delta_y: f32,
+    },
+    Text(char),
+    GamepadConnected(GamepadId),
+    /// Buttons held on the gamepad are released.
+    GamepadDisconnected(GamepadId),
+    GamepadButton {
+        gamepad: GamepadId,
+        button: GamepadButton,
+        state: ButtonState,
+    },
+    /// Axis value after the dead zone was applied, between -1 and 1 for sticks and between 0 and 1 for triggers.
+    GamepadAxis {
+        gamepad: GamepadId,
+        axis: GamepadAxis,
+        value: f32,
+    },
+}
+
+impl InputEvent {
+    pub fn key(key: KeyCode, state: ButtonState) -> Self {
+        InputEvent::Key { key, state }
+    }
+}
@@ -0,0 +1,125 @@
+use super::*;
+use crate::asset_decoding::load_asset;
+use crate::message_bus::{HandlerReceiver, MessageBus, MessageHandlerType};
+use assets::{AssetCache, AssetIdentifier};
+use std::sync::{Mutex, RwLock, RwLockReadGuard};
+
+/// Engine resource collecting the [`InputEvent`]s sent through the [`MessageBus`] into an [`InputState`]
+/// at the start of every update tick.
+pub struct InputManager {
+    receiver: HandlerReceiver<InputEvent>,
+    bindings: RwLock<InputBindings>,
+    state: RwLock<InputState>,
+    recorder: Mutex<Option<InputRecorder>>,
+    replay: Mutex<Option<InputReplay>>,
+}
+
+impl InputManager {
+    pub fn new(message_bus: &MessageBus) -> Self {
+        Self {
+            receiver: message_bus.add_handler(MessageHandlerType::Update),
+            bindings: Default::default(),
+            state: Default::default(),
+            recorder: Default::default(),
+            replay: Default::default(),
+        }
+    }
+
+    /// Returns the input of the current update tick.
+    pub fn state(&self) -> RwLockReadGuard<'_, InputState> {
+        self.state.read().unwrap()
+    }
+
+    pub fn bindings(&self) -> InputBindings {
+        self.bindings.read().unwrap().clone()
+    }
+
+    /// Replaces all bindings, taking effect in the next update tick.
+    pub fn set_bindings(&self, bindings: InputBindings) {
+        *self.bindings.write().unwrap() = bindings;
+    }
+
+    /// Rebinds the action, an empty list of bindings removes it.
+    pub fn bind_action(&self, action: &str, bindings: Vec<InputBinding>) {
+        let mut all_bindings = self.bindings.write().unwrap();
+        match bindings.is_empty() {
+            true => all_bindings.actions.remove(action),
+            false => all_bindings.actions.insert(String::from(action), bindings),
+        };
+    }
+
+    pub fn bind_axis(&self, axis: &str, binding: AxisBinding) {
+        self.bindings
+            .write()
+            .unwrap()
+            .axes
+            .insert(String::from(axis), binding);
+    }
+
+    /// Loads the bindings from a CBOR or TOML asset, blocking until it is available.
+    pub fn load_bindings(
+        &self,
+        asset_cache: &AssetCache,
+        asset_id: AssetIdentifier,
+    ) -> Result<(), InputError> {
+        self.set_bindings(load_asset(asset_cache, asset_id)?);
+        Ok(())
+    }
+
+    /// Starts recording the input applied in each update tick, discarding a previous recording.
+    pub fn start_recording(&self) {
+        let held = self.state().held_events();
+        *self.recorder.lock().unwrap() = Some(InputRecorder::new(held));
+    }
+
+    /// Stops recording and returns the recorded input, None if no recording was started.
+    pub fn stop_recording(&self) -> Option<InputRecording> {
+        self.recorder
+            .lock()
+            .unwrap()
+            .take()
+            .map(InputRecorder::finish)
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recorder.lock().unwrap().is_some()
+    }
+
+    /// Replays the recording, starting with the next update tick.
+    /// The input state is reset when the replay starts and input sent while replaying is discarded.
+    pub fn replay(&self, recording: InputRecording) {
+        *self.replay.lock().unwrap() = Some(InputReplay::new(recording));
+    }
+
+    /// Returns true until all ticks of the replayed recording were applied.
+    pub fn is_replaying(&self) -> bool {
+        self.replay.lock().unwrap().is_some()
+    }
+
+    /// Applies the events received since the last tick, or the replayed events of the tick.
+    /// Executed before the update tick with the given index is started.
+    pub(crate) fn begin_tick(&self, tick: u64) {
+        let mut events = self.receiver.try_iter().collect::<Vec<_>>();
+        {
+            let mut replay_guard = self.replay.lock().unwrap();
+            if let Some(replay) = replay_guard.as_mut() {
+                if replay.is_starting() {
+                    *self.state.write().unwrap() = Default::default();
+                }
+                events = replay.replay(tick);
+                if replay.is_finished() {
+                    *replay_guard = None;
+                }
+            }
+        }
+        if let Some(recorder) = self.recorder.lock().unwrap().as_mut() {
+            recorder.record(tick, &events);
+        }
+        self.advance(&events);
+    }
+
+    pub(super) fn advance(&self, events: &[InputEvent]) {
+        let bindings = self.bindings.read().unwrap();
+        self.state.write().unwrap().advance(events, &bindings);
+    }
+}
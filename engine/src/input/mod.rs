@@ -0,0 +1,13 @@
+mod bindings;
+mod events;
+mod input_manager;
+mod recording;
+mod state;
+#[cfg(test)]
+mod tests;
+
+pub use bindings::*;
+pub use events::*;
+pub use input_manager::*;
+pub use recording::*;
+pub use state::*;
@@ -0,0 +1,119 @@
+use super::*;
+use crate::cbor_file::{load_cbor, save_cbor};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::path::Path;
+
+/// Input events applied during a single update tick.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedInput {
+    /// Update tick relative to the start of the recording.
+    pub tick: u64,
+    pub events: Vec<InputEvent>,
+}
+
+/// Input recorded by the [`InputManager`], see [`InputManager::start_recording`].
+///
+/// Replaying the recording applies the same events in the same update ticks, which reproduces the session
+/// as long as the game only depends on the input and the update ticks.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct InputRecording {
+    /// Amount of update ticks covered by the recording.
+    pub tick_count: u64,
+    /// Ticks without input are omitted.
+    pub ticks: Vec<RecordedInput>,
+}
+
+impl InputRecording {
+    /// Writes the recording as CBOR.
+    pub fn save(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        save_cbor(self, path)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        load_cbor(path)
+    }
+}
+
+/// Recording in progress. Its ticks start counting with the first update tick after it was started.
+#[derive(Debug)]
+pub(super) struct InputRecorder {
+    start_tick: Option<u64>,
+    tick_count: u64,
+    /// Buttons held when the recording started, pressed again at the start of the replay.
+    held: Vec<InputEvent>,
+    ticks: Vec<RecordedInput>,
+}
+
+impl InputRecorder {
+    pub(super) fn new(held: Vec<InputEvent>) -> Self {
+        Self {
+            start_tick: None,
+            tick_count: 0,
+            held,
+            ticks: vec![],
+        }
+    }
+
+    pub(super) fn record(&mut self, tick: u64, events: &[InputEvent]) {
+        let tick = tick - *self.start_tick.get_or_insert(tick);
+        self.tick_count = tick + 1;
+        let events = match tick {
+            0 => std::mem::take(&mut self.held)
+                .into_iter()
+                .chain(events.iter().cloned())
+                .collect::<Vec<_>>(),
+            _ => events.to_vec(),
+        };
+        if !events.is_empty() {
+            self.ticks.push(RecordedInput { tick, events });
+        }
+    }
+
+    pub(super) fn finish(self) -> InputRecording {
+        InputRecording {
+            tick_count: self.tick_count,
+            ticks: self.ticks,
+        }
+    }
+}
+
+/// Replay in progress. Its ticks start counting with the first update tick after it was started.
+#[derive(Debug)]
+pub(super) struct InputReplay {
+    start_tick: Option<u64>,
+    tick_count: u64,
+    ticks: VecDeque<RecordedInput>,
+}
+
+impl InputReplay {
+    pub(super) fn new(recording: InputRecording) -> Self {
+        let mut ticks = recording.ticks;
+        ticks.sort_by_key(|e| e.tick);
+        Self {
+            start_tick: None,
+            tick_count: recording.tick_count,
+            ticks: ticks.into(),
+        }
+    }
+
+    /// Returns true if the replay starts with this tick.
+    pub(super) fn is_starting(&self) -> bool {
+        self.start_tick.is_none()
+    }
+
+    /// Returns the recorded events of the tick.
+    pub(super) fn replay(&mut self, tick: u64) -> Vec<InputEvent> {
+        let tick = tick - *self.start_tick.get_or_insert(tick);
+        self.tick_count = self.tick_count.saturating_sub(1);
+        let mut events = vec![];
+        while self.ticks.front().is_some_and(|e| e.tick <= tick) {
+            events.extend(self.ticks.pop_front().unwrap().events);
+        }
+        events
+    }
+
+    pub(super) fn is_finished(&self) -> bool {
+        self.tick_count == 0 && self.ticks.is_empty()
+    }
+}
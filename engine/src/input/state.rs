@@ -0,0 +1,317 @@
+use super::*;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::hash::Hash;
+
+/// Buttons which are held down, and which changed during the last update tick.
+#[derive(Debug, Clone)]
+struct ButtonSet<T: Eq + Hash> {
+    down: HashSet<T>,
+    pressed: HashSet<T>,
+    released: HashSet<T>,
+}
+
+impl<T: Eq + Hash> Default for ButtonSet<T> {
+    fn default() -> Self {
+        Self {
+            down: Default::default(),
+            pressed: Default::default(),
+            released: Default::default(),
+        }
+    }
+}
+
+impl<T: Eq + Hash + Clone> ButtonSet<T> {
+    fn begin_tick(&mut self) {
+        self.pressed.clear();
+        self.released.clear();
+    }
+
+    fn apply(&mut self, button: T, state: ButtonState) {
+        match state {
+            ButtonState::Pressed => {
+                // Key repeats do not count as presses.
+                if self.down.insert(button.clone()) {
+                    self.pressed.insert(button);
+                }
+            }
+            ButtonState::Released => {
+                if self.down.remove(&button) {
+                    self.released.insert(button);
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct GamepadState {
+    buttons: ButtonSet<GamepadButton>,
+    axes: HashMap<GamepadAxis, f32>,
+}
+
+/// Input of the current update tick, see [`InputManager::state`].
+///
+/// Buttons pressed and released within the same tick are reported as pressed and released, but not as down.
+#[derive(Debug, Clone, Default)]
+pub struct InputState {
+    keys: ButtonSet<KeyCode>,
+    mouse_buttons: ButtonSet<MouseButton>,
+    gamepads: BTreeMap<GamepadId, GamepadState>,
+    actions: ButtonSet<String>,
+    axes: HashMap<String, f32>,
+    cursor_position: Option<(f32, f32)>,
+    scroll_delta: (f32, f32),
+    text: String,
+}
+
+impl InputState {
+    pub fn is_key_down(&self, key: KeyCode) -> bool {
+        self.keys.down.contains(&key)
+    }
+    pub fn was_key_pressed(&self, key: KeyCode) -> bool {
+        self.keys.pressed.contains(&key)
+    }
+    pub fn was_key_released(&self, key: KeyCode) -> bool {
+        self.keys.released.contains(&key)
+    }
+
+    pub fn is_mouse_button_down(&self, button: MouseButton) -> bool {
+        self.mouse_buttons.down.contains(&button)
+    }
+    pub fn was_mouse_button_pressed(&self, button: MouseButton) -> bool {
+        self.mouse_buttons.pressed.contains(&button)
+    }
+    pub fn was_mouse_button_released(&self, button: MouseButton) -> bool {
+        self.mouse_buttons.released.contains(&button)
+    }
+
+    /// Returns the connected gamepads, ordered by id.
+    pub fn gamepads(&self) -> Vec<GamepadId> {
+        self.gamepads.keys().copied().collect()
+    }
+    pub fn is_gamepad_connected(&self, gamepad: GamepadId) -> bool {
+        self.gamepads.contains_key(&gamepad)
+    }
+    pub fn is_gamepad_button_down(&self, gamepad: GamepadId, button: GamepadButton) -> bool {
+        self.gamepads
+            .get(&gamepad)
+            .is_some_and(|e| e.buttons.down.contains(&button))
+    }
+    pub fn was_gamepad_button_pressed(&self, gamepad: GamepadId, button: GamepadButton) -> bool {
+        self.gamepads
+            .get(&gamepad)
+            .is_some_and(|e| e.buttons.pressed.contains(&button))
+    }
+    pub fn was_gamepad_button_released(&self, gamepad: GamepadId, button: GamepadButton) -> bool {
+        self.gamepads
+            .get(&gamepad)
+            .is_some_and(|e| e.buttons.released.contains(&button))
+    }
+    /// Value of the gamepad axis, 0 for disconnected gamepads.
+    pub fn gamepad_axis(&self, gamepad: GamepadId, axis: GamepadAxis) -> f32 {
+        self.gamepads
+            .get(&gamepad)
+            .and_then(|e| e.axes.get(&axis).copied())
+            .unwrap_or(0.0)
+    }
+
+    /// Last known cursor position, None until the cursor moved.
+    pub fn cursor_position(&self) -> Option<(f32, f32)> {
+        self.cursor_position
+    }
+    /// Distance scrolled during the tick.
+    pub fn scroll_delta(&self) -> (f32, f32) {
+        self.scroll_delta
+    }
+    /// Text entered during the tick.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns true while any binding of the action is held down.
+    pub fn is_action_down(&self, action: &str) -> bool {
+        self.actions.down.contains(action)
+    }
+    /// Returns true if a binding of the action was pressed during the tick.
+    pub fn was_action_pressed(&self, action: &str) -> bool {
+        self.actions.pressed.contains(action)
+    }
+    /// Returns true if the last held binding of the action was released during the tick.
+    pub fn was_action_released(&self, action: &str) -> bool {
+        self.actions.released.contains(action)
+    }
+    /// Value of the axis between -1 and 1, 0 for unknown axes.
+    pub fn axis(&self, axis: &str) -> f32 {
+        self.axes.get(axis).copied().unwrap_or(0.0)
+    }
+
+    /// Returns the events which lead from an empty state to the buttons held and the cursor position of this state.
+    pub(super) fn held_events(&self) -> Vec<InputEvent> {
+        let keys = self.keys.down.iter().map(|key| InputEvent::Key {
+            key: *key,
+            state: ButtonState::Pressed,
+        });
+        let mouse_buttons = self
+            .mouse_buttons
+            .down
+            .iter()
+            .map(|button| InputEvent::MouseButton {
+                button: *button,
+                state: ButtonState::Pressed,
+            });
+        let cursor = self
+            .cursor_position
+            .map(|(x, y)| InputEvent::CursorMoved { x, y });
+        let gamepads = self.gamepads.iter().flat_map(|(gamepad, state)| {
+            let buttons = state
+                .buttons
+                .down
+                .iter()
+                .map(|button| InputEvent::GamepadButton {
+                    gamepad: *gamepad,
+                    button: *button,
+                    state: ButtonState::Pressed,
+                });
+            let axes =
+                state
+                    .axes
+                    .iter()
+                    .filter(|(_, value)| **value != 0.0)
+                    .map(|(axis, value)| InputEvent::GamepadAxis {
+                        gamepad: *gamepad,
+                        axis: *axis,
+                        value: *value,
+                    });
+            std::iter::once(InputEvent::GamepadConnected(*gamepad))
+                .chain(buttons)
+                .chain(axes)
+        });
+        keys.chain(mouse_buttons)
+            .chain(cursor)
+            .chain(gamepads)
+            .collect()
+    }
+
+    /// Applies the events received since the last tick and evaluates the bindings.
+    pub(super) fn advance(&mut self, events: &[InputEvent], bindings: &InputBindings) {
+        self.keys.begin_tick();
+        self.mouse_buttons.begin_tick();
+        self.gamepads
+            .values_mut()
+            .for_each(|e| e.buttons.begin_tick());
+        self.scroll_delta = (0.0, 0.0);
+        self.text.clear();
+        for event in events {
+            match event {
+                InputEvent::Key { key, state } => self.keys.apply(*key, *state),
+                InputEvent::MouseButton { button, state } => {
+                    self.mouse_buttons.apply(*button, *state)
+                }
+                InputEvent::CursorMoved { x, y } => self.cursor_position = Some((*x, *y)),
+                InputEvent::Scrolled { delta_x, delta_y } => {
+                    self.scroll_delta.0 += delta_x;
+                    self.scroll_delta.1 += delta_y;
+                }
+                InputEvent::Text(character) => self.text.push(*character),
+                InputEvent::GamepadConnected(gamepad) => {
+                    self.gamepads.entry(*gamepad).or_default();
+                }
+                InputEvent::GamepadDisconnected(gamepad) => {
+                    self.gamepads.remove(gamepad);
+                }
+                InputEvent::GamepadButton {
+                    gamepad,
+                    button,
+                    state,
+                } => self
+                    .gamepads
+                    .entry(*gamepad)
+                    .or_default()
+                    .buttons
+                    .apply(*button, *state),
+                InputEvent::GamepadAxis {
+                    gamepad,
+                    axis,
+                    value,
+                } => {
+                    self.gamepads
+                        .entry(*gamepad)
+                        .or_default()
+                        .axes
+                        .insert(*axis, *value);
+                }
+            }
+        }
+        self.evaluate_bindings(bindings);
+    }
+
+    fn evaluate_bindings(&mut self, bindings: &InputBindings) {
+        self.actions.begin_tick();
+        for (action, action_bindings) in &bindings.actions {
+            let pressed = action_bindings.iter().any(|e| self.was_pressed(e));
+            let down = action_bindings.iter().any(|e| self.is_down(e));
+            let was_down = self.actions.down.contains(action);
+            if pressed {
+                self.actions.pressed.insert(action.clone());
+            }
+            if down {
+                self.actions.down.insert(action.clone());
+            } else {
+                self.actions.down.remove(action);
+                if was_down || action_bindings.iter().any(|e| self.was_released(e)) {
+                    self.actions.released.insert(action.clone());
+                }
+            }
+        }
+        // Actions which were unbound while held down.
+        self.actions
+            .down
+            .retain(|e| bindings.actions.contains_key(e));
+
+        self.axes.clear();
+        for (axis, binding) in &bindings.axes {
+            let positive = binding.positive.iter().any(|e| self.is_down(e)) as i32;
+            let negative = binding.negative.iter().any(|e| self.is_down(e)) as i32;
+            let analog = self
+                .gamepads
+                .values()
+                .flat_map(|e| binding.gamepad_axes.iter().filter_map(|a| e.axes.get(a)))
+                .fold(0.0f32, |max, e| if e.abs() > max.abs() { *e } else { max });
+            let value = ((positive - negative) as f32 + analog).clamp(-1.0, 1.0);
+            self.axes.insert(axis.clone(), value);
+        }
+    }
+
+    fn is_down(&self, binding: &InputBinding) -> bool {
+        match binding {
+            InputBinding::Key(key) => self.is_key_down(*key),
+            InputBinding::MouseButton(button) => self.is_mouse_button_down(*button),
+            InputBinding::GamepadButton(button) => self
+                .gamepads
+                .values()
+                .any(|e| e.buttons.down.contains(button)),
+        }
+    }
+
+    fn was_pressed(&self, binding: &InputBinding) -> bool {
+        match binding {
+            InputBinding::Key(key) => self.was_key_pressed(*key),
+            InputBinding::MouseButton(button) => self.was_mouse_button_pressed(*button),
+            InputBinding::GamepadButton(button) => self
+                .gamepads
+                .values()
+                .any(|e| e.buttons.pressed.contains(button)),
+        }
+    }
+
+    fn was_released(&self, binding: &InputBinding) -> bool {
+        match binding {
+            InputBinding::Key(key) => self.was_key_released(*key),
+            InputBinding::MouseButton(button) => self.was_mouse_button_released(*button),
+            InputBinding::GamepadButton(button) => self
+                .gamepads
+                .values()
+                .any(|e| e.buttons.released.contains(button)),
+        }
+    }
+}
@@ -0,0 +1,253 @@
+use super::*;
+use crate::message_bus::MessageBus;
+use assets::AssetSerializationFormat;
+
+#[test]
+fn test_input_state() {
+    let bus = MessageBus::default();
+    let input = InputManager::new(&bus);
+    let sender = bus.get_sender::<InputEvent>();
+    sender.send(InputEvent::key(KeyCode::W, ButtonState::Pressed));
+    sender.send(InputEvent::key(KeyCode::W, ButtonState::Pressed));
+    sender.send(InputEvent::CursorMoved { x: 4.0, y: 2.0 });
+    sender.send(InputEvent::Scrolled {
+        delta_x: 0.0,
+        delta_y: 1.0,
+    });
+    sender.send(InputEvent::Scrolled {
+        delta_x: 0.0,
+        delta_y: 2.0,
+    });
+    sender.send(InputEvent::Text('h'));
+    sender.send(InputEvent::Text('i'));
+    input.begin_tick(0);
+    {
+        let state = input.state();
+        assert!(state.is_key_down(KeyCode::W));
+        assert!(state.was_key_pressed(KeyCode::W));
+        assert!(!state.was_key_released(KeyCode::W));
+        assert_eq!(state.cursor_position(), Some((4.0, 2.0)));
+        assert_eq!(state.scroll_delta(), (0.0, 3.0));
+        assert_eq!(state.text(), "hi");
+    }
+
+    sender.send(InputEvent::key(KeyCode::W, ButtonState::Released));
+    sender.send(InputEvent::MouseButton {
+        button: MouseButton::Left,
+        state: ButtonState::Pressed,
+    });
+    sender.send(InputEvent::MouseButton {
+        button: MouseButton::Left,
+        state: ButtonState::Released,
+    });
+    input.begin_tick(1);
+    let state = input.state();
+    assert!(!state.is_key_down(KeyCode::W));
+    assert!(state.was_key_released(KeyCode::W));
+    assert!(state.was_mouse_button_pressed(MouseButton::Left));
+    assert!(state.was_mouse_button_released(MouseButton::Left));
+    assert!(!state.is_mouse_button_down(MouseButton::Left));
+    assert_eq!(state.cursor_position(), Some((4.0, 2.0)));
+    assert_eq!(state.scroll_delta(), (0.0, 0.0));
+    assert_eq!(state.text(), "");
+}
+
+#[test]
+fn test_actions_and_axes() {
+    let bindings = InputBindings::from_bytes(
+        br#"
+            [actions]
+            jump = [{ key = "Space" }, { mouse_button = "Left" }]
+
+            [axes.move_x]
+            positive = [{ key = "D" }]
+            negative = [{ key = "A" }]
+        "#,
+        AssetSerializationFormat::Toml,
+    )
+    .unwrap();
+    let bus = MessageBus::default();
+    let input = InputManager::new(&bus);
+    input.set_bindings(bindings);
+
+    input.advance(&[
+        InputEvent::key(KeyCode::Space, ButtonState::Pressed),
+        InputEvent::key(KeyCode::D, ButtonState::Pressed),
+    ]);
+    assert!(input.state().was_action_pressed("jump"));
+    assert!(input.state().is_action_down("jump"));
+    assert_eq!(input.state().axis("move_x"), 1.0);
+
+    // Holding a second binding keeps the action down.
+    input.advance(&[
+        InputEvent::MouseButton {
+            button: MouseButton::Left,
+            state: ButtonState::Pressed,
+        },
+        InputEvent::key(KeyCode::Space, ButtonState::Released),
+        InputEvent::key(KeyCode::A, ButtonState::Pressed),
+    ]);
+    assert!(input.state().was_action_pressed("jump"));
+    assert!(!input.state().was_action_released("jump"));
+    assert!(input.state().is_action_down("jump"));
+    assert_eq!(input.state().axis("move_x"), 0.0);
+
+    input.advance(&[InputEvent::MouseButton {
+        button: MouseButton::Left,
+        state: ButtonState::Released,
+    }]);
+    assert!(input.state().was_action_released("jump"));
+    assert!(!input.state().is_action_down("jump"));
+    assert_eq!(input.state().axis("unknown"), 0.0);
+}
+
+#[test]
+fn test_rebinding() {
+    let bus = MessageBus::default();
+    let input = InputManager::new(&bus);
+    input.bind_action("fire", vec![InputBinding::Key(KeyCode::F)]);
+    input.advance(&[InputEvent::key(KeyCode::F, ButtonState::Pressed)]);
+    assert!(input.state().is_action_down("fire"));
+
+    input.bind_action("fire", vec![InputBinding::Key(KeyCode::G)]);
+    input.advance(&[]);
+    assert!(!input.state().is_action_down("fire"));
+    assert!(input.state().was_action_released("fire"));
+
+    input.advance(&[InputEvent::key(KeyCode::G, ButtonState::Pressed)]);
+    assert!(input.state().was_action_pressed("fire"));
+
+    input.bind_action("fire", vec![]);
+    input.advance(&[]);
+    assert!(!input.state().is_action_down("fire"));
+    assert!(input.bindings().actions.is_empty());
+
+    let bindings = input.bindings();
+    let bytes = serde_cbor::to_vec(&bindings).unwrap();
+    let decoded = InputBindings::from_bytes(&bytes, AssetSerializationFormat::Binary).unwrap();
+    assert_eq!(decoded, bindings);
+}
+
+#[test]
+fn test_record_and_replay_input() {
+    let bus = MessageBus::default();
+    let input = InputManager::new(&bus);
+    input.bind_action("jump", vec![InputBinding::Key(KeyCode::Space)]);
+    let sender = bus.get_sender::<InputEvent>();
+    sender.send(InputEvent::key(KeyCode::W, ButtonState::Pressed));
+    input.begin_tick(4);
+
+    input.start_recording();
+    sender.send(InputEvent::key(KeyCode::Space, ButtonState::Pressed));
+    input.begin_tick(5);
+    input.begin_tick(6);
+    sender.send(InputEvent::key(KeyCode::Space, ButtonState::Released));
+    input.begin_tick(7);
+    input.begin_tick(8);
+    let recording = input.stop_recording().unwrap();
+    assert!(!input.is_recording());
+    assert_eq!(recording.tick_count, 4);
+    assert_eq!(
+        recording.ticks,
+        vec![
+            RecordedInput {
+                tick: 0,
+                events: vec![
+                    InputEvent::key(KeyCode::W, ButtonState::Pressed),
+                    InputEvent::key(KeyCode::Space, ButtonState::Pressed)
+                ],
+            },
+            RecordedInput {
+                tick: 2,
+                events: vec![InputEvent::key(KeyCode::Space, ButtonState::Released)],
+            },
+        ]
+    );
+
+    let path = std::env::temp_dir().join(format!("input_{}.rec", std::process::id()));
+    recording.save(&path).unwrap();
+    let loaded = InputRecording::load(&path).unwrap();
+    let _ = std::fs::remove_file(&path);
+    assert_eq!(loaded, recording);
+
+    let bus = MessageBus::default();
+    let replayed = InputManager::new(&bus);
+    replayed.set_bindings(input.bindings());
+    replayed.replay(loaded);
+    let mut jumps = vec![];
+    for tick in 0..4 {
+        // Live input is ignored while replaying.
+        bus.get_sender()
+            .send(InputEvent::key(KeyCode::A, ButtonState::Pressed));
+        replayed.begin_tick(tick);
+        let state = replayed.state();
+        assert!(state.is_key_down(KeyCode::W));
+        assert!(!state.is_key_down(KeyCode::A));
+        jumps.push(state.is_action_down("jump"));
+    }
+    assert_eq!(jumps, vec![true, true, false, false]);
+    assert!(!replayed.is_replaying());
+
+    bus.get_sender()
+        .send(InputEvent::key(KeyCode::A, ButtonState::Pressed));
+    replayed.begin_tick(4);
+    assert!(replayed.state().is_key_down(KeyCode::A));
+}
+
+#[test]
+fn test_gamepad_input() {
+    let pad = GamepadId(1);
+    let bus = MessageBus::default();
+    let input = InputManager::new(&bus);
+    input.bind_action(
+        "jump",
+        vec![InputBinding::GamepadButton(GamepadButton::South)],
+    );
+    input.bind_axis(
+        "move_x",
+        AxisBinding {
+            positive: vec![InputBinding::Key(KeyCode::D)],
+            gamepad_axes: vec![GamepadAxis::LeftStickX],
+            ..Default::default()
+        },
+    );
+    input.advance(&[
+        InputEvent::GamepadConnected(pad),
+        InputEvent::GamepadConnected(GamepadId(0)),
+        InputEvent::GamepadButton {
+            gamepad: pad,
+            button: GamepadButton::South,
+            state: ButtonState::Pressed,
+        },
+        InputEvent::GamepadAxis {
+            gamepad: pad,
+            axis: GamepadAxis::LeftStickX,
+            value: -0.5,
+        },
+        InputEvent::GamepadAxis {
+            gamepad: GamepadId(0),
+            axis: GamepadAxis::LeftStickX,
+            value: 0.25,
+        },
+    ]);
+    {
+        let state = input.state();
+        assert_eq!(state.gamepads(), vec![GamepadId(0), pad]);
+        assert!(state.was_gamepad_button_pressed(pad, GamepadButton::South));
+        assert!(!state.is_gamepad_button_down(GamepadId(0), GamepadButton::South));
+        assert!(state.was_action_pressed("jump"));
+        assert_eq!(state.gamepad_axis(pad, GamepadAxis::LeftStickX), -0.5);
+        assert_eq!(state.axis("move_x"), -0.5);
+    }
+
+    input.advance(&[InputEvent::key(KeyCode::D, ButtonState::Pressed)]);
+    assert_eq!(input.state().axis("move_x"), 0.5);
+
+    // Disconnecting releases the held buttons.
+    input.advance(&[InputEvent::GamepadDisconnected(pad)]);
+    let state = input.state();
+    assert!(!state.is_gamepad_connected(pad));
+    assert!(state.was_action_released("jump"));
+    assert_eq!(state.gamepad_axis(pad, GamepadAxis::LeftStickX), 0.0);
+    assert_eq!(state.axis("move_x"), 1.0);
+}
@@ -1,16 +1,24 @@
+mod asset_decoding;
+mod cbor_file;
+pub mod config;
 mod engine;
 pub mod engine_stages;
+pub mod input;
 pub mod message_bus;
 pub mod platform;
+pub mod render_world;
 pub mod resource_manager;
 pub mod scene_manager;
+mod value_merge;
 
 pub use engine::{
-    controller::EngineController, create_info::*, result::EngineUpdateResult, Engine,
+    clock::*, commands::*, controller::EngineController, create_info::*, frame_stats::*,
+    result::EngineUpdateResult, stage_error::*, Engine,
 };
 pub use engine_stages::{
     RenderStage, RenderStageConstructor, RenderStageConstructorInput, RenderStageUpdateInput,
-    UpdateStage, UpdateStageConstructor, UpdateStageConstructorInput, UpdateStageUpdateInput,
+    StageQueue, UpdateStage, UpdateStageConstructor, UpdateStageConstructorInput,
+    UpdateStageUpdateInput,
 };
 pub use message_bus::*;
 pub use platform::*;
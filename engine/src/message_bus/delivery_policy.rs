@@ -0,0 +1,58 @@
+use super::*;
+
+/// How messages of a type are queued for each of their handlers.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum DeliveryPolicy {
+    /// Every message is queued until it is received.
+    #[default]
+    Unbounded,
+    /// Queues at most the given amount of messages, further messages are dropped until the handler caught up.
+    DropNewest(usize),
+    /// Queues at most the given amount of messages, dropping the oldest queued message when full.
+    DropOldest(usize),
+    /// Only the latest message is queued, e.g. for window sizes.
+    Coalesce,
+}
+
+impl DeliveryPolicy {
+    /// Capacity of the handler queues, None if unbounded.
+    pub(super) fn capacity(&self) -> Option<usize> {
+        match self {
+            DeliveryPolicy::Unbounded => None,
+            DeliveryPolicy::DropNewest(capacity) | DeliveryPolicy::DropOldest(capacity) => {
+                Some((*capacity).max(1))
+            }
+            DeliveryPolicy::Coalesce => Some(1),
+        }
+    }
+
+    pub(super) fn drops_oldest(&self) -> bool {
+        matches!(
+            self,
+            DeliveryPolicy::DropOldest(_) | DeliveryPolicy::Coalesce
+        )
+    }
+}
+
+/// Sets the delivery policy of a single message type.
+type DeliveryPolicySetter = Box<dyn Fn(&MessageBus) + Send + Sync>;
+
+/// Delivery policies applied to the [`MessageBus`] of the engine before stages register their handlers.
+#[derive(Default)]
+pub struct MessageDeliveryPolicies {
+    policies: Vec<DeliveryPolicySetter>,
+}
+
+impl MessageDeliveryPolicies {
+    pub fn with<M: Message>(mut self, policy: DeliveryPolicy) -> Self {
+        self.policies
+            .push(Box::new(move |bus| bus.set_delivery_policy::<M>(policy)));
+        self
+    }
+
+    pub(crate) fn apply(&self, message_bus: &MessageBus) {
+        for policy in &self.policies {
+            policy(message_bus);
+        }
+    }
+}
@@ -0,0 +1,29 @@
+use super::*;
+use crossbeam::channel::Receiver;
+use std::ops::Deref;
+use std::sync::Arc;
+
+/// Receiving end of a message handler, see [`MessageBus::add_handler`].
+/// The handler is removed from the bus once its receiver is dropped.
+#[derive(Debug)]
+pub struct HandlerReceiver<M: Message> {
+    receiver: Receiver<M>,
+    _alive: Arc<()>,
+}
+
+impl<M: Message> HandlerReceiver<M> {
+    pub(super) fn new(receiver: Receiver<M>, alive: Arc<()>) -> Self {
+        Self {
+            receiver,
+            _alive: alive,
+        }
+    }
+}
+
+impl<M: Message> Deref for HandlerReceiver<M> {
+    type Target = Receiver<M>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.receiver
+    }
+}
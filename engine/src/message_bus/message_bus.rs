@@ -1,103 +1,126 @@
 use crate::message_bus::message_sender::MessageSender;
-use crate::message_bus::Message;
-use crossbeam::channel::{unbounded, Receiver, Sender};
-use std::any::{Any, TypeId};
+use crate::message_bus::subscription::{receive_subscribed_messages, SubscriptionList};
+use crate::message_bus::{DeliveryPolicy, HandlerReceiver, Message, MessageTracer, Subscription};
+use std::any::{type_name, Any, TypeId};
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 
 #[repr(u8)]
-#[derive(Copy, Clone)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum MessageHandlerType {
     Render = 0,
     Update = 1,
 }
 
-pub struct MessageBusBuilder {
-    channels: HashMap<TypeId, Box<dyn Any + Sync + Send>>,
-    finalization_handlers: Vec<fn(map: &mut HashMap<TypeId, Box<dyn Any + Sync + Send>>)>,
+struct MessageChannel {
+    sender: Box<dyn Any + Send + Sync>,
+    type_name: &'static str,
+    overflow_count: fn(&dyn Any) -> u64,
 }
 
-impl Default for MessageBusBuilder {
+/// Routes messages to all handlers registered for their type.
+/// Handlers can be added at any time. A handler is removed once its receiver is dropped.
+/// Messages are queued for each handler according to the [`DeliveryPolicy`] of their type.
+pub struct MessageBus {
+    channels: RwLock<HashMap<TypeId, MessageChannel>>,
+    subscriptions: Arc<SubscriptionList>,
+    tracer: Arc<MessageTracer>,
+}
+
+impl Default for MessageBus {
     fn default() -> Self {
         Self {
             channels: Default::default(),
-            finalization_handlers: vec![],
+            subscriptions: Default::default(),
+            tracer: Default::default(),
         }
     }
 }
 
-impl MessageBusBuilder {
-    fn finalize<M: Message>(map: &mut HashMap<TypeId, Box<dyn Any + Send + Sync>>) {
-        let value = map.remove(&TypeId::of::<M>()).unwrap();
-        let (render, update): (Vec<Sender<M>>, Vec<Sender<M>>) = *(value
-            .downcast::<(Vec<Sender<M>>, Vec<Sender<M>>)>()
-            .unwrap());
-        let values: Arc<(Vec<Sender<M>>, Vec<Sender<M>>)> = Arc::new((render, update));
-        map.insert(TypeId::of::<M>(), Box::from(MessageSender::new(values)));
+impl MessageBus {
+    /// Adds a handler for `M`, returning the receiver its messages are delivered to.
+    pub fn add_handler<M: Message>(&self, handler_type: MessageHandlerType) -> HandlerReceiver<M> {
+        self.get_sender::<M>().add_handler(handler_type)
     }
 
-    fn get_senders_mut<M: Message>(
-        &mut self,
+    /// Subscribes the callback to messages of type `M`, until the returned subscription is dropped.
+    /// The engine calls the callback on the given thread, after the stages of the thread processed their messages.
+    pub fn subscribe<M: Message>(
+        &self,
         handler_type: MessageHandlerType,
-    ) -> Option<&mut Vec<Sender<M>>> {
-        return if let Some(value) = self.channels.get_mut(&TypeId::of::<M>()) {
-            let (render_senders, update_senders) = value
-                .downcast_mut::<(Vec<Sender<M>>, Vec<Sender<M>>)>()
-                .unwrap();
-            match handler_type {
-                MessageHandlerType::Render => Some(render_senders),
-                MessageHandlerType::Update => Some(update_senders),
-            }
-        } else {
-            None
-        };
+        callback: impl FnMut(M) + Send + 'static,
+    ) -> Subscription {
+        let receiver = self.add_handler::<M>(handler_type);
+        Subscription::new(&self.subscriptions, handler_type, receiver, callback)
     }
 
-    pub fn add_update_handler<M: Message>(
-        &mut self,
-        handler_type: MessageHandlerType,
-    ) -> Receiver<M> {
-        return match self.get_senders_mut::<M>(handler_type) {
-            Some(values) => {
-                let (sender, receiver) = unbounded();
-                values.push(sender);
-                receiver
-            }
-            None => {
-                self.channels.insert(
-                    TypeId::of::<M>(),
-                    Box::from((Vec::<Sender<M>>::new(), Vec::<Sender<M>>::new())),
-                );
-                self.finalization_handlers.push(Self::finalize::<M>);
-                let values = self.get_senders_mut(handler_type).unwrap();
-                let (sender, receiver) = unbounded();
-                values.push(sender);
-                receiver
-            }
-        };
+    /// Unsubscribes the callback, which is the same as dropping the subscription.
+    pub fn unsubscribe(&self, subscription: Subscription) {
+        drop(subscription);
     }
 
-    pub fn build(mut self) -> MessageBus {
-        for handler in self.finalization_handlers {
-            (handler)(&mut self.channels);
-        }
-        MessageBus {
-            channels: self.channels,
-        }
+    /// Calls the subscribed callbacks of the given thread with their queued messages.
+    pub(crate) fn receive_subscribed_messages(&self, handler_type: MessageHandlerType) {
+        receive_subscribed_messages(&self.subscriptions, handler_type);
     }
-}
 
-pub struct MessageBus {
-    channels: HashMap<TypeId, Box<dyn Any + Send + Sync>>,
-}
+    /// Returns the tracer, which records the messages sent through the bus once started.
+    pub fn tracer(&self) -> &MessageTracer {
+        &self.tracer
+    }
 
-impl MessageBus {
-    pub fn get_sender<M: Message>(&self) -> Option<MessageSender<M>> {
-        return if let Some(v) = self.channels.get(&TypeId::of::<M>()) {
-            let sender = v.downcast_ref::<MessageSender<M>>().unwrap();
-            Some(sender.clone())
-        } else {
-            None
-        };
+    /// Replays traced messages recorded before the given update tick, see [`MessageTracer::replay`].
+    /// Called before each update job is started.
+    pub(crate) fn begin_update_tick(&self, tick: u64) {
+        self.tracer.begin_update_tick(self, tick);
     }
+
+    /// Sets how messages of type `M` are queued for handlers added afterwards.
+    pub fn set_delivery_policy<M: Message>(&self, policy: DeliveryPolicy) {
+        self.get_sender::<M>().set_delivery_policy(policy);
+    }
+
+    /// Returns the amount of messages of type `M` dropped or coalesced because a handler queue was full.
+    pub fn overflow_count<M: Message>(&self) -> u64 {
+        self.get_sender::<M>().overflow_count()
+    }
+
+    /// Returns the type names and overflow counts of all message types which overflowed.
+    pub fn overflow_counts(&self) -> Vec<(&'static str, u64)> {
+        self.channels
+            .read()
+            .unwrap()
+            .values()
+            .map(|e| (e.type_name, (e.overflow_count)(e.sender.as_ref())))
+            .filter(|(_, count)| *count > 0)
+            .collect()
+    }
+
+    /// Returns the sender for messages of type `M`.
+    /// Messages are delivered to the handlers registered at the time they are sent.
+    pub fn get_sender<M: Message>(&self) -> MessageSender<M> {
+        if let Some(v) = self.channels.read().unwrap().get(&TypeId::of::<M>()) {
+            return v.sender.downcast_ref::<MessageSender<M>>().unwrap().clone();
+        }
+        self.channels
+            .write()
+            .unwrap()
+            .entry(TypeId::of::<M>())
+            .or_insert_with(|| MessageChannel {
+                sender: Box::new(MessageSender::<M>::traced(Arc::clone(&self.tracer))),
+                type_name: type_name::<M>(),
+                overflow_count: overflow_count_of::<M>,
+            })
+            .sender
+            .downcast_ref::<MessageSender<M>>()
+            .unwrap()
+            .clone()
+    }
+}
+
+fn overflow_count_of<M: Message>(sender: &dyn Any) -> u64 {
+    sender
+        .downcast_ref::<MessageSender<M>>()
+        .unwrap()
+        .overflow_count()
 }
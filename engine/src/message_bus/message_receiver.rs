@@ -1,15 +1,14 @@
 use super::*;
 use crate::engine_stages::{RenderStageMessageContext, UpdateStageMessageContext};
-use crossbeam::channel::*;
 use std::marker::PhantomData;
 
 pub struct MessageReceiver<C, M: Message, T: MessageHandler<C, M>> {
-    receiver: Receiver<M>,
+    receiver: HandlerReceiver<M>,
     _phantom: PhantomData<fn(C, T)>,
 }
 
 impl<C, M: Message, T: MessageHandler<C, M>> MessageReceiver<C, M, T> {
-    pub fn new(receiver: Receiver<M>) -> Self {
+    pub fn new(receiver: HandlerReceiver<M>) -> Self {
         Self {
             receiver,
             _phantom: Default::default(),
@@ -1,52 +1,143 @@
 use super::*;
-use crossbeam::channel::Sender;
-use std::sync::Arc;
-use utils::t_warn;
+use crossbeam::channel::{bounded, unbounded, Receiver, Sender, TrySendError};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+
+#[derive(Debug)]
+struct HandlerChannel<M: Message> {
+    sender: Sender<M>,
+    /// Second receiving end used to drop the oldest message of a full queue.
+    oldest: Option<Receiver<M>>,
+    /// Dangling once the [`HandlerReceiver`] was dropped.
+    alive: Weak<()>,
+}
+
+#[derive(Debug)]
+struct SenderState<M: Message> {
+    policy: DeliveryPolicy,
+    render: Vec<HandlerChannel<M>>,
+    update: Vec<HandlerChannel<M>>,
+}
 
 #[derive(Debug)]
 pub struct MessageSender<M: Message> {
-    senders: Arc<(Vec<Sender<M>>, Vec<Sender<M>>)>,
+    /// Render and update thread channels. Channels whose receiver was dropped are removed when sending.
+    state: Arc<Mutex<SenderState<M>>>,
+    /// Amount of messages dropped because a handler queue was full.
+    overflow_count: Arc<AtomicU64>,
+    tracer: Option<Arc<MessageTracer>>,
 }
 
 impl<M: Message> Clone for MessageSender<M> {
     fn clone(&self) -> Self {
         Self {
-            senders: Arc::clone(&self.senders),
+            state: Arc::clone(&self.state),
+            overflow_count: Arc::clone(&self.overflow_count),
+            tracer: self.tracer.clone(),
+        }
+    }
+}
+
+impl<M: Message> Default for MessageSender<M> {
+    fn default() -> Self {
+        Self {
+            state: Arc::new(Mutex::new(SenderState {
+                policy: Default::default(),
+                render: vec![],
+                update: vec![],
+            })),
+            overflow_count: Default::default(),
+            tracer: None,
         }
     }
 }
 
 impl<M: Message> MessageSender<M> {
-    pub fn new(senders: Arc<(Vec<Sender<M>>, Vec<Sender<M>>)>) -> Self {
-        Self { senders }
+    pub(super) fn traced(tracer: Arc<MessageTracer>) -> Self {
+        Self {
+            tracer: Some(tracer),
+            ..Default::default()
+        }
+    }
+
+    pub(super) fn add_handler(&self, handler_type: MessageHandlerType) -> HandlerReceiver<M> {
+        let mut state = self.state.lock().unwrap();
+        let (sender, receiver) = match state.policy.capacity() {
+            Some(capacity) => bounded(capacity),
+            None => unbounded(),
+        };
+        let alive = Arc::new(());
+        let channel = HandlerChannel {
+            sender,
+            oldest: state.policy.drops_oldest().then(|| receiver.clone()),
+            alive: Arc::downgrade(&alive),
+        };
+        match handler_type {
+            MessageHandlerType::Render => state.render.push(channel),
+            MessageHandlerType::Update => state.update.push(channel),
+        }
+        HandlerReceiver::new(receiver, alive)
+    }
+
+    /// The policy only applies to handlers added afterwards.
+    pub(super) fn set_delivery_policy(&self, policy: DeliveryPolicy) {
+        self.state.lock().unwrap().policy = policy;
+    }
+
+    pub fn delivery_policy(&self) -> DeliveryPolicy {
+        self.state.lock().unwrap().policy
+    }
+
+    /// Returns the amount of messages dropped or coalesced because a handler queue was full.
+    pub fn overflow_count(&self) -> u64 {
+        self.overflow_count.load(Ordering::Relaxed)
     }
 
     pub fn send(&self, message: M) {
-        self.senders.0.iter().for_each(|s| {
-            if let Err(e) = s.send(message.clone()) {
-                t_warn!("Could not send message: {}", e);
-            }
-        });
-        self.senders.1.iter().for_each(|s| {
-            if let Err(e) = s.send(message.clone()) {
-                t_warn!("Could not send message: {}", e);
-            }
-        });
+        self.trace(&message, MessageDirection::Both);
+        let mut state = self.state.lock().unwrap();
+        self.send_to(&mut state.render, &message);
+        self.send_to(&mut state.update, &message);
     }
 
     pub fn send_to_update_thread(&self, message: M) {
-        self.senders.1.iter().for_each(|s| {
-            if let Err(e) = s.send(message.clone()) {
-                t_warn!("Could not send message: {}", e);
-            }
-        });
+        self.trace(&message, MessageDirection::Update);
+        self.send_to(&mut self.state.lock().unwrap().update, &message);
     }
 
     pub fn send_to_render_thread(&self, message: M) {
-        self.senders.0.iter().for_each(|s| {
-            if let Err(e) = s.send(message.clone()) {
-                t_warn!("Could not send message: {}", e);
+        self.trace(&message, MessageDirection::Render);
+        self.send_to(&mut self.state.lock().unwrap().render, &message);
+    }
+
+    fn trace(&self, message: &M, direction: MessageDirection) {
+        if let Some(tracer) = &self.tracer {
+            if tracer.is_recording() {
+                tracer.record(message, direction);
+            }
+        }
+    }
+
+    fn send_to(&self, channels: &mut Vec<HandlerChannel<M>>, message: &M) {
+        channels.retain(|e| e.alive.strong_count() > 0);
+        for channel in channels.iter() {
+            let mut message = message.clone();
+            loop {
+                match channel.sender.try_send(message) {
+                    Ok(()) => break,
+                    Err(TrySendError::Full(rejected)) => {
+                        self.overflow_count.fetch_add(1, Ordering::Relaxed);
+                        match &channel.oldest {
+                            Some(oldest) => {
+                                let _ = oldest.try_recv();
+                                message = rejected;
+                            }
+                            None => break,
+                        }
+                    }
+                    Err(TrySendError::Disconnected(_)) => break,
+                }
             }
-        });
+        }
     }
 }
@@ -1,13 +1,25 @@
+mod delivery_policy;
+mod handler_receiver;
 mod message;
 mod message_bus;
 mod message_handler;
 mod message_receiver;
 mod message_sender;
 mod registerers;
+mod request;
+mod subscription;
+#[cfg(test)]
+mod tests;
+mod tracer;
 
+pub use delivery_policy::*;
+pub use handler_receiver::*;
 pub use message::*;
 pub use message_bus::*;
 pub use message_handler::*;
 pub use message_receiver::*;
 pub use message_sender::*;
 pub use registerers::*;
+pub use request::*;
+pub use subscription::*;
+pub use tracer::*;
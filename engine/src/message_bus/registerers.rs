@@ -1,23 +1,22 @@
 use super::*;
 use crate::engine_stages::{RenderStageMessageContext, UpdateStageMessageContext};
-use crossbeam::channel::*;
 use std::marker::PhantomData;
 
 pub struct AnyMessageRegisterer<'a> {
-    builder: &'a mut MessageBusBuilder,
+    message_bus: &'a MessageBus,
     handler_type: MessageHandlerType,
 }
 
 impl<'a> AnyMessageRegisterer<'a> {
-    pub fn new(builder: &'a mut MessageBusBuilder, handler_type: MessageHandlerType) -> Self {
+    pub fn new(message_bus: &'a MessageBus, handler_type: MessageHandlerType) -> Self {
         Self {
-            builder,
+            message_bus,
             handler_type,
         }
     }
 
-    pub fn register<M: Message>(&mut self) -> Receiver<M> {
-        self.builder.add_update_handler::<M>(self.handler_type)
+    pub fn register<M: Message>(&mut self) -> HandlerReceiver<M> {
+        self.message_bus.add_handler::<M>(self.handler_type)
     }
 }
 
@@ -0,0 +1,139 @@
+use super::*;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+struct ResponseSlot<R> {
+    response: Option<R>,
+    responded: bool,
+    /// Amount of live copies of the request.
+    requests: usize,
+    waker: Option<Waker>,
+}
+
+/// Message expecting a response, sent through [`MessageSender::request`].
+/// Every handler receives a copy of the request, only the first response is delivered to the requester.
+/// The request is cancelled once all copies were dropped without responding.
+pub struct Request<Q: Message, R: Send + 'static> {
+    payload: Q,
+    slot: Arc<Mutex<ResponseSlot<R>>>,
+}
+
+impl<Q: Message, R: Send + 'static> Clone for Request<Q, R> {
+    fn clone(&self) -> Self {
+        self.slot.lock().unwrap().requests += 1;
+        Self {
+            payload: self.payload.clone(),
+            slot: Arc::clone(&self.slot),
+        }
+    }
+}
+
+impl<Q: Message, R: Send + 'static> Drop for Request<Q, R> {
+    fn drop(&mut self) {
+        let waker = {
+            let mut slot = self.slot.lock().unwrap();
+            slot.requests -= 1;
+            match slot.requests {
+                0 => slot.waker.take(),
+                _ => None,
+            }
+        };
+        // Woken without holding the lock, the requester may be polled right away.
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+impl<Q: Message, R: Send + 'static> Request<Q, R> {
+    pub fn payload(&self) -> &Q {
+        &self.payload
+    }
+
+    /// Delivers the response to the requester. Returns false if another handler already responded.
+    pub fn respond(&self, response: R) -> bool {
+        let waker = {
+            let mut slot = self.slot.lock().unwrap();
+            if slot.responded {
+                return false;
+            }
+            slot.response = Some(response);
+            slot.responded = true;
+            slot.waker.take()
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+        true
+    }
+}
+
+/// One-shot response to a [`Request`], which can be polled or awaited.
+pub struct PendingResponse<R: Send + 'static> {
+    slot: Arc<Mutex<ResponseSlot<R>>>,
+}
+
+impl<R: Send + 'static> PendingResponse<R> {
+    /// Takes the response if it arrived.
+    pub fn try_receive(&self) -> Option<R> {
+        self.slot.lock().unwrap().response.take()
+    }
+
+    /// Returns true if no response will arrive, because no handler received the request
+    /// or all handlers dropped it without responding.
+    pub fn is_cancelled(&self) -> bool {
+        let slot = self.slot.lock().unwrap();
+        slot.requests == 0 && !slot.responded
+    }
+}
+
+impl<R: Send + 'static> Future for PendingResponse<R> {
+    /// None if the request was cancelled or the response was already taken.
+    type Output = Option<R>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut slot = self.slot.lock().unwrap();
+        if slot.responded || slot.requests == 0 {
+            return Poll::Ready(slot.response.take());
+        }
+        slot.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+impl<Q: Message, R: Send + 'static> MessageSender<Request<Q, R>> {
+    /// Sends the request to the handlers of both threads.
+    pub fn request(&self, payload: Q) -> PendingResponse<R> {
+        let (request, response) = Self::create_request(payload);
+        self.send(request);
+        response
+    }
+
+    pub fn request_to_update_thread(&self, payload: Q) -> PendingResponse<R> {
+        let (request, response) = Self::create_request(payload);
+        self.send_to_update_thread(request);
+        response
+    }
+
+    pub fn request_to_render_thread(&self, payload: Q) -> PendingResponse<R> {
+        let (request, response) = Self::create_request(payload);
+        self.send_to_render_thread(request);
+        response
+    }
+
+    fn create_request(payload: Q) -> (Request<Q, R>, PendingResponse<R>) {
+        let slot = Arc::new(Mutex::new(ResponseSlot {
+            response: None,
+            responded: false,
+            requests: 1,
+            waker: None,
+        }));
+        let request = Request {
+            payload,
+            slot: Arc::clone(&slot),
+        };
+        (request, PendingResponse { slot })
+    }
+}
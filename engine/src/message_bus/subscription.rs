@@ -0,0 +1,79 @@
+use super::*;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+
+pub(super) type SubscriptionList = Mutex<Vec<Arc<SubscriptionEntry>>>;
+
+pub(super) struct SubscriptionEntry {
+    handler_type: MessageHandlerType,
+    /// Passes the queued messages to the callback.
+    receive: Mutex<Box<dyn FnMut() + Send>>,
+    active: AtomicBool,
+}
+
+/// Callback registered through [`MessageBus::subscribe`], for subscribers which are not stages such as plugins.
+/// The callback is unregistered once the subscription is dropped.
+pub struct Subscription {
+    entry: Arc<SubscriptionEntry>,
+    subscriptions: Weak<SubscriptionList>,
+}
+
+impl Subscription {
+    pub(super) fn new<M: Message>(
+        subscriptions: &Arc<SubscriptionList>,
+        handler_type: MessageHandlerType,
+        receiver: HandlerReceiver<M>,
+        mut callback: impl FnMut(M) + Send + 'static,
+    ) -> Self {
+        let entry = Arc::new(SubscriptionEntry {
+            handler_type,
+            receive: Mutex::new(Box::new(move || {
+                for message in receiver.try_iter() {
+                    callback(message);
+                }
+            })),
+            active: AtomicBool::new(true),
+        });
+        subscriptions.lock().unwrap().push(Arc::clone(&entry));
+        Self {
+            entry,
+            subscriptions: Arc::downgrade(subscriptions),
+        }
+    }
+
+    pub fn handler_type(&self) -> MessageHandlerType {
+        self.entry.handler_type
+    }
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        self.entry.active.store(false, Ordering::Release);
+        if let Some(subscriptions) = self.subscriptions.upgrade() {
+            subscriptions
+                .lock()
+                .unwrap()
+                .retain(|e| !Arc::ptr_eq(e, &self.entry));
+        }
+    }
+}
+
+/// Calls the callbacks of the given thread with their queued messages.
+/// Callbacks may subscribe and unsubscribe, new subscriptions receive their messages in the next call.
+pub(super) fn receive_subscribed_messages(
+    subscriptions: &SubscriptionList,
+    handler_type: MessageHandlerType,
+) {
+    let entries = subscriptions
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|e| e.handler_type == handler_type)
+        .cloned()
+        .collect::<Vec<_>>();
+    for entry in entries {
+        if entry.active.load(Ordering::Acquire) {
+            (entry.receive.lock().unwrap())();
+        }
+    }
+}
@@ -0,0 +1,232 @@
+use super::*;
+use serde::{Deserialize, Serialize};
+use std::num::NonZeroUsize;
+use std::sync::{Arc, Mutex};
+use utils::dispatcher::Dispatcher;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+struct Resized(u32);
+
+fn received(receiver: &HandlerReceiver<Resized>) -> Vec<u32> {
+    receiver.try_iter().map(|e| e.0).collect()
+}
+
+#[test]
+fn test_unbounded_delivery() {
+    let bus = MessageBus::default();
+    let update = bus.add_handler::<Resized>(MessageHandlerType::Update);
+    let render = bus.add_handler::<Resized>(MessageHandlerType::Render);
+    let sender = bus.get_sender::<Resized>();
+    (0..4).for_each(|e| sender.send(Resized(e)));
+    sender.send_to_render_thread(Resized(4));
+
+    assert_eq!(received(&update), vec![0, 1, 2, 3]);
+    assert_eq!(received(&render), vec![0, 1, 2, 3, 4]);
+    assert_eq!(bus.overflow_count::<Resized>(), 0);
+}
+
+#[test]
+fn test_bounded_delivery() {
+    let bus = MessageBus::default();
+    bus.set_delivery_policy::<Resized>(DeliveryPolicy::DropNewest(2));
+    let newest = bus.add_handler::<Resized>(MessageHandlerType::Update);
+    bus.set_delivery_policy::<Resized>(DeliveryPolicy::DropOldest(2));
+    let oldest = bus.add_handler::<Resized>(MessageHandlerType::Update);
+    let sender = bus.get_sender::<Resized>();
+    (0..4).for_each(|e| sender.send(Resized(e)));
+
+    assert_eq!(received(&newest), vec![0, 1]);
+    assert_eq!(received(&oldest), vec![2, 3]);
+    assert_eq!(bus.overflow_count::<Resized>(), 4);
+    assert_eq!(
+        bus.overflow_counts(),
+        vec![(std::any::type_name::<Resized>(), 4)]
+    );
+}
+
+#[test]
+fn test_coalesced_delivery() {
+    let bus = MessageBus::default();
+    MessageDeliveryPolicies::default()
+        .with::<Resized>(DeliveryPolicy::Coalesce)
+        .apply(&bus);
+    let receiver = bus.add_handler::<Resized>(MessageHandlerType::Render);
+    let sender = bus.get_sender::<Resized>();
+    (0..3).for_each(|e| sender.send(Resized(e)));
+    assert_eq!(received(&receiver), vec![2]);
+
+    sender.send(Resized(3));
+    assert_eq!(received(&receiver), vec![3]);
+}
+
+#[test]
+fn test_dropped_handler_is_removed() {
+    let bus = MessageBus::default();
+    bus.set_delivery_policy::<Resized>(DeliveryPolicy::Coalesce);
+    let receiver = bus.add_handler::<Resized>(MessageHandlerType::Update);
+    let sender = bus.get_sender::<Resized>();
+    sender.send(Resized(0));
+    drop(receiver);
+
+    sender.send(Resized(1));
+    assert_eq!(bus.overflow_count::<Resized>(), 0);
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+struct SwapchainExtent;
+
+type ExtentRequest = Request<SwapchainExtent, (u32, u32)>;
+
+#[test]
+fn test_request_response() {
+    let bus = MessageBus::default();
+    let render = bus.add_handler::<ExtentRequest>(MessageHandlerType::Render);
+    let update = bus.add_handler::<ExtentRequest>(MessageHandlerType::Update);
+    let response = bus
+        .get_sender::<ExtentRequest>()
+        .request_to_render_thread(SwapchainExtent);
+    assert!(update.try_recv().is_err());
+    assert!(response.try_receive().is_none());
+    assert!(!response.is_cancelled());
+
+    let request = render.try_recv().unwrap();
+    assert_eq!(*request.payload(), SwapchainExtent);
+    assert!(request.respond((800, 600)));
+    assert!(!request.respond((1024, 768)));
+    assert_eq!(response.try_receive(), Some((800, 600)));
+}
+
+#[test]
+fn test_request_cancelled() {
+    let bus = MessageBus::default();
+    let sender = bus.get_sender::<ExtentRequest>();
+    assert!(sender.request(SwapchainExtent).is_cancelled());
+
+    let render = bus.add_handler::<ExtentRequest>(MessageHandlerType::Render);
+    let update = bus.add_handler::<ExtentRequest>(MessageHandlerType::Update);
+    let response = sender.request(SwapchainExtent);
+    drop(render.try_recv().unwrap());
+    assert!(!response.is_cancelled());
+    drop(update);
+    assert!(response.is_cancelled());
+}
+
+#[test]
+fn test_await_response() {
+    let dispatcher = Dispatcher::new(
+        Some(NonZeroUsize::new(1).unwrap()),
+        NonZeroUsize::new(1).unwrap(),
+        Some(NonZeroUsize::new(1).unwrap()),
+        NonZeroUsize::new(1).unwrap(),
+    )
+    .unwrap();
+    let bus = MessageBus::default();
+    let render = bus.add_handler::<ExtentRequest>(MessageHandlerType::Render);
+    let response = bus.get_sender::<ExtentRequest>().request(SwapchainExtent);
+    let handler = std::thread::spawn(move || {
+        let request = render.recv().unwrap();
+        request.respond((1920, 1080));
+    });
+    assert_eq!(
+        dispatcher.spawn_async_blocking(response),
+        Some((1920, 1080))
+    );
+    handler.join().unwrap();
+}
+
+#[test]
+fn test_subscriptions() {
+    let bus = MessageBus::default();
+    let received = Arc::new(Mutex::new(vec![]));
+    let update = {
+        let received = Arc::clone(&received);
+        bus.subscribe::<Resized>(MessageHandlerType::Update, move |e| {
+            received.lock().unwrap().push(e.0)
+        })
+    };
+    let sender = bus.get_sender::<Resized>();
+    sender.send(Resized(0));
+    sender.send_to_update_thread(Resized(1));
+    bus.receive_subscribed_messages(MessageHandlerType::Render);
+    assert!(received.lock().unwrap().is_empty());
+    bus.receive_subscribed_messages(MessageHandlerType::Update);
+    assert_eq!(*received.lock().unwrap(), vec![0, 1]);
+
+    sender.send(Resized(2));
+    bus.unsubscribe(update);
+    bus.receive_subscribed_messages(MessageHandlerType::Update);
+    assert_eq!(*received.lock().unwrap(), vec![0, 1]);
+}
+
+#[test]
+fn test_subscribe_from_callback() {
+    let bus = Arc::new(MessageBus::default());
+    let subscriptions = Arc::new(Mutex::new(vec![]));
+    let subscription = {
+        let callback_bus = Arc::clone(&bus);
+        let subscriptions = Arc::clone(&subscriptions);
+        bus.subscribe::<Resized>(MessageHandlerType::Render, move |_| {
+            let subscription =
+                callback_bus.subscribe::<SwapchainExtent>(MessageHandlerType::Render, |_| {});
+            subscriptions.lock().unwrap().push(subscription);
+        })
+    };
+    bus.get_sender::<Resized>().send(Resized(0));
+    bus.receive_subscribed_messages(MessageHandlerType::Render);
+    assert_eq!(subscriptions.lock().unwrap().len(), 1);
+
+    // Unsubscribing releases the callback and the bus it captured.
+    bus.unsubscribe(subscription);
+    subscriptions.lock().unwrap().clear();
+    assert_eq!(Arc::strong_count(&bus), 1);
+}
+
+#[test]
+fn test_trace_and_replay() {
+    let bus = MessageBus::default();
+    bus.tracer().register_payload::<Resized>();
+    bus.tracer().start_recording();
+    let sender = bus.get_sender::<Resized>();
+    sender.send(Resized(0));
+    bus.begin_update_tick(0);
+    sender.send_to_render_thread(Resized(1));
+    bus.get_sender::<SwapchainExtent>().send(SwapchainExtent);
+    let trace = bus.tracer().stop_recording();
+    sender.send(Resized(2));
+
+    assert_eq!(
+        trace
+            .messages
+            .iter()
+            .map(|e| (e.tick, e.direction, e.payload.is_some()))
+            .collect::<Vec<_>>(),
+        vec![
+            (0, MessageDirection::Both, true),
+            (1, MessageDirection::Render, true),
+            (1, MessageDirection::Both, false)
+        ]
+    );
+    assert_eq!(
+        trace.messages[2].type_name,
+        std::any::type_name::<SwapchainExtent>()
+    );
+
+    let path = std::env::temp_dir().join("zircon_engine_test_message_trace.cbor");
+    trace.save(&path).unwrap();
+    let loaded = MessageTrace::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(loaded, trace);
+
+    let replay_bus = MessageBus::default();
+    replay_bus.tracer().register_payload::<Resized>();
+    let update = replay_bus.add_handler::<Resized>(MessageHandlerType::Update);
+    let render = replay_bus.add_handler::<Resized>(MessageHandlerType::Render);
+    replay_bus.tracer().replay(loaded);
+    replay_bus.begin_update_tick(0);
+    assert_eq!(received(&update), vec![0]);
+    assert_eq!(received(&render), vec![0]);
+    replay_bus.begin_update_tick(1);
+    assert!(received(&update).is_empty());
+    assert_eq!(received(&render), vec![1]);
+    assert!(!replay_bus.tracer().is_replaying());
+}
@@ -0,0 +1,214 @@
+use super::*;
+use crate::cbor_file::{load_cbor, save_cbor};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_cbor::Value;
+use std::any::{type_name, Any, TypeId};
+use std::collections::{HashMap, VecDeque};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Mutex, RwLock};
+use std::time::{Duration, Instant};
+use utils::*;
+
+/// Threads a message was sent to.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum MessageDirection {
+    Both,
+    Update,
+    Render,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TracedMessage {
+    /// Amount of update ticks started before the message was sent.
+    pub tick: u64,
+    /// Time since the recording started.
+    pub timestamp: Duration,
+    pub type_name: String,
+    pub direction: MessageDirection,
+    /// Only captured for types registered through [`MessageTracer::register_payload`].
+    pub payload: Option<Value>,
+}
+
+/// Messages recorded by the [`MessageTracer`], in the order they were sent.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MessageTrace {
+    pub messages: Vec<TracedMessage>,
+}
+
+impl MessageTrace {
+    /// Writes the trace as CBOR.
+    pub fn save(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        save_cbor(self, path)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        load_cbor(path)
+    }
+}
+
+type PayloadSerializer = fn(&dyn Any) -> Option<Value>;
+type PayloadSender = fn(&MessageBus, Value, MessageDirection) -> Result<(), String>;
+
+/// Opt-in recording of the messages sent through a [`MessageBus`], see [`MessageBus::tracer`].
+///
+/// A recorded trace can be replayed into another engine, e.g. a headless engine, to reproduce bugs
+/// which depend on the order of messages. Only messages with captured payloads can be replayed.
+pub struct MessageTracer {
+    recording: AtomicBool,
+    tick: AtomicU64,
+    started: Mutex<Instant>,
+    messages: Mutex<Vec<TracedMessage>>,
+    payloads: RwLock<HashMap<TypeId, PayloadSerializer>>,
+    /// Keyed by type name, used to replay messages.
+    senders: RwLock<HashMap<&'static str, PayloadSender>>,
+    replay: Mutex<VecDeque<TracedMessage>>,
+}
+
+impl Default for MessageTracer {
+    fn default() -> Self {
+        Self {
+            recording: AtomicBool::new(false),
+            tick: AtomicU64::new(0),
+            started: Mutex::new(Instant::now()),
+            messages: Default::default(),
+            payloads: Default::default(),
+            senders: Default::default(),
+            replay: Default::default(),
+        }
+    }
+}
+
+impl std::fmt::Debug for MessageTracer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MessageTracer")
+            .field("recording", &self.is_recording())
+            .field("tick", &self.tick())
+            .finish()
+    }
+}
+
+impl MessageTracer {
+    /// Captures the payloads of messages of type `M`, which makes them replayable.
+    pub fn register_payload<M: Message + Serialize + DeserializeOwned>(&self) {
+        self.payloads
+            .write()
+            .unwrap()
+            .insert(TypeId::of::<M>(), serialize_payload::<M>);
+        self.senders
+            .write()
+            .unwrap()
+            .insert(type_name::<M>(), send_payload::<M>);
+    }
+
+    /// Starts recording, discarding messages recorded before.
+    pub fn start_recording(&self) {
+        self.messages.lock().unwrap().clear();
+        *self.started.lock().unwrap() = Instant::now();
+        self.recording.store(true, Ordering::Release);
+    }
+
+    /// Stops recording and returns the recorded messages.
+    pub fn stop_recording(&self) -> MessageTrace {
+        self.recording.store(false, Ordering::Release);
+        MessageTrace {
+            messages: std::mem::take(&mut *self.messages.lock().unwrap()),
+        }
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recording.load(Ordering::Acquire)
+    }
+
+    /// Amount of update ticks started so far.
+    pub fn tick(&self) -> u64 {
+        self.tick.load(Ordering::Acquire)
+    }
+
+    /// Sends the messages of the trace again, each before the first update tick started after it was recorded.
+    /// Messages without payload are skipped.
+    pub fn replay(&self, trace: MessageTrace) {
+        let mut messages = trace.messages;
+        messages.sort_by_key(|e| e.tick);
+        self.replay.lock().unwrap().extend(messages);
+    }
+
+    /// Returns true while replayed messages are pending.
+    pub fn is_replaying(&self) -> bool {
+        !self.replay.lock().unwrap().is_empty()
+    }
+
+    pub(super) fn record<M: Message>(&self, message: &M, direction: MessageDirection) {
+        let payload = self
+            .payloads
+            .read()
+            .unwrap()
+            .get(&TypeId::of::<M>())
+            .and_then(|serialize| serialize(message));
+        let timestamp = self.started.lock().unwrap().elapsed();
+        self.messages.lock().unwrap().push(TracedMessage {
+            tick: self.tick(),
+            timestamp,
+            type_name: String::from(type_name::<M>()),
+            direction,
+            payload,
+        });
+    }
+
+    /// Replays the messages recorded before the update tick with the given index was started, then counts it.
+    pub(super) fn begin_update_tick(&self, message_bus: &MessageBus, tick: u64) {
+        let mut pending = vec![];
+        {
+            let mut replay = self.replay.lock().unwrap();
+            while replay.front().is_some_and(|e| e.tick <= tick) {
+                pending.extend(replay.pop_front());
+            }
+        }
+        for message in pending {
+            let Some(payload) = message.payload else {
+                continue;
+            };
+            let send = self
+                .senders
+                .read()
+                .unwrap()
+                .get(message.type_name.as_str())
+                .copied();
+            let result = match send {
+                Some(send) => send(message_bus, payload, message.direction),
+                None => Err(String::from("The payload type is not registered.")),
+            };
+            if let Err(e) = result {
+                t_warn!("Could not replay message {}: {}", message.type_name, e);
+            }
+        }
+        self.tick.store(tick + 1, Ordering::Release);
+    }
+}
+
+fn serialize_payload<M: Message + Serialize>(message: &dyn Any) -> Option<Value> {
+    let message = message.downcast_ref::<M>()?;
+    match serde_cbor::value::to_value(message) {
+        Ok(v) => Some(v),
+        Err(e) => {
+            t_warn!("Could not capture message {}: {}", type_name::<M>(), e);
+            None
+        }
+    }
+}
+
+fn send_payload<M: Message + DeserializeOwned>(
+    message_bus: &MessageBus,
+    payload: Value,
+    direction: MessageDirection,
+) -> Result<(), String> {
+    let message = serde_cbor::value::from_value::<M>(payload).map_err(|e| e.to_string())?;
+    let sender = message_bus.get_sender::<M>();
+    match direction {
+        MessageDirection::Both => sender.send(message),
+        MessageDirection::Update => sender.send_to_update_thread(message),
+        MessageDirection::Render => sender.send_to_render_thread(message),
+    }
+    Ok(())
+}
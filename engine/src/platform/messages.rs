@@ -1,15 +1,19 @@
+use serde::{Deserialize, Serialize};
 use utils::defer_drop::*;
 
 use super::PlatformWindowHandle;
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct WindowDidOpen {
     pub window: PlatformWindowHandle,
 }
 
-#[derive(Clone, Debug)]
+/// The window is kept alive until all receivers dropped the message.
+/// Replayed messages do not keep any window alive, as their window was closed while recording.
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct WindowWillClose {
     pub window: PlatformWindowHandle,
+    #[serde(skip)]
     _resource_dropper: Option<DeferDrop>,
 }
 
@@ -23,7 +27,7 @@ impl WindowWillClose {
 }
 unsafe impl Send for WindowWillClose {}
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct WindowDidResize {
     pub window: PlatformWindowHandle,
     pub new_width: u32,
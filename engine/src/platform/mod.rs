@@ -1,4 +1,5 @@
 use crate::engine::controller::EngineController;
+use crate::engine::stage_error::StageError;
 
 mod interface;
 mod messages;
@@ -11,5 +12,6 @@ pub use window::*;
 /// Trait that is used to control the state of the game engine and interact with the OS windowing library.
 pub trait Platform {
     /// Execute this function to run the game engine on this platform.
-    fn run(self, controller: EngineController);
+    /// Returns once the engine stopped, with the error that stopped it, if any.
+    fn run(self, controller: EngineController) -> Result<(), StageError>;
 }
@@ -0,0 +1,17 @@
+/// Values which can be blended between two update ticks for rendering.
+pub trait Interpolate {
+    /// Blends from `self` towards `next`, `alpha` ranges from 0 (`self`) to 1 (`next`).
+    fn interpolate(&self, next: &Self, alpha: f32) -> Self;
+}
+
+impl Interpolate for f32 {
+    fn interpolate(&self, next: &Self, alpha: f32) -> Self {
+        self + (next - self) * alpha
+    }
+}
+
+impl Interpolate for f64 {
+    fn interpolate(&self, next: &Self, alpha: f32) -> Self {
+        self + (next - self) * alpha as f64
+    }
+}
@@ -0,0 +1,7 @@
+mod interpolate;
+mod snapshot;
+mod world;
+
+pub use interpolate::*;
+pub use snapshot::*;
+pub use world::*;
@@ -0,0 +1,161 @@
+use super::Interpolate;
+use crate::scene_manager::SceneHandle;
+use shard_ecs::Entity;
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Entity of a scene. Entities of different scenes may share the same [`Entity`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SceneEntity {
+    pub scene: SceneHandle,
+    pub entity: Entity,
+}
+
+impl SceneEntity {
+    fn key(&self) -> (SceneHandle, u32) {
+        (self.scene, self.entity.raw())
+    }
+}
+
+/// Values extracted from the components of all entities of all scenes for a single type.
+pub struct ExtractedComponents<T> {
+    entities: Vec<SceneEntity>,
+    values: Vec<T>,
+    lookup: HashMap<(SceneHandle, u32), usize>,
+}
+
+impl<T> Default for ExtractedComponents<T> {
+    fn default() -> Self {
+        Self {
+            entities: vec![],
+            values: vec![],
+            lookup: Default::default(),
+        }
+    }
+}
+
+impl<T> ExtractedComponents<T> {
+    pub(super) fn push(&mut self, entity: SceneEntity, value: T) {
+        self.lookup.insert(entity.key(), self.values.len());
+        self.entities.push(entity);
+        self.values.push(value);
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn get(&self, entity: SceneEntity) -> Option<&T> {
+        self.lookup.get(&entity.key()).map(|idx| &self.values[*idx])
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (SceneEntity, &T)> {
+        self.entities.iter().copied().zip(self.values.iter())
+    }
+}
+
+/// Immutable copy of the render relevant game state, taken at the end of an update tick.
+#[derive(Default)]
+pub struct RenderSnapshot {
+    update_index: u64,
+    components: HashMap<TypeId, Box<dyn Any + Send + Sync>>,
+}
+
+impl RenderSnapshot {
+    pub(super) fn new(update_index: u64) -> Self {
+        Self {
+            update_index,
+            components: Default::default(),
+        }
+    }
+
+    pub(super) fn components_mut<T: Send + Sync + 'static>(
+        &mut self,
+    ) -> &mut ExtractedComponents<T> {
+        self.components
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| Box::new(ExtractedComponents::<T>::default()))
+            .downcast_mut::<ExtractedComponents<T>>()
+            .unwrap()
+    }
+
+    /// Amount of update ticks which were extracted before this snapshot, starting at 1.
+    /// The empty snapshot published before the first update has index 0.
+    pub fn update_index(&self) -> u64 {
+        self.update_index
+    }
+
+    /// Returns all extracted values of type `T`, or `None` if no extractor produces `T`.
+    pub fn components<T: Send + Sync + 'static>(&self) -> Option<&ExtractedComponents<T>> {
+        self.components
+            .get(&TypeId::of::<T>())?
+            .downcast_ref::<ExtractedComponents<T>>()
+    }
+
+    pub fn get<T: Send + Sync + 'static>(&self, entity: SceneEntity) -> Option<&T> {
+        self.components::<T>()?.get(entity)
+    }
+}
+
+/// The two most recently published snapshots.
+/// Rendering happens between both, as described by `alpha_till_next_update`.
+#[derive(Clone, Default)]
+pub struct RenderSnapshots {
+    previous: Arc<RenderSnapshot>,
+    current: Arc<RenderSnapshot>,
+}
+
+impl RenderSnapshots {
+    pub(super) fn advance(&self, next: Arc<RenderSnapshot>) -> Self {
+        Self {
+            previous: Arc::clone(&self.current),
+            current: next,
+        }
+    }
+
+    pub fn previous(&self) -> &RenderSnapshot {
+        &self.previous
+    }
+
+    pub fn current(&self) -> &RenderSnapshot {
+        &self.current
+    }
+
+    /// Interpolates the value of the entity between both snapshots.
+    /// Entities which did not exist in the previous snapshot use their current value.
+    pub fn interpolate<T: Interpolate + Clone + Send + Sync + 'static>(
+        &self,
+        entity: SceneEntity,
+        alpha: f32,
+    ) -> Option<T> {
+        let current = self.current.get::<T>(entity)?;
+        Some(match self.previous.get::<T>(entity) {
+            Some(previous) => previous.interpolate(current, alpha),
+            None => current.clone(),
+        })
+    }
+
+    /// Iterates all entities of the current snapshot, interpolating their values.
+    pub fn iter_interpolated<T: Interpolate + Clone + Send + Sync + 'static>(
+        &self,
+        alpha: f32,
+    ) -> impl Iterator<Item = (SceneEntity, T)> + '_ {
+        let previous = self.previous.components::<T>();
+        self.current
+            .components::<T>()
+            .into_iter()
+            .flat_map(|e| e.iter())
+            .map(move |(entity, current)| {
+                let value = match previous.and_then(|e| e.get(entity)) {
+                    Some(previous) => previous.interpolate(current, alpha),
+                    None => current.clone(),
+                };
+                (entity, value)
+            })
+    }
+}
@@ -0,0 +1,90 @@
+use super::*;
+use crate::scene_manager::{SceneHandle, SceneManager};
+use shard_ecs::{Component, Registry};
+use std::any::{type_name, TypeId};
+use std::sync::{Arc, Mutex, RwLock};
+use utils::*;
+
+type Extractor = Box<dyn Fn(SceneHandle, &Registry, &mut RenderSnapshot) + Send + Sync>;
+
+/// Engine resource through which render stages read game state.
+///
+/// At the end of every update tick the registered extractors copy components of every scene into a new
+/// [`RenderSnapshot`]. Once the engine collects the result of that tick on the main thread, the snapshot is published
+/// as the current one and the former current snapshot becomes the previous one. Both stay unchanged until the next
+/// update tick is collected, so render stages can interpolate between them with `alpha_till_next_update`.
+#[derive(Default)]
+pub struct RenderWorld {
+    extractors: RwLock<Vec<(TypeId, Extractor)>>,
+    /// Snapshot extracted by the last update job, not published yet.
+    pending: Mutex<Option<Arc<RenderSnapshot>>>,
+    snapshots: RwLock<RenderSnapshots>,
+    update_counter: Mutex<u64>,
+}
+
+impl RenderWorld {
+    /// Copies the component `C` of every entity into the snapshots.
+    pub fn register_component<C: Component + Clone>(&self) {
+        self.register_extractor::<C, C>(C::clone);
+    }
+
+    /// Extracts a value of type `T` from the component `C` of every entity into the snapshots.
+    /// Useful for components which are too expensive to copy every tick.
+    /// Each extracted type may only be produced by a single extractor, further registrations are ignored.
+    pub fn register_extractor<C: Component, T: Send + Sync + 'static>(
+        &self,
+        extract: impl Fn(&C) -> T + Send + Sync + 'static,
+    ) {
+        let mut extractors = self.extractors.write().unwrap();
+        if extractors.iter().any(|(id, _)| *id == TypeId::of::<T>()) {
+            t_warn!(
+                "An extractor for {} is already registered, ignoring.",
+                type_name::<T>()
+            );
+            return;
+        }
+        extractors.push((
+            TypeId::of::<T>(),
+            Box::new(move |scene, registry, snapshot| {
+                let extracted = snapshot.components_mut::<T>();
+                for (entities, components) in registry.iter_entity_components_matching::<C>() {
+                    for (entity, component) in entities.iter().zip(components) {
+                        let entity = SceneEntity {
+                            scene,
+                            entity: *entity,
+                        };
+                        extracted.push(entity, extract(component));
+                    }
+                }
+            }),
+        ));
+    }
+
+    /// Returns the previous and current snapshot.
+    pub fn snapshots(&self) -> RenderSnapshots {
+        self.snapshots.read().unwrap().clone()
+    }
+
+    /// Runs all extractors on the registry of every scene.
+    /// Executed on the update thread at the end of each update tick.
+    pub(crate) fn extract(&self, scene_manager: &SceneManager) {
+        let mut update_counter = self.update_counter.lock().unwrap();
+        *update_counter += 1;
+        let mut snapshot = RenderSnapshot::new(*update_counter);
+        for (_, extractor) in self.extractors.read().unwrap().iter() {
+            for scene in scene_manager.scenes() {
+                extractor(scene.handle(), scene.registry(), &mut snapshot);
+            }
+        }
+        *self.pending.lock().unwrap() = Some(Arc::new(snapshot));
+    }
+
+    /// Publishes the last extracted snapshot, if any.
+    /// Executed on the main thread once the result of an update tick is collected.
+    pub(crate) fn publish(&self) {
+        if let Some(next) = self.pending.lock().unwrap().take() {
+            let mut snapshots = self.snapshots.write().unwrap();
+            *snapshots = snapshots.advance(next);
+        }
+    }
+}
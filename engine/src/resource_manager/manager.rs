@@ -1,33 +1,192 @@
+use crate::message_bus::{MessageBus, MessageSender};
+use dashmap::mapref::entry::Entry;
 use dashmap::*;
-use std::any::{Any, TypeId};
-use std::sync::Arc;
+use std::any::{type_name, Any, TypeId};
+use std::sync::{Arc, RwLock};
+use utils::*;
+
+/// Sent after a resource was added, including when it replaced a resource of the same type.
+#[derive(Debug, Copy, Clone)]
+pub struct ResourceWasAdded {
+    pub type_id: TypeId,
+    pub type_name: &'static str,
+}
+
+impl ResourceWasAdded {
+    pub fn is<T: 'static>(&self) -> bool {
+        self.type_id == TypeId::of::<T>()
+    }
+}
+
+/// Sent after a resource was removed or replaced.
+#[derive(Debug, Copy, Clone)]
+pub struct ResourceWasRemoved {
+    pub type_id: TypeId,
+    pub type_name: &'static str,
+}
+
+impl ResourceWasRemoved {
+    pub fn is<T: 'static>(&self) -> bool {
+        self.type_id == TypeId::of::<T>()
+    }
+}
+
+struct ResourceMessageSenders {
+    was_added: MessageSender<ResourceWasAdded>,
+    was_removed: MessageSender<ResourceWasRemoved>,
+}
 
 #[derive(Debug)]
+struct ResourceEntry {
+    value: Arc<dyn Any + Send + Sync>,
+    type_name: &'static str,
+    /// Identifier of the stage owning a scoped resource.
+    owner: Option<&'static str>,
+}
+
+/// Engine wide resources, one per type.
+/// Once the engine is initialized, [`ResourceWasAdded`] and [`ResourceWasRemoved`] are sent through the [`MessageBus`].
 pub struct EngineResourceManager {
-    engine_resources: DashMap<TypeId, Arc<dyn Any + Send + Sync>>,
+    engine_resources: DashMap<TypeId, ResourceEntry>,
+    senders: RwLock<Option<ResourceMessageSenders>>,
 }
 
 impl Default for EngineResourceManager {
     fn default() -> Self {
         Self {
             engine_resources: Default::default(),
+            senders: Default::default(),
         }
     }
 }
 
+impl std::fmt::Debug for EngineResourceManager {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EngineResourceManager")
+            .field("engine_resources", &self.engine_resources)
+            .finish()
+    }
+}
+
 impl EngineResourceManager {
-    pub fn add_resource<T: Send + Sync + 'static>(&self, resource: T) {
-        self.engine_resources
-            .insert(TypeId::of::<T>(), Arc::from(resource));
+    /// Sends resource messages through the bus from now on.
+    pub(crate) fn publish_messages(&self, message_bus: &MessageBus) {
+        *self.senders.write().unwrap() = Some(ResourceMessageSenders {
+            was_added: message_bus.get_sender(),
+            was_removed: message_bus.get_sender(),
+        });
+    }
+
+    /// Adds the resource, replacing and returning the resource of the same type if there is one.
+    pub fn add_resource<T: Send + Sync + 'static>(&self, resource: T) -> Option<Arc<T>> {
+        self.insert(resource, None)
+    }
+
+    /// Adds the resource unless a resource of the same type exists, in which case it is handed back.
+    pub fn try_add_resource<T: Send + Sync + 'static>(&self, resource: T) -> Result<(), T> {
+        match self.engine_resources.entry(TypeId::of::<T>()) {
+            Entry::Occupied(_) => return Err(resource),
+            Entry::Vacant(entry) => {
+                entry.insert(ResourceEntry {
+                    value: Arc::new(resource),
+                    type_name: type_name::<T>(),
+                    owner: None,
+                });
+            }
+        }
+        self.send_added::<T>();
+        Ok(())
+    }
+
+    /// Adds a resource owned by the stage with the given identifier, replacing the resource of the same type.
+    /// The resource is removed after the stage was shut down, see [`Self::remove_scoped_resources`].
+    pub fn add_scoped_resource<T: Send + Sync + 'static>(
+        &self,
+        owner: &'static str,
+        resource: T,
+    ) -> Option<Arc<T>> {
+        self.insert(resource, Some(owner))
     }
 
     pub fn remove_resource<T: Send + Sync + 'static>(&self) -> Option<Arc<T>> {
-        let (_key, value) = self.engine_resources.remove(&TypeId::of::<T>())?;
-        Some(value.downcast::<T>().unwrap())
+        let (_key, entry) = self.engine_resources.remove(&TypeId::of::<T>())?;
+        self.send_removed(TypeId::of::<T>(), entry.type_name);
+        Some(entry.value.downcast::<T>().unwrap())
+    }
+
+    /// Removes all resources owned by the stage with the given identifier.
+    pub fn remove_scoped_resources(&self, owner: &str) {
+        let owned = self
+            .engine_resources
+            .iter()
+            .filter(|e| e.owner == Some(owner))
+            .map(|e| *e.key())
+            .collect::<Vec<_>>();
+        for type_id in owned {
+            if let Some((_key, entry)) = self
+                .engine_resources
+                .remove_if(&type_id, |_, e| e.owner == Some(owner))
+            {
+                t_info!("Removed resource {} of stage {}.", entry.type_name, owner);
+                self.send_removed(type_id, entry.type_name);
+            }
+        }
     }
 
     pub fn get_resource<T: Send + Sync + 'static>(&self) -> Option<Arc<T>> {
-        let value = self.engine_resources.get(&TypeId::of::<T>())?;
-        Some(Arc::clone(value.value()).downcast::<T>().unwrap())
+        let entry = self.engine_resources.get(&TypeId::of::<T>())?;
+        Some(Arc::clone(&entry.value).downcast::<T>().unwrap())
+    }
+
+    /// Returns the resource, which must have been added before.
+    pub fn resource<T: Send + Sync + 'static>(&self) -> Arc<T> {
+        match self.get_resource::<T>() {
+            Some(v) => v,
+            None => {
+                t_fatal!("Resource {} has not been added!", type_name::<T>());
+            }
+        }
+    }
+
+    pub fn contains<T: Send + Sync + 'static>(&self) -> bool {
+        self.engine_resources.contains_key(&TypeId::of::<T>())
+    }
+
+    fn insert<T: Send + Sync + 'static>(
+        &self,
+        resource: T,
+        owner: Option<&'static str>,
+    ) -> Option<Arc<T>> {
+        let previous = self.engine_resources.insert(
+            TypeId::of::<T>(),
+            ResourceEntry {
+                value: Arc::new(resource),
+                type_name: type_name::<T>(),
+                owner,
+            },
+        );
+        let previous = previous.map(|e| {
+            self.send_removed(TypeId::of::<T>(), e.type_name);
+            e.value.downcast::<T>().unwrap()
+        });
+        self.send_added::<T>();
+        previous
+    }
+
+    fn send_added<T: 'static>(&self) {
+        if let Some(senders) = &*self.senders.read().unwrap() {
+            senders.was_added.send(ResourceWasAdded {
+                type_id: TypeId::of::<T>(),
+                type_name: type_name::<T>(),
+            });
+        }
+    }
+
+    fn send_removed(&self, type_id: TypeId, type_name: &'static str) {
+        if let Some(senders) = &*self.senders.read().unwrap() {
+            senders
+                .was_removed
+                .send(ResourceWasRemoved { type_id, type_name });
+        }
     }
 }
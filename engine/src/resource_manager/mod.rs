@@ -1,4 +1,6 @@
 mod manager;
+#[cfg(test)]
+mod tests;
 mod thread_local_manager;
 
 pub use manager::*;
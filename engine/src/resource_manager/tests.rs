@@ -0,0 +1,66 @@
+use super::*;
+use crate::message_bus::{MessageBus, MessageHandlerType};
+
+#[derive(Debug, PartialEq)]
+struct Counter(u32);
+
+#[derive(Debug, PartialEq)]
+struct Settings(&'static str);
+
+#[test]
+fn test_add_and_replace_resource() {
+    let resources = EngineResourceManager::default();
+    assert!(resources.add_resource(Counter(1)).is_none());
+    assert_eq!(*resources.add_resource(Counter(2)).unwrap(), Counter(1));
+    assert_eq!(*resources.resource::<Counter>(), Counter(2));
+
+    assert_eq!(resources.try_add_resource(Counter(3)), Err(Counter(3)));
+    assert_eq!(resources.try_add_resource(Settings("default")), Ok(()));
+    assert_eq!(*resources.remove_resource::<Counter>().unwrap(), Counter(2));
+    assert!(!resources.contains::<Counter>());
+    assert!(resources.get_resource::<Counter>().is_none());
+}
+
+#[test]
+fn test_resource_messages() {
+    let bus = MessageBus::default();
+    let added = bus.add_handler::<ResourceWasAdded>(MessageHandlerType::Update);
+    let removed = bus.add_handler::<ResourceWasRemoved>(MessageHandlerType::Update);
+    let resources = EngineResourceManager::default();
+    resources.add_resource(Settings("unpublished"));
+    resources.publish_messages(&bus);
+    assert!(added.try_recv().is_err());
+
+    resources.add_resource(Counter(1));
+    assert!(added.try_recv().unwrap().is::<Counter>());
+    resources.add_resource(Counter(2));
+    assert!(removed.try_recv().unwrap().is::<Counter>());
+    assert!(added.try_recv().unwrap().is::<Counter>());
+    assert!(resources.try_add_resource(Counter(3)).is_err());
+    assert!(added.try_recv().is_err());
+
+    resources.remove_resource::<Settings>();
+    assert!(removed.try_recv().unwrap().is::<Settings>());
+    assert!(resources.remove_resource::<Settings>().is_none());
+    assert!(removed.try_recv().is_err());
+}
+
+#[test]
+fn test_scoped_resources() {
+    let bus = MessageBus::default();
+    let removed = bus.add_handler::<ResourceWasRemoved>(MessageHandlerType::Update);
+    let resources = EngineResourceManager::default();
+    resources.publish_messages(&bus);
+    resources.add_scoped_resource("Physics", Counter(1));
+    resources.add_scoped_resource("Audio", Settings("audio"));
+
+    resources.remove_scoped_resources("Physics");
+    assert!(removed.try_recv().unwrap().is::<Counter>());
+    assert!(!resources.contains::<Counter>());
+    assert!(resources.contains::<Settings>());
+
+    // Replacing a scoped resource through `add_resource` makes it unscoped.
+    resources.add_resource(Settings("shared"));
+    resources.remove_scoped_resources("Audio");
+    assert_eq!(*resources.resource::<Settings>(), Settings("shared"));
+}
@@ -0,0 +1,261 @@
+use super::*;
+use crate::render_world::Interpolate;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use shard_ecs::*;
+use std::collections::HashMap;
+
+/// Parent of an entity. Its [`Transform`] is relative to the [`GlobalTransform`] of the parent.
+/// Managed through [`TransformHierarchy`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Component)]
+pub struct Parent(Entity);
+
+impl Parent {
+    pub fn entity(&self) -> Entity {
+        self.0
+    }
+}
+
+/// Children of an entity, in the order they were attached. Managed through [`TransformHierarchy`].
+#[derive(Debug, Clone, Default, Eq, PartialEq, Component)]
+pub struct Children(Vec<Entity>);
+
+impl Children {
+    pub fn entities(&self) -> &[Entity] {
+        &self.0
+    }
+}
+
+/// Entities are serialized as their raw handles, which are only meaningful within the serialized scene.
+/// [`SceneComponentRegistry::deserialize_into`] maps them to the deserialized entities.
+impl Serialize for Parent {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.raw().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Parent {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        u32::deserialize(deserializer).map(|raw| Parent(unsafe { Entity::from_raw(raw) }))
+    }
+}
+
+impl Serialize for Children {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.0.iter().map(|e| e.raw()))
+    }
+}
+
+impl<'de> Deserialize<'de> for Children {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Vec::<u32>::deserialize(deserializer).map(|raw| {
+            Children(
+                raw.into_iter()
+                    .map(|e| unsafe { Entity::from_raw(e) })
+                    .collect(),
+            )
+        })
+    }
+}
+
+/// Transform of an entity in scene space, computed from the [`Transform`]s of the entity and its ancestors.
+/// Updated by [`TransformHierarchy::propagate`] after the update stages ran.
+#[derive(Debug, Copy, Clone, PartialEq, Component, Serialize, Deserialize)]
+pub struct GlobalTransform(Transform);
+
+impl GlobalTransform {
+    pub fn transform(&self) -> &Transform {
+        &self.0
+    }
+}
+
+/// The global transform of an entity without a parent equals its transform.
+impl From<Transform> for GlobalTransform {
+    fn from(transform: Transform) -> Self {
+        GlobalTransform(transform)
+    }
+}
+
+impl Interpolate for GlobalTransform {
+    fn interpolate(&self, next: &Self, alpha: f32) -> Self {
+        GlobalTransform(self.0.interpolate(&next.0, alpha))
+    }
+}
+
+/// Functions maintaining the [`Parent`] and [`Children`] components of a registry.
+pub struct TransformHierarchy;
+
+impl TransformHierarchy {
+    /// Attaches `child` to `parent`, detaching it from its former parent.
+    /// Returns false if either entity does not exist or `parent` is `child` or one of its descendants.
+    pub fn set_parent(registry: &mut Registry, child: Entity, parent: Entity) -> bool {
+        let previous = registry.get_component::<Parent>(child).map(|e| e.0);
+        if previous == Some(parent) {
+            return true;
+        }
+        if Self::is_ancestor(registry, child, parent) {
+            return false;
+        }
+        // Adding the components fails if the entities do not exist.
+        match registry.get_component_mut::<Children>(parent) {
+            Some(children) => children.0.push(child),
+            None => {
+                if registry
+                    .add_component(parent, Children(vec![child]))
+                    .is_err()
+                {
+                    return false;
+                }
+            }
+        }
+        if !Self::replace_component(registry, child, Parent(parent)) {
+            Self::remove_child(registry, parent, child);
+            return false;
+        }
+        if let Some(previous) = previous {
+            Self::remove_child(registry, previous, child);
+        }
+        true
+    }
+
+    /// Detaches the entity from its parent, making it a root. Its [`Transform`] is kept as is.
+    /// Returns false if the entity has no parent.
+    pub fn detach(registry: &mut Registry, entity: Entity) -> bool {
+        match registry.remove_component::<Parent>(entity) {
+            Ok(parent) => {
+                Self::remove_child(registry, parent.0, entity);
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// Destroys the entity together with all of its descendants.
+    /// Returns false if the entity does not exist.
+    pub fn despawn_recursive(registry: &mut Registry, entity: Entity) -> bool {
+        Self::detach(registry, entity);
+        let mut pending = vec![entity];
+        let mut destroyed = false;
+        while let Some(entity) = pending.pop() {
+            if let Some(children) = registry.get_component::<Children>(entity) {
+                pending.extend_from_slice(&children.0);
+            }
+            destroyed |= registry.destroy_entity(entity);
+        }
+        destroyed
+    }
+
+    /// Computes the [`GlobalTransform`] of every entity with a [`Transform`], starting at the roots.
+    /// Entities without a [`Transform`] pass the transform of their parent on to their children.
+    pub fn propagate(registry: &mut Registry) {
+        let mut pending = registry
+            .iter_entities()
+            .filter(|e| {
+                !registry.has_component::<Parent>(*e)
+                    && (registry.has_component::<Transform>(*e)
+                        || registry.has_component::<Children>(*e))
+            })
+            .map(|e| (e, Transform::identity()))
+            .collect::<Vec<_>>();
+        while let Some((entity, parent)) = pending.pop() {
+            let global = match registry.get_component::<Transform>(entity) {
+                Some(transform) => {
+                    let global = parent.mul_transform(transform);
+                    Self::replace_component(registry, entity, GlobalTransform(global));
+                    global
+                }
+                None => parent,
+            };
+            if let Some(children) = registry.get_component::<Children>(entity) {
+                pending.extend(children.0.iter().map(|e| (*e, global)));
+            }
+        }
+    }
+
+    fn is_ancestor(registry: &Registry, ancestor: Entity, entity: Entity) -> bool {
+        let mut current = Some(entity);
+        while let Some(entity) = current {
+            if entity == ancestor {
+                return true;
+            }
+            current = registry.get_component::<Parent>(entity).map(|e| e.0);
+        }
+        false
+    }
+
+    fn remove_child(registry: &mut Registry, parent: Entity, child: Entity) {
+        if let Some(children) = registry.get_component_mut::<Children>(parent) {
+            children.0.retain(|e| *e != child);
+            if children.0.is_empty() {
+                let _ = registry.remove_component::<Children>(parent);
+            }
+        }
+    }
+
+    /// Returns false if the entity does not exist.
+    fn replace_component<C: Component>(
+        registry: &mut Registry,
+        entity: Entity,
+        component: C,
+    ) -> bool {
+        match registry.get_component_mut::<C>(entity) {
+            Some(existing) => {
+                *existing = component;
+                true
+            }
+            None => registry.add_component(entity, component).is_ok(),
+        }
+    }
+}
+
+/// Hierarchy of deserialized entities, whose [`Parent`] and [`Children`] still reference the raw handles of the
+/// serialized scene. The entities stay detached from each other until [`PendingHierarchy::attach`].
+#[derive(Default)]
+pub(crate) struct PendingHierarchy {
+    entities: Vec<Entity>,
+    /// Deserialized entities keyed by their serialized identifiers.
+    ids: HashMap<u32, Entity>,
+    /// Serialized identifiers of the children of each entity.
+    children: Vec<(Entity, Vec<u32>)>,
+}
+
+impl PendingHierarchy {
+    /// Detaches the deserialized entity until all entities of the serialized scene were added.
+    /// Removing the components could fail if they are the only components of the entity, so they are cleared instead.
+    pub(crate) fn insert(&mut self, registry: &mut Registry, id: Option<u32>, entity: Entity) {
+        self.entities.push(entity);
+        if let Some(id) = id {
+            self.ids.insert(id, entity);
+        }
+        if let Some(parent) = registry.get_component_mut::<Parent>(entity) {
+            parent.0 = Entity::INVALID;
+        }
+        if let Some(children) = registry.get_component_mut::<Children>(entity) {
+            let ids = children.0.drain(..).map(|e| e.raw()).collect();
+            self.children.push((entity, ids));
+        }
+    }
+
+    /// Attaches the entities to the children they were serialized with, keeping the order of the children.
+    /// Children which were not deserialized are skipped.
+    pub(crate) fn attach(self, registry: &mut Registry) {
+        for (parent, ids) in &self.children {
+            for child in ids.iter().filter_map(|e| self.ids.get(e)) {
+                TransformHierarchy::set_parent(registry, *child, *parent);
+            }
+        }
+        for entity in self.entities {
+            if registry
+                .get_component::<Parent>(entity)
+                .is_some_and(|e| !e.0.is_valid())
+            {
+                let _ = registry.remove_component::<Parent>(entity);
+            }
+            if registry
+                .get_component::<Children>(entity)
+                .is_some_and(|e| e.0.is_empty())
+            {
+                let _ = registry.remove_component::<Children>(entity);
+            }
+        }
+    }
+}
@@ -1,5 +1,17 @@
+mod hierarchy;
+mod prefab;
 mod scene;
 mod scene_manager;
+mod serialization;
+mod streaming;
+#[cfg(test)]
+mod tests;
+mod transform;
 
+pub use hierarchy::*;
+pub use prefab::*;
 pub use scene::*;
 pub use scene_manager::*;
+pub use serialization::*;
+pub use streaming::*;
+pub use transform::*;
@@ -0,0 +1,253 @@
+use super::*;
+use crate::asset_decoding::load_asset;
+use crate::value_merge::merge_cbor_values;
+use assets::{AssetCache, AssetIdentifier};
+use serde::{Deserialize, Serialize};
+use serde_cbor::Value;
+use shard_ecs::Entity;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, RwLock};
+
+/// Template for an entity and its children, stored as a CBOR or TOML asset.
+///
+/// A prefab may be based on another prefab asset, referenced by its name such as `assets.prefabs.character`.
+/// Its components are merged into the components of the base prefab, tables field by field, and its children are
+/// appended to the children of the base prefab.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Prefab {
+    #[serde(default)]
+    pub base: Option<String>,
+    /// Component values keyed by their names registered in the [`SceneComponentRegistry`].
+    #[serde(default)]
+    pub components: BTreeMap<String, Value>,
+    #[serde(default)]
+    pub children: Vec<Prefab>,
+}
+
+impl Prefab {
+    /// Maximum depth of base prefab references, guards against cyclic references.
+    pub const MAX_BASE_DEPTH: usize = 16;
+    /// Maximum nesting depth of children, guards against prefabs containing themselves as a child.
+    pub const MAX_CHILD_DEPTH: usize = 32;
+
+    /// Returns the prefab with the given component values merged into its own.
+    pub fn with_overrides(mut self, overrides: BTreeMap<String, Value>) -> Self {
+        Self::merge_components(&mut self.components, overrides);
+        self
+    }
+
+    /// Replaces the base prefab references of the prefab and its children by the referenced prefabs.
+    pub fn resolve(
+        self,
+        load: &mut impl FnMut(AssetIdentifier) -> Result<Prefab, SceneSerializationError>,
+    ) -> Result<Prefab, SceneSerializationError> {
+        self.resolve_with_depth(load, 0, 0)
+    }
+
+    /// `base_depth` counts the base prefabs of the current entity, `child_depth` the ancestors of the current entity.
+    fn resolve_with_depth(
+        self,
+        load: &mut impl FnMut(AssetIdentifier) -> Result<Prefab, SceneSerializationError>,
+        base_depth: usize,
+        child_depth: usize,
+    ) -> Result<Prefab, SceneSerializationError> {
+        if base_depth > Self::MAX_BASE_DEPTH {
+            return Err(SceneSerializationError::InvalidPrefab(String::from(
+                "Base prefabs are nested too deeply, they may reference each other.",
+            )));
+        }
+        if child_depth > Self::MAX_CHILD_DEPTH {
+            return Err(SceneSerializationError::PrefabChildrenTooDeep(
+                Self::MAX_CHILD_DEPTH,
+            ));
+        }
+        let mut resolved = match &self.base {
+            Some(base) => load(AssetIdentifier::named(base))?.resolve_with_depth(
+                load,
+                base_depth + 1,
+                child_depth,
+            )?,
+            None => Prefab::default(),
+        };
+        Self::merge_components(&mut resolved.components, self.components);
+        for child in self.children {
+            resolved
+                .children
+                .push(child.resolve_with_depth(load, 0, child_depth + 1)?);
+        }
+        Ok(resolved)
+    }
+
+    fn merge_components(target: &mut BTreeMap<String, Value>, components: BTreeMap<String, Value>) {
+        for (name, value) in components {
+            match target.get_mut(&name) {
+                Some(existing) => merge_cbor_values(existing, value),
+                None => {
+                    target.insert(name, value);
+                }
+            }
+        }
+    }
+}
+
+/// Entities created by spawning a [`Prefab`], in depth-first order starting with the root.
+#[derive(Debug, Clone)]
+pub struct PrefabInstance {
+    entities: Vec<Entity>,
+    /// Index of the parent of each entity, None for the root.
+    parents: Vec<Option<usize>>,
+}
+
+impl PrefabInstance {
+    pub fn root(&self) -> Entity {
+        self.entities[0]
+    }
+    pub fn entities(&self) -> &[Entity] {
+        &self.entities
+    }
+    pub fn parent(&self, entity: Entity) -> Option<Entity> {
+        let idx = self.entities.iter().position(|e| *e == entity)?;
+        self.parents[idx].map(|e| self.entities[e])
+    }
+}
+
+/// Engine resource caching resolved prefab assets.
+/// Prefabs are cached with the variant generation of the asset cache, they are loaded again once the active
+/// variants change.
+pub struct PrefabLibrary {
+    components: Arc<SceneComponentRegistry>,
+    prefabs: RwLock<HashMap<AssetIdentifier, (u64, Arc<Prefab>)>>,
+}
+
+impl PrefabLibrary {
+    pub fn new(components: Arc<SceneComponentRegistry>) -> Self {
+        Self {
+            components,
+            prefabs: Default::default(),
+        }
+    }
+
+    pub fn components(&self) -> &SceneComponentRegistry {
+        &self.components
+    }
+
+    /// Returns the resolved prefab, loading it and its base prefabs through the asset cache if it is not cached yet.
+    pub fn load(
+        &self,
+        asset_cache: &AssetCache,
+        asset_id: AssetIdentifier,
+    ) -> Result<Arc<Prefab>, SceneSerializationError> {
+        self.load_with(
+            asset_cache.variant_generation(),
+            asset_id,
+            &mut |asset_id| Ok(load_asset::<Prefab>(asset_cache, asset_id)?),
+        )
+    }
+
+    /// Returns the resolved prefab if it was cached with the given variant generation, loading it otherwise.
+    pub(super) fn load_with(
+        &self,
+        generation: u64,
+        asset_id: AssetIdentifier,
+        load: &mut impl FnMut(AssetIdentifier) -> Result<Prefab, SceneSerializationError>,
+    ) -> Result<Arc<Prefab>, SceneSerializationError> {
+        if let Some((_, prefab)) = self
+            .prefabs
+            .read()
+            .unwrap()
+            .get(&asset_id)
+            .filter(|(e, _)| *e == generation)
+        {
+            return Ok(Arc::clone(prefab));
+        }
+        let prefab = Arc::new(load(asset_id)?.resolve(load)?);
+        self.prefabs
+            .write()
+            .unwrap()
+            .insert(asset_id, (generation, Arc::clone(&prefab)));
+        Ok(prefab)
+    }
+
+    /// Drops the cached prefabs, e.g. after the prefab assets changed.
+    pub fn clear(&self) {
+        self.prefabs.write().unwrap().clear();
+    }
+}
+
+impl Scene {
+    /// Loads the prefab asset through the library and creates its entities in the scene.
+    /// Returns the root entity, with the children of the prefab attached to it.
+    pub fn spawn_prefab(
+        &mut self,
+        prefabs: &PrefabLibrary,
+        asset_cache: &AssetCache,
+        asset_id: AssetIdentifier,
+    ) -> Result<Entity, SceneSerializationError> {
+        let prefab = prefabs.load(asset_cache, asset_id)?;
+        self.instantiate_prefab(&prefab, prefabs.components())
+            .map(|e| e.root())
+    }
+
+    /// Creates the entities of a resolved prefab in the scene, see [`Prefab::resolve`].
+    /// Each entity is attached to its parent within the prefab through the [`TransformHierarchy`].
+    /// On failure, the entities created so far are destroyed again.
+    pub fn instantiate_prefab(
+        &mut self,
+        prefab: &Prefab,
+        components: &SceneComponentRegistry,
+    ) -> Result<PrefabInstance, SceneSerializationError> {
+        let mut instance = PrefabInstance {
+            entities: vec![],
+            parents: vec![],
+        };
+        let mut pending = vec![(prefab, None)];
+        while let Some((prefab, parent)) = pending.pop() {
+            if let Err(e) = self.instantiate_entity(prefab, parent, components, &mut instance) {
+                for entity in instance.entities {
+                    self.registry_mut().destroy_entity(entity);
+                }
+                return Err(e);
+            }
+            let idx = instance.entities.len() - 1;
+            pending.extend(prefab.children.iter().rev().map(|e| (e, Some(idx))));
+        }
+        for (entity, parent) in instance.entities.iter().zip(&instance.parents) {
+            if let Some(parent) = parent {
+                TransformHierarchy::set_parent(
+                    self.registry_mut(),
+                    *entity,
+                    instance.entities[*parent],
+                );
+            }
+        }
+        Ok(instance)
+    }
+
+    fn instantiate_entity(
+        &mut self,
+        prefab: &Prefab,
+        parent: Option<usize>,
+        components: &SceneComponentRegistry,
+        instance: &mut PrefabInstance,
+    ) -> Result<(), SceneSerializationError> {
+        if prefab.base.is_some() {
+            return Err(SceneSerializationError::InvalidPrefab(String::from(
+                "The prefab has not been resolved.",
+            )));
+        }
+        let serialized = SerializedEntity {
+            id: None,
+            components: prefab.components.clone(),
+        };
+        match components.deserialize_entity(&serialized, self.registry_mut())? {
+            Some(entity) => {
+                instance.entities.push(entity);
+                instance.parents.push(parent);
+                Ok(())
+            }
+            None => Err(SceneSerializationError::InvalidPrefab(String::from(
+                "Prefab entities require at least one component.",
+            ))),
+        }
+    }
+}
@@ -4,13 +4,15 @@ use utils::handles::*;
 
 pub struct Scene {
     handle: SceneHandle,
+    name: String,
     registry: Registry,
 }
 
 impl Scene {
-    pub(super) fn new(handle: SceneHandle) -> Scene {
+    pub(super) fn new(handle: SceneHandle, name: String) -> Scene {
         Self {
             handle,
+            name,
             registry: Default::default(),
         }
     }
@@ -18,6 +20,9 @@ impl Scene {
     pub fn handle(&self) -> Handle<Scene, u32> {
         self.handle
     }
+    pub fn name(&self) -> &str {
+        &self.name
+    }
     pub fn registry(&self) -> &Registry {
         &self.registry
     }
@@ -26,20 +31,32 @@ impl Scene {
     }
 }
 
-#[allow(unused)]
+/// Sent when the active scene changed.
 #[derive(Clone)]
 pub struct SceneDidBecomeCurrent {
     pub scene: SceneHandle,
 }
 
-#[allow(unused)]
+/// Sent after a scene was created.
 #[derive(Clone)]
 pub struct SceneWasCreated {
     pub scene: SceneHandle,
 }
 
-#[allow(unused)]
+/// Sent after a scene was destroyed, the handle no longer refers to a scene.
 #[derive(Clone)]
 pub struct SceneWasDestroyed {
     pub scene: SceneHandle,
 }
+
+/// Scenes an update stage operates on, declared through [`crate::engine_stages::UpdateStageDependencies::targets`].
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub enum SceneTarget {
+    /// The active scene.
+    #[default]
+    Active,
+    /// All scenes, in creation order.
+    All,
+    /// The scenes with the given names, in creation order.
+    Named(Vec<String>),
+}
@@ -1,35 +1,272 @@
 use super::*;
-use std::collections::HashMap;
+use crate::asset_decoding::load_asset;
+use crate::message_bus::{MessageBus, MessageSender};
+use assets::{AssetCache, AssetIdentifier};
+use utils::dispatcher::Dispatcher;
 use utils::handles::Handle;
+use utils::*;
 
 pub type SceneHandle = Handle<Scene, u32>;
 
+struct SceneMessageSenders {
+    did_become_current: MessageSender<SceneDidBecomeCurrent>,
+    was_created: MessageSender<SceneWasCreated>,
+    was_destroyed: MessageSender<SceneWasDestroyed>,
+    chunk_was_loaded: MessageSender<SceneChunkWasLoaded>,
+}
+
+/// Owns all scenes of the engine. Every scene is updated, one of them is the active scene.
+/// Scenes are kept in creation order, handles are never reused.
 pub struct SceneManager {
-    #[allow(unused)]
     counter: u32,
     active: SceneHandle,
-    scenes: HashMap<SceneHandle, Scene>,
+    scenes: Vec<Scene>,
+    senders: Option<SceneMessageSenders>,
+    streamer: SceneStreamer,
 }
 
 impl Default for SceneManager {
     fn default() -> Self {
-        let scene = Scene::new(Handle::from(0));
-        let mut scenes: HashMap<SceneHandle, Scene> = Default::default();
-        scenes.insert(Handle::from(0), scene);
         Self {
             counter: 1,
             active: Handle::from(0),
-            scenes,
+            scenes: vec![Scene::new(
+                Handle::from(0),
+                String::from(Self::DEFAULT_SCENE_NAME),
+            )],
+            senders: None,
+            streamer: Default::default(),
         }
     }
 }
 
 impl SceneManager {
+    /// Name of the scene that is created with the manager.
+    pub const DEFAULT_SCENE_NAME: &'static str = "default";
+
+    /// Creates a manager which publishes scene lifecycle messages on the message bus.
+    pub fn new(message_bus: &MessageBus) -> Self {
+        Self {
+            senders: Some(SceneMessageSenders {
+                did_become_current: message_bus.get_sender(),
+                was_created: message_bus.get_sender(),
+                was_destroyed: message_bus.get_sender(),
+                chunk_was_loaded: message_bus.get_sender(),
+            }),
+            ..Default::default()
+        }
+    }
+
     pub fn active_scene(&self) -> &Scene {
-        self.scenes.get(&self.active).unwrap()
+        self.scene(self.active).unwrap()
     }
 
     pub fn active_scene_mut(&mut self) -> &mut Scene {
-        self.scenes.get_mut(&self.active).unwrap()
+        let active = self.active;
+        self.scene_mut(active).unwrap()
+    }
+
+    pub fn scene(&self, handle: SceneHandle) -> Option<&Scene> {
+        self.scenes.iter().find(|e| e.handle() == handle)
+    }
+
+    pub fn scene_mut(&mut self, handle: SceneHandle) -> Option<&mut Scene> {
+        self.scenes.iter_mut().find(|e| e.handle() == handle)
+    }
+
+    /// Returns the first scene with the given name.
+    pub fn find_scene(&self, name: &str) -> Option<SceneHandle> {
+        self.scenes
+            .iter()
+            .find(|e| e.name() == name)
+            .map(|e| e.handle())
+    }
+
+    /// Iterates all scenes in creation order.
+    pub fn scenes(&self) -> impl Iterator<Item = &Scene> {
+        self.scenes.iter()
+    }
+
+    pub fn scenes_mut(&mut self) -> impl Iterator<Item = &mut Scene> {
+        self.scenes.iter_mut()
+    }
+
+    /// Iterates the scenes selected by the target in creation order.
+    pub fn target_scenes<'a>(&'a self, target: &'a SceneTarget) -> impl Iterator<Item = &'a Scene> {
+        let active = self.active;
+        self.scenes
+            .iter()
+            .filter(move |e| Self::is_targeted(e, active, target))
+    }
+
+    pub fn target_scenes_mut<'a>(
+        &'a mut self,
+        target: &'a SceneTarget,
+    ) -> impl Iterator<Item = &'a mut Scene> {
+        let active = self.active;
+        self.scenes
+            .iter_mut()
+            .filter(move |e| Self::is_targeted(e, active, target))
+    }
+
+    /// Creates an empty scene and sends [`SceneWasCreated`].
+    pub fn create_scene(&mut self, name: impl Into<String>) -> SceneHandle {
+        let scene = Scene::new(self.next_handle(), name.into());
+        self.insert_scene(scene)
+    }
+
+    /// Creates a scene from the serialized scene and sends [`SceneWasCreated`].
+    /// No scene is created if any of its entities cannot be deserialized.
+    pub fn load_serialized_scene(
+        &mut self,
+        serialized: &SerializedScene,
+        components: &SceneComponentRegistry,
+    ) -> Result<SceneHandle, SceneSerializationError> {
+        let mut scene = Scene::new(self.next_handle(), serialized.name.clone());
+        components.deserialize_into(serialized, &mut scene)?;
+        Ok(self.insert_scene(scene))
+    }
+
+    /// Loads a scene asset stored as CBOR or TOML, blocking until it is available.
+    /// See [`SceneManager::load_serialized_scene`].
+    pub fn load_scene(
+        &mut self,
+        asset_cache: &AssetCache,
+        asset_id: AssetIdentifier,
+        components: &SceneComponentRegistry,
+    ) -> Result<SceneHandle, SceneSerializationError> {
+        let serialized = load_asset::<SerializedScene>(asset_cache, asset_id)?;
+        self.load_serialized_scene(&serialized, components)
+    }
+
+    /// Destroys the scene and all of its entities and sends [`SceneWasDestroyed`].
+    /// Returns false if the scene does not exist or is the active scene.
+    pub fn destroy_scene(&mut self, handle: SceneHandle) -> bool {
+        if handle == self.active {
+            t_warn!("Cannot destroy the active scene.");
+            return false;
+        }
+        let idx = match self.scenes.iter().position(|e| e.handle() == handle) {
+            Some(idx) => idx,
+            None => return false,
+        };
+        self.scenes.remove(idx);
+        self.streamer.remove_scene(handle);
+        if let Some(senders) = &self.senders {
+            senders
+                .was_destroyed
+                .send(SceneWasDestroyed { scene: handle });
+        }
+        true
+    }
+
+    /// Makes the scene the active scene and sends [`SceneDidBecomeCurrent`].
+    /// Returns false if the scene does not exist.
+    pub fn set_active_scene(&mut self, handle: SceneHandle) -> bool {
+        if self.scene(handle).is_none() {
+            return false;
+        }
+        if handle != self.active {
+            self.active = handle;
+            if let Some(senders) = &self.senders {
+                senders
+                    .did_become_current
+                    .send(SceneDidBecomeCurrent { scene: handle });
+            }
+        }
+        true
+    }
+
+    /// Streams the entities of a scene asset into an existing scene in the background.
+    /// The asset is read and decoded off-thread, its entities are merged during the update phase within the
+    /// streaming entity budget. [`SceneChunkWasLoaded`] is sent once all entities were merged.
+    /// Returns None if the scene or the asset does not exist.
+    pub fn stream_chunk(
+        &mut self,
+        asset_cache: &AssetCache,
+        asset_id: AssetIdentifier,
+        scene: SceneHandle,
+    ) -> Option<SceneChunkHandle> {
+        self.scene(scene)?;
+        self.streamer.request(asset_cache, asset_id, scene)
+    }
+
+    /// Merges an already deserialized chunk into the scene, see [`SceneManager::stream_chunk`].
+    pub fn stream_serialized_chunk(
+        &mut self,
+        serialized: SerializedScene,
+        scene: SceneHandle,
+    ) -> Option<SceneChunkHandle> {
+        self.scene(scene)?;
+        Some(self.streamer.insert_serialized(serialized, scene))
+    }
+
+    pub fn chunk(&self, chunk: SceneChunkHandle) -> Option<&SceneChunk> {
+        self.streamer.chunk(chunk)
+    }
+
+    /// Destroys exactly the entities the chunk added to its scene, cancelling it if it is still loading.
+    /// Returns false if the chunk does not exist.
+    pub fn unload_chunk(&mut self, chunk: SceneChunkHandle) -> bool {
+        let chunk = match self.streamer.remove(chunk) {
+            Some(v) => v,
+            None => return false,
+        };
+        if let Some(scene) = self.scene_mut(chunk.scene()) {
+            for entity in chunk.entities() {
+                scene.registry_mut().destroy_entity(*entity);
+            }
+        }
+        true
+    }
+
+    /// Maximum amount of streamed entities merged per update tick.
+    pub fn streaming_entity_budget(&self) -> usize {
+        self.streamer.entity_budget()
+    }
+
+    pub fn set_streaming_entity_budget(&mut self, entity_budget: usize) {
+        self.streamer.set_entity_budget(entity_budget);
+    }
+
+    /// Advances streamed chunks. Executed on the update thread before the update stages run.
+    pub(crate) fn update_streaming(
+        &mut self,
+        components: &SceneComponentRegistry,
+        dispatcher: &Dispatcher,
+    ) {
+        let loaded = self
+            .streamer
+            .update(&mut self.scenes, components, dispatcher);
+        if let Some(senders) = &self.senders {
+            for (chunk, scene) in loaded {
+                senders
+                    .chunk_was_loaded
+                    .send(SceneChunkWasLoaded { chunk, scene });
+            }
+        }
+    }
+
+    fn next_handle(&mut self) -> SceneHandle {
+        let handle = SceneHandle::from(self.counter);
+        self.counter += 1;
+        handle
+    }
+
+    fn insert_scene(&mut self, scene: Scene) -> SceneHandle {
+        let handle = scene.handle();
+        self.scenes.push(scene);
+        if let Some(senders) = &self.senders {
+            senders.was_created.send(SceneWasCreated { scene: handle });
+        }
+        handle
+    }
+
+    fn is_targeted(scene: &Scene, active: SceneHandle, target: &SceneTarget) -> bool {
+        match target {
+            SceneTarget::Active => scene.handle() == active,
+            SceneTarget::All => true,
+            SceneTarget::Named(names) => names.iter().any(|e| e == scene.name()),
+        }
     }
 }
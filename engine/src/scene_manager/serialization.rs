@@ -0,0 +1,317 @@
+use super::*;
+use crate::asset_decoding::{decode, AssetDecodingError};
+use assets::AssetSerializationFormat;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_cbor::Value;
+use shard_ecs::{Component, Entity, Registry};
+use std::any::{type_name, TypeId};
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::sync::RwLock;
+use utils::*;
+
+type ComponentSerializer = fn(&Registry, Entity) -> Option<Result<Value, String>>;
+type ComponentDeserializer = fn(&mut Registry, Option<Entity>, &Value) -> Result<Entity, String>;
+
+struct ComponentEntry {
+    name: &'static str,
+    type_id: TypeId,
+    serialize: ComponentSerializer,
+    deserialize: ComponentDeserializer,
+}
+
+/// Engine resource mapping stable component names to their serde functions.
+///
+/// Only registered components are written when serializing a scene, other components are skipped.
+/// The names are stored in the serialized data, so they must not change once scenes have been saved.
+#[derive(Default)]
+pub struct SceneComponentRegistry {
+    components: RwLock<Vec<ComponentEntry>>,
+}
+
+impl SceneComponentRegistry {
+    /// Registers the component `C` under the given name.
+    /// Each name and each component may only be registered once, further registrations are ignored.
+    pub fn register_component<C: Component + Serialize + DeserializeOwned>(
+        &self,
+        name: &'static str,
+    ) {
+        let mut components = self.components.write().unwrap();
+        if components
+            .iter()
+            .any(|e| e.name == name || e.type_id == TypeId::of::<C>())
+        {
+            t_warn!(
+                "Scene component {} is already registered as `{}`, ignoring.",
+                type_name::<C>(),
+                name
+            );
+            return;
+        }
+        components.push(ComponentEntry {
+            name,
+            type_id: TypeId::of::<C>(),
+            serialize: serialize_component::<C>,
+            deserialize: deserialize_component::<C>,
+        });
+    }
+
+    /// Serializes the registered components of every entity in the scene.
+    /// Entities without any registered component are skipped.
+    pub fn serialize(&self, scene: &Scene) -> Result<SerializedScene, SceneSerializationError> {
+        let components = self.components.read().unwrap();
+        let mut entities = vec![];
+        for entity in scene.registry().iter_entities() {
+            let mut serialized = BTreeMap::new();
+            for entry in components.iter() {
+                match (entry.serialize)(scene.registry(), entity) {
+                    Some(Ok(value)) => {
+                        serialized.insert(String::from(entry.name), value);
+                    }
+                    Some(Err(e)) => {
+                        return Err(SceneSerializationError::InvalidComponent(
+                            String::from(entry.name),
+                            e,
+                        ))
+                    }
+                    None => {}
+                }
+            }
+            if !serialized.is_empty() {
+                entities.push(SerializedEntity {
+                    id: Some(entity.raw()),
+                    components: serialized,
+                });
+            }
+        }
+        Ok(SerializedScene {
+            name: String::from(scene.name()),
+            entities,
+        })
+    }
+
+    /// Creates the entities of the serialized scene in the given scene.
+    /// Their [`Parent`] and [`Children`] are mapped to the created entities, references to other entities are dropped.
+    /// On failure, the entities created so far are destroyed again.
+    pub fn deserialize_into(
+        &self,
+        serialized: &SerializedScene,
+        scene: &mut Scene,
+    ) -> Result<Vec<Entity>, SceneSerializationError> {
+        let registry = scene.registry_mut();
+        let mut entities = Vec::with_capacity(serialized.entities.len());
+        let mut hierarchy = PendingHierarchy::default();
+        for serialized_entity in &serialized.entities {
+            match self.deserialize_entity(serialized_entity, registry) {
+                Ok(Some(entity)) => {
+                    hierarchy.insert(registry, serialized_entity.id, entity);
+                    entities.push(entity);
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    for entity in entities {
+                        registry.destroy_entity(entity);
+                    }
+                    return Err(e);
+                }
+            }
+        }
+        hierarchy.attach(registry);
+        Ok(entities)
+    }
+
+    /// Creates a single entity in the registry. Returns None if the entity has no components.
+    /// On failure, the partially created entity is destroyed again.
+    pub fn deserialize_entity(
+        &self,
+        serialized: &SerializedEntity,
+        registry: &mut Registry,
+    ) -> Result<Option<Entity>, SceneSerializationError> {
+        let components = self.components.read().unwrap();
+        let mut entity = None;
+        for (name, value) in &serialized.components {
+            let result = match components.iter().find(|e| e.name == name) {
+                Some(entry) => (entry.deserialize)(registry, entity, value)
+                    .map_err(|e| SceneSerializationError::InvalidComponent(name.clone(), e)),
+                None => Err(SceneSerializationError::UnknownComponent(name.clone())),
+            };
+            match result {
+                Ok(v) => entity = Some(v),
+                Err(e) => {
+                    if let Some(entity) = entity {
+                        registry.destroy_entity(entity);
+                    }
+                    return Err(e);
+                }
+            }
+        }
+        Ok(entity)
+    }
+}
+
+fn serialize_component<C: Component + Serialize>(
+    registry: &Registry,
+    entity: Entity,
+) -> Option<Result<Value, String>> {
+    registry
+        .get_component::<C>(entity)
+        .map(|e| serde_cbor::value::to_value(e).map_err(|e| e.to_string()))
+}
+
+fn deserialize_component<C: Component + DeserializeOwned>(
+    registry: &mut Registry,
+    entity: Option<Entity>,
+    value: &Value,
+) -> Result<Entity, String> {
+    let component = serde_cbor::value::from_value::<C>(value.clone()).map_err(|e| e.to_string())?;
+    match entity {
+        Some(entity) => match registry.add_component(entity, component) {
+            Ok(_) => Ok(entity),
+            Err(_) => Err(String::from("Component could not be added to the entity.")),
+        },
+        None => match registry.create_entity(component) {
+            Ok(entity) => Ok(entity),
+            Err(_) => Err(String::from("Entity could not be created.")),
+        },
+    }
+}
+
+/// Entity with its registered components, keyed by their registered names.
+///
+/// Components are kept as CBOR values, which represent every serde data type. TOML is only used when encoding
+/// the scene, so values which TOML cannot represent fail there, e.g. integers above `i64::MAX`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SerializedEntity {
+    /// Identifies the entity within the serialized scene, referenced by the hierarchy components of other entities.
+    #[serde(default)]
+    pub id: Option<u32>,
+    pub components: BTreeMap<String, Value>,
+}
+
+/// Format independent representation of a scene, see [`SceneComponentRegistry`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SerializedScene {
+    pub name: String,
+    pub entities: Vec<SerializedEntity>,
+}
+
+impl SerializedScene {
+    /// Encodes the scene as CBOR for [`AssetSerializationFormat::Binary`] or as TOML.
+    pub fn to_bytes(
+        &self,
+        format: AssetSerializationFormat,
+    ) -> Result<Vec<u8>, SceneSerializationError> {
+        match format {
+            AssetSerializationFormat::Binary => serde_cbor::to_vec(self)
+                .map_err(|e| SceneSerializationError::Encoding(format, e.to_string())),
+            // Components mix plain values and tables, which TOML only accepts when written through a `Value`.
+            AssetSerializationFormat::Toml => serde_cbor::value::to_value(self)
+                .map_err(|e| e.to_string())
+                .and_then(toml_value)
+                .and_then(|e| toml::to_string(&e).map_err(|e| e.to_string()))
+                .map(String::into_bytes)
+                .map_err(|e| SceneSerializationError::Encoding(format, e)),
+            AssetSerializationFormat::Unknown => Err(SceneSerializationError::UnsupportedFormat),
+        }
+    }
+
+    pub fn from_bytes(
+        bytes: &[u8],
+        format: AssetSerializationFormat,
+    ) -> Result<Self, SceneSerializationError> {
+        Ok(decode(bytes, format)?)
+    }
+}
+
+/// Converts a CBOR value to TOML, which has no null values, integers beyond `i64` or non-string keys.
+fn toml_value(value: Value) -> Result<toml::Value, String> {
+    Ok(match value {
+        Value::Bool(v) => toml::Value::Boolean(v),
+        Value::Integer(v) => match i64::try_from(v) {
+            Ok(v) => toml::Value::Integer(v),
+            Err(_) => return Err(format!("{} does not fit into a TOML integer.", v)),
+        },
+        Value::Float(v) => toml::Value::Float(v),
+        Value::Text(v) => toml::Value::String(v),
+        Value::Bytes(v) => toml::Value::Array(
+            v.into_iter()
+                .map(|e| toml::Value::Integer(e as i64))
+                .collect(),
+        ),
+        Value::Array(v) => {
+            toml::Value::Array(v.into_iter().map(toml_value).collect::<Result<_, _>>()?)
+        }
+        Value::Map(v) => toml::Value::Table(
+            v.into_iter()
+                .map(|(key, value)| match key {
+                    Value::Text(key) => Ok((key, toml_value(value)?)),
+                    key => Err(format!("TOML keys must be strings, found {:?}.", key)),
+                })
+                .collect::<Result<_, _>>()?,
+        ),
+        Value::Tag(_, v) => toml_value(*v)?,
+        Value::Null => return Err(String::from("TOML cannot represent empty values.")),
+        _ => return Err(String::from("Unsupported value.")),
+    })
+}
+
+#[derive(Debug)]
+pub enum SceneSerializationError {
+    /// No component is registered under the name.
+    UnknownComponent(String),
+    /// A component could not be converted or added to its entity.
+    InvalidComponent(String, String),
+    Encoding(AssetSerializationFormat, String),
+    Decoding(AssetSerializationFormat, String),
+    UnsupportedFormat,
+    /// The scene asset could not be loaded from the asset cache.
+    AssetUnavailable,
+    InvalidPrefab(String),
+    /// Children of a prefab are nested deeper than the contained limit, see [`Prefab::MAX_CHILD_DEPTH`].
+    PrefabChildrenTooDeep(usize),
+}
+
+impl From<AssetDecodingError> for SceneSerializationError {
+    fn from(e: AssetDecodingError) -> Self {
+        match e {
+            AssetDecodingError::Decoding(format, e) => SceneSerializationError::Decoding(format, e),
+            AssetDecodingError::UnsupportedFormat => SceneSerializationError::UnsupportedFormat,
+            AssetDecodingError::AssetUnavailable => SceneSerializationError::AssetUnavailable,
+        }
+    }
+}
+
+impl std::error::Error for SceneSerializationError {}
+impl Display for SceneSerializationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SceneSerializationError::UnknownComponent(name) => {
+                write!(f, "Unknown scene component `{}`.", name)
+            }
+            SceneSerializationError::InvalidComponent(name, e) => {
+                write!(f, "Invalid scene component `{}`: {}", name, e)
+            }
+            SceneSerializationError::Encoding(format, e) => {
+                write!(f, "Could not encode scene as {:?}: {}", format, e)
+            }
+            SceneSerializationError::Decoding(format, e) => {
+                write!(f, "Could not decode scene from {:?}: {}", format, e)
+            }
+            SceneSerializationError::UnsupportedFormat => {
+                write!(f, "Unsupported scene serialization format.")
+            }
+            SceneSerializationError::AssetUnavailable => {
+                write!(f, "Scene asset could not be loaded.")
+            }
+            SceneSerializationError::InvalidPrefab(e) => write!(f, "Invalid prefab: {}", e),
+            SceneSerializationError::PrefabChildrenTooDeep(limit) => {
+                write!(
+                    f,
+                    "Prefab children are nested deeper than {} levels.",
+                    limit
+                )
+            }
+        }
+    }
+}
@@ -0,0 +1,242 @@
+use super::*;
+use assets::{AssetBlobHandle, AssetCache, AssetIdentifier, AssetSerializationFormat, AssetState};
+use crossbeam::channel::{bounded, Receiver, TryRecvError};
+use shard_ecs::Entity;
+use utils::dispatcher::Dispatcher;
+use utils::handles::Handle;
+use utils::*;
+
+pub type SceneChunkHandle = Handle<SceneChunk, u32>;
+
+/// Sent once all entities of a streamed chunk were merged into its scene.
+#[derive(Clone)]
+pub struct SceneChunkWasLoaded {
+    pub chunk: SceneChunkHandle,
+    pub scene: SceneHandle,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum SceneChunkState {
+    /// The asset is being read or decoded in the background.
+    Loading,
+    /// Entities are being created in the scene, a limited amount per update tick.
+    Merging,
+    Loaded,
+    /// Loading failed, entities which were already merged have been removed again.
+    Failed,
+}
+
+enum ChunkStage {
+    Requested(AssetBlobHandle, AssetSerializationFormat),
+    Decoding(Receiver<Result<SerializedScene, SceneSerializationError>>),
+    /// Contains the index of the next entity to merge.
+    Merging(SerializedScene, usize),
+    Loaded,
+    Failed,
+}
+
+/// Serialized entities streamed additively into a scene, see [`SceneManager::stream_chunk`].
+pub struct SceneChunk {
+    handle: SceneChunkHandle,
+    scene: SceneHandle,
+    /// The entities merged into the scene so far.
+    entities: Vec<Entity>,
+    /// Hierarchy of the merged entities, attached once all entities are merged.
+    hierarchy: PendingHierarchy,
+    stage: ChunkStage,
+}
+
+impl SceneChunk {
+    pub fn handle(&self) -> SceneChunkHandle {
+        self.handle
+    }
+    pub fn scene(&self) -> SceneHandle {
+        self.scene
+    }
+    pub fn entities(&self) -> &[Entity] {
+        &self.entities
+    }
+    pub fn state(&self) -> SceneChunkState {
+        match self.stage {
+            ChunkStage::Requested(..) | ChunkStage::Decoding(_) => SceneChunkState::Loading,
+            ChunkStage::Merging(..) => SceneChunkState::Merging,
+            ChunkStage::Loaded => SceneChunkState::Loaded,
+            ChunkStage::Failed => SceneChunkState::Failed,
+        }
+    }
+}
+
+/// Keeps track of the chunks streamed by the [`SceneManager`].
+pub(super) struct SceneStreamer {
+    counter: u32,
+    /// Maximum amount of entities merged per update tick, over all chunks.
+    entity_budget: usize,
+    chunks: Vec<SceneChunk>,
+}
+
+impl Default for SceneStreamer {
+    fn default() -> Self {
+        Self {
+            counter: 0,
+            entity_budget: 256,
+            chunks: vec![],
+        }
+    }
+}
+
+impl SceneStreamer {
+    pub(super) fn entity_budget(&self) -> usize {
+        self.entity_budget
+    }
+
+    pub(super) fn set_entity_budget(&mut self, entity_budget: usize) {
+        self.entity_budget = entity_budget.max(1);
+    }
+
+    pub(super) fn chunk(&self, handle: SceneChunkHandle) -> Option<&SceneChunk> {
+        self.chunks.iter().find(|e| e.handle == handle)
+    }
+
+    pub(super) fn request(
+        &mut self,
+        asset_cache: &AssetCache,
+        asset_id: AssetIdentifier,
+        scene: SceneHandle,
+    ) -> Option<SceneChunkHandle> {
+        let format = asset_cache.asset_format(asset_id)?;
+        let blob = match asset_cache.request_binary(asset_id) {
+            Ok(v) => v,
+            Err(e) => {
+                t_warn!("Could not request scene chunk {}: {:?}", asset_id, e);
+                return None;
+            }
+        };
+        Some(self.insert(scene, ChunkStage::Requested(blob, format)))
+    }
+
+    pub(super) fn insert_serialized(
+        &mut self,
+        serialized: SerializedScene,
+        scene: SceneHandle,
+    ) -> SceneChunkHandle {
+        self.insert(scene, ChunkStage::Merging(serialized, 0))
+    }
+
+    pub(super) fn remove(&mut self, handle: SceneChunkHandle) -> Option<SceneChunk> {
+        let idx = self.chunks.iter().position(|e| e.handle == handle)?;
+        Some(self.chunks.remove(idx))
+    }
+
+    /// Forgets the chunks of a destroyed scene.
+    pub(super) fn remove_scene(&mut self, scene: SceneHandle) {
+        self.chunks.retain(|e| e.scene != scene);
+    }
+
+    /// Starts decoding the chunks whose assets became available and merges decoded chunks within the entity budget.
+    /// Returns the chunks which finished loading.
+    pub(super) fn update(
+        &mut self,
+        scenes: &mut [Scene],
+        components: &SceneComponentRegistry,
+        dispatcher: &Dispatcher,
+    ) -> Vec<(SceneChunkHandle, SceneHandle)> {
+        let mut budget = self.entity_budget;
+        let mut loaded = vec![];
+        for chunk in &mut self.chunks {
+            let stage = std::mem::replace(&mut chunk.stage, ChunkStage::Failed);
+            let was_merging = matches!(stage, ChunkStage::Merging(..));
+            chunk.stage = match stage {
+                ChunkStage::Requested(blob, format) => match blob.state() {
+                    AssetState::Loading => ChunkStage::Requested(blob, format),
+                    AssetState::Available => {
+                        let (sender, receiver) = bounded(1);
+                        dispatcher.spawn(move || {
+                            let result = match blob.read() {
+                                Some(bytes) => SerializedScene::from_bytes(bytes, format),
+                                None => Err(SceneSerializationError::AssetUnavailable),
+                            };
+                            let _ = sender.send(result);
+                        });
+                        ChunkStage::Decoding(receiver)
+                    }
+                    AssetState::Failed => {
+                        t_warn!("Could not load scene chunk {:?}.", chunk.handle);
+                        ChunkStage::Failed
+                    }
+                },
+                ChunkStage::Decoding(receiver) => match receiver.try_recv() {
+                    Ok(Ok(serialized)) => ChunkStage::Merging(serialized, 0),
+                    Ok(Err(e)) => {
+                        t_warn!("Could not decode scene chunk {:?}: {}", chunk.handle, e);
+                        ChunkStage::Failed
+                    }
+                    Err(TryRecvError::Empty) => ChunkStage::Decoding(receiver),
+                    Err(TryRecvError::Disconnected) => ChunkStage::Failed,
+                },
+                ChunkStage::Merging(serialized, next) => {
+                    Self::merge(chunk, scenes, components, serialized, next, &mut budget)
+                }
+                stage => stage,
+            };
+            if was_merging && matches!(chunk.stage, ChunkStage::Loaded) {
+                loaded.push((chunk.handle, chunk.scene));
+            }
+        }
+        loaded
+    }
+
+    fn merge(
+        chunk: &mut SceneChunk,
+        scenes: &mut [Scene],
+        components: &SceneComponentRegistry,
+        serialized: SerializedScene,
+        mut next: usize,
+        budget: &mut usize,
+    ) -> ChunkStage {
+        let registry = match scenes.iter_mut().find(|e| e.handle() == chunk.scene) {
+            Some(scene) => scene.registry_mut(),
+            None => return ChunkStage::Failed,
+        };
+        while next < serialized.entities.len() && *budget > 0 {
+            let serialized_entity = &serialized.entities[next];
+            match components.deserialize_entity(serialized_entity, registry) {
+                Ok(Some(entity)) => {
+                    chunk
+                        .hierarchy
+                        .insert(registry, serialized_entity.id, entity);
+                    chunk.entities.push(entity);
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    t_warn!("Could not merge scene chunk {:?}: {}", chunk.handle, e);
+                    for entity in chunk.entities.drain(..) {
+                        registry.destroy_entity(entity);
+                    }
+                    chunk.hierarchy = Default::default();
+                    return ChunkStage::Failed;
+                }
+            }
+            next += 1;
+            *budget -= 1;
+        }
+        if next < serialized.entities.len() {
+            ChunkStage::Merging(serialized, next)
+        } else {
+            std::mem::take(&mut chunk.hierarchy).attach(registry);
+            ChunkStage::Loaded
+        }
+    }
+
+    fn insert(&mut self, scene: SceneHandle, stage: ChunkStage) -> SceneChunkHandle {
+        let handle = SceneChunkHandle::from(self.counter);
+        self.counter += 1;
+        self.chunks.push(SceneChunk {
+            handle,
+            scene,
+            entities: vec![],
+            hierarchy: Default::default(),
+            stage,
+        });
+        handle
+    }
+}
@@ -0,0 +1,826 @@
+use super::*;
+use crate::message_bus::{MessageBus, MessageHandlerType};
+use assets::AssetSerializationFormat;
+use math::*;
+use serde::{Deserialize, Serialize};
+use shard_ecs::*;
+use std::num::NonZeroUsize;
+use std::sync::Arc;
+use toml::Value;
+use utils::dispatcher::Dispatcher;
+
+#[derive(Debug, Clone, PartialEq, Component, Serialize, Deserialize)]
+struct Position {
+    x: f32,
+    y: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Component, Serialize, Deserialize)]
+struct Tag(String);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum Shape {
+    Mesh { asset: assets::AssetIdentifier },
+    Sphere(f32),
+}
+
+#[derive(Debug, Clone, PartialEq, Component, Serialize, Deserialize)]
+struct Model {
+    shape: Shape,
+}
+
+#[test]
+fn test_create_and_destroy_scene() {
+    let bus = MessageBus::default();
+    let created = bus.add_handler::<SceneWasCreated>(MessageHandlerType::Update);
+    let destroyed = bus.add_handler::<SceneWasDestroyed>(MessageHandlerType::Update);
+    let mut manager = SceneManager::new(&bus);
+
+    let level = manager.create_scene("level");
+    assert_eq!(created.try_recv().unwrap().scene, level);
+    assert_eq!(manager.find_scene("level"), Some(level));
+    assert_eq!(manager.scenes().count(), 2);
+
+    assert!(manager.destroy_scene(level));
+    assert_eq!(destroyed.try_recv().unwrap().scene, level);
+    assert!(manager.scene(level).is_none());
+    assert!(!manager.destroy_scene(level));
+}
+
+#[test]
+fn test_active_scene() {
+    let bus = MessageBus::default();
+    let current = bus.add_handler::<SceneDidBecomeCurrent>(MessageHandlerType::Update);
+    let mut manager = SceneManager::new(&bus);
+    let default = manager.active_scene().handle();
+
+    let level = manager.create_scene("level");
+    assert!(manager.set_active_scene(level));
+    assert_eq!(current.try_recv().unwrap().scene, level);
+    assert_eq!(manager.active_scene().name(), "level");
+
+    // The active scene cannot be destroyed, switching to it again sends nothing.
+    assert!(!manager.destroy_scene(level));
+    assert!(manager.set_active_scene(level));
+    assert!(current.try_recv().is_err());
+
+    assert!(manager.set_active_scene(default));
+    assert!(manager.destroy_scene(level));
+    assert!(!manager.set_active_scene(level));
+}
+
+#[test]
+fn test_scene_targets() {
+    let mut manager = SceneManager::default();
+    let ui = manager.create_scene("ui");
+    let level = manager.create_scene("level");
+    manager.set_active_scene(level);
+
+    let handles = |target: SceneTarget| {
+        manager
+            .target_scenes(&target)
+            .map(|e| e.handle())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(handles(SceneTarget::Active), vec![level]);
+    assert_eq!(handles(SceneTarget::All).len(), 3);
+    assert_eq!(
+        handles(SceneTarget::Named(vec![
+            String::from("level"),
+            String::from("ui")
+        ])),
+        vec![ui, level]
+    );
+}
+
+fn scene_components() -> SceneComponentRegistry {
+    let components = SceneComponentRegistry::default();
+    components.register_component::<Position>("position");
+    components.register_component::<Tag>("tag");
+    components
+}
+
+#[test]
+fn test_scene_serialization_round_trip() {
+    let components = scene_components();
+    let mut manager = SceneManager::default();
+    let level = manager.create_scene("level");
+    let registry = manager.scene_mut(level).unwrap().registry_mut();
+    registry
+        .create_entity((Position { x: 1.0, y: 2.0 }, Tag(String::from("player"))))
+        .unwrap();
+    registry.create_entity(Position { x: 3.0, y: 4.0 }).unwrap();
+
+    let serialized = components.serialize(manager.scene(level).unwrap()).unwrap();
+    assert_eq!(serialized.name, "level");
+    assert_eq!(serialized.entities.len(), 2);
+
+    for format in [
+        AssetSerializationFormat::Binary,
+        AssetSerializationFormat::Toml,
+    ] {
+        let bytes = serialized.to_bytes(format).unwrap();
+        let decoded = SerializedScene::from_bytes(&bytes, format).unwrap();
+        assert_eq!(decoded, serialized);
+
+        let handle = manager
+            .load_serialized_scene(&decoded, &components)
+            .unwrap();
+        let scene = manager.scene(handle).unwrap();
+        assert_eq!(scene.name(), "level");
+        let players = scene
+            .registry()
+            .iter_components_matching::<(Position, Tag)>()
+            .flat_map(|(positions, tags)| positions.iter().zip(tags.iter()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            players,
+            vec![(&Position { x: 1.0, y: 2.0 }, &Tag(String::from("player")))]
+        );
+        assert_eq!(scene.registry().iter_entities().count(), 2);
+    }
+}
+
+#[test]
+fn test_scene_serialization_large_ids() {
+    let components = scene_components();
+    components.register_component::<Model>("model");
+    let mut manager = SceneManager::default();
+    let level = manager.create_scene("level");
+    let model = Model {
+        shape: Shape::Mesh {
+            asset: assets::AssetIdentifier::from(u64::MAX - 1),
+        },
+    };
+    let registry = manager.scene_mut(level).unwrap().registry_mut();
+    registry.create_entity(model.clone()).unwrap();
+    registry
+        .create_entity(Model {
+            shape: Shape::Sphere(2.0),
+        })
+        .unwrap();
+
+    let serialized = components.serialize(manager.scene(level).unwrap()).unwrap();
+    for format in [
+        AssetSerializationFormat::Binary,
+        AssetSerializationFormat::Toml,
+    ] {
+        let bytes = serialized.to_bytes(format).unwrap();
+        let decoded = SerializedScene::from_bytes(&bytes, format).unwrap();
+        let handle = manager
+            .load_serialized_scene(&decoded, &components)
+            .unwrap();
+        let models = manager
+            .scene(handle)
+            .unwrap()
+            .registry()
+            .iter_components_matching::<Model>()
+            .flat_map(|models| models.iter().cloned())
+            .collect::<Vec<_>>();
+        assert!(models.contains(&model));
+        assert_eq!(models.len(), 2);
+    }
+}
+
+#[test]
+fn test_scene_deserialization_unknown_component() {
+    let components = scene_components();
+    let mut manager = SceneManager::default();
+    let serialized = SerializedScene::from_bytes(
+        br#"
+name = "level"
+
+[[entities]]
+[entities.components.position]
+x = 1.0
+y = 2.0
+
+[[entities]]
+[entities.components.velocity]
+x = 1.0
+"#,
+        AssetSerializationFormat::Toml,
+    )
+    .unwrap();
+    assert!(matches!(
+        manager.load_serialized_scene(&serialized, &components),
+        Err(SceneSerializationError::UnknownComponent(name)) if name == "velocity"
+    ));
+    assert!(manager.find_scene("level").is_none());
+}
+
+fn positions(count: usize) -> SerializedScene {
+    let components = scene_components();
+    let mut manager = SceneManager::default();
+    let registry = manager.active_scene_mut().registry_mut();
+    for i in 0..count {
+        registry
+            .create_entity(Position {
+                x: i as f32,
+                y: 0.0,
+            })
+            .unwrap();
+    }
+    components.serialize(manager.active_scene()).unwrap()
+}
+
+#[test]
+fn test_scene_chunk_streaming() {
+    let dispatcher = Dispatcher::new(
+        Some(NonZeroUsize::new(1).unwrap()),
+        NonZeroUsize::new(1).unwrap(),
+        Some(NonZeroUsize::new(1).unwrap()),
+        NonZeroUsize::new(1).unwrap(),
+    )
+    .unwrap();
+    let components = scene_components();
+    let bus = MessageBus::default();
+    let loaded = bus.add_handler::<SceneChunkWasLoaded>(MessageHandlerType::Update);
+    let mut manager = SceneManager::new(&bus);
+    manager.set_streaming_entity_budget(4);
+    let level = manager.active_scene().handle();
+    let existing = manager
+        .active_scene_mut()
+        .registry_mut()
+        .create_entity(Tag(String::from("existing")))
+        .unwrap();
+
+    let first = manager
+        .stream_serialized_chunk(positions(6), level)
+        .unwrap();
+    let second = manager
+        .stream_serialized_chunk(positions(3), level)
+        .unwrap();
+
+    // The budget is shared by all chunks, in the order they were streamed.
+    manager.update_streaming(&components, &dispatcher);
+    assert_eq!(manager.chunk(first).unwrap().entities().len(), 4);
+    assert_eq!(
+        manager.chunk(second).unwrap().state(),
+        SceneChunkState::Merging
+    );
+    assert!(loaded.try_recv().is_err());
+
+    manager.update_streaming(&components, &dispatcher);
+    assert_eq!(
+        manager.chunk(first).unwrap().state(),
+        SceneChunkState::Loaded
+    );
+    assert_eq!(manager.chunk(second).unwrap().entities().len(), 2);
+    assert_eq!(loaded.try_recv().unwrap().chunk, first);
+
+    manager.update_streaming(&components, &dispatcher);
+    assert_eq!(
+        manager.chunk(second).unwrap().state(),
+        SceneChunkState::Loaded
+    );
+    assert_eq!(loaded.try_recv().unwrap().chunk, second);
+    assert_eq!(
+        manager.active_scene().registry().iter_entities().count(),
+        10
+    );
+
+    // Unloading removes exactly the entities the chunk added.
+    let added = manager.chunk(first).unwrap().entities().to_vec();
+    assert!(manager.unload_chunk(first));
+    assert!(!manager.unload_chunk(first));
+    let registry = manager.active_scene().registry();
+    assert_eq!(registry.iter_entities().count(), 4);
+    assert!(registry.has_component::<Tag>(existing));
+    assert!(added
+        .iter()
+        .all(|e| !registry.has_component::<Position>(*e)));
+}
+
+fn tagged(registry: &Registry, tag: &str) -> Entity {
+    registry
+        .iter_entities()
+        .find(|e| {
+            registry
+                .get_component::<Tag>(*e)
+                .is_some_and(|e| e.0 == tag)
+        })
+        .unwrap()
+}
+
+#[test]
+fn test_scene_serialization_hierarchy() {
+    let components = scene_components();
+    components.register_component::<Parent>("parent");
+    components.register_component::<Children>("children");
+    let mut manager = SceneManager::default();
+    let registry = manager.active_scene_mut().registry_mut();
+    let [root, first, second, grandchild] = ["root", "first", "second", "grandchild"]
+        .map(|e| registry.create_entity(Tag(String::from(e))).unwrap());
+    TransformHierarchy::set_parent(registry, second, root);
+    TransformHierarchy::set_parent(registry, first, root);
+    TransformHierarchy::set_parent(registry, grandchild, first);
+    let serialized = components.serialize(manager.active_scene()).unwrap();
+
+    let assert_hierarchy = |registry: &Registry| {
+        let [root, first, second, grandchild] =
+            ["root", "first", "second", "grandchild"].map(|e| tagged(registry, e));
+        assert_eq!(parent(registry, root), None);
+        assert_eq!(children(registry, root), vec![second, first]);
+        assert_eq!(parent(registry, first), Some(root));
+        assert_eq!(children(registry, first), vec![grandchild]);
+        assert_eq!(parent(registry, grandchild), Some(first));
+        assert!(!registry.has_component::<Children>(second));
+    };
+    for format in [
+        AssetSerializationFormat::Binary,
+        AssetSerializationFormat::Toml,
+    ] {
+        let bytes = serialized.to_bytes(format).unwrap();
+        let decoded = SerializedScene::from_bytes(&bytes, format).unwrap();
+        let handle = manager
+            .load_serialized_scene(&decoded, &components)
+            .unwrap();
+        assert_hierarchy(manager.scene(handle).unwrap().registry());
+    }
+
+    // Streamed entities get other handles, and stay detached until all entities of the chunk are merged.
+    let dispatcher = Dispatcher::new(
+        Some(NonZeroUsize::new(1).unwrap()),
+        NonZeroUsize::new(1).unwrap(),
+        Some(NonZeroUsize::new(1).unwrap()),
+        NonZeroUsize::new(1).unwrap(),
+    )
+    .unwrap();
+    let level = manager.create_scene("level");
+    let registry = manager.scene_mut(level).unwrap().registry_mut();
+    registry
+        .create_entity(Tag(String::from("existing")))
+        .unwrap();
+    manager.set_streaming_entity_budget(2);
+    let chunk = manager.stream_serialized_chunk(serialized, level).unwrap();
+    manager.update_streaming(&components, &dispatcher);
+    let registry = manager.scene(level).unwrap().registry();
+    assert!(manager.chunk(chunk).unwrap().entities().iter().all(|e| {
+        registry
+            .get_component::<Parent>(*e)
+            .is_none_or(|e| !e.entity().is_valid())
+            && children(registry, *e).is_empty()
+    }));
+    manager.update_streaming(&components, &dispatcher);
+    assert_hierarchy(manager.scene(level).unwrap().registry());
+}
+
+#[test]
+fn test_prefab_resolve_and_instantiate() {
+    let components = scene_components();
+    let base: Prefab = toml::from_str(
+        r#"
+[components.position]
+x = 1.0
+y = 2.0
+
+[[children]]
+components = { tag = "weapon" }
+"#,
+    )
+    .unwrap();
+    let player: Prefab = toml::from_str(
+        r#"
+base = "assets.prefabs.character"
+
+[components]
+tag = "player"
+
+[components.position]
+y = 5.0
+"#,
+    )
+    .unwrap();
+
+    // Prefabs reference other assets by name.
+    assert_eq!(
+        Value::from("assets.prefabs.character").try_into::<assets::AssetIdentifier>(),
+        Ok(assets::asset_id!(assets.prefabs.character))
+    );
+
+    let prefab = player
+        .resolve(&mut |asset_id| {
+            assert_eq!(asset_id, assets::asset_id!(assets.prefabs.character));
+            Ok(base.clone())
+        })
+        .unwrap();
+    let mut scene = SceneManager::default();
+    let scene = scene.active_scene_mut();
+    let instance = scene.instantiate_prefab(&prefab, &components).unwrap();
+
+    assert_eq!(instance.entities().len(), 2);
+    let root = instance.root();
+    let registry = scene.registry();
+    assert_eq!(
+        registry.get_component::<Position>(root),
+        Some(&Position { x: 1.0, y: 5.0 })
+    );
+    assert_eq!(
+        registry.get_component::<Tag>(root),
+        Some(&Tag(String::from("player")))
+    );
+    let weapon = instance.entities()[1];
+    assert_eq!(instance.parent(weapon), Some(root));
+    assert_eq!(
+        registry.get_component::<Tag>(weapon),
+        Some(&Tag(String::from("weapon")))
+    );
+}
+
+#[test]
+fn test_prefab_cyclic_base() {
+    let prefab = Prefab {
+        base: Some(String::from("assets.prefabs.cycle")),
+        ..Default::default()
+    };
+    assert!(matches!(
+        prefab.clone().resolve(&mut |_| Ok(prefab.clone())),
+        Err(SceneSerializationError::InvalidPrefab(_))
+    ));
+}
+
+#[test]
+fn test_prefab_instance_parents() {
+    let components = scene_components();
+    let prefab: Prefab = toml::from_str(
+        r#"
+components = { tag = "root" }
+
+[[children]]
+components = { tag = "arm" }
+
+[[children.children]]
+components = { tag = "hand" }
+
+[[children]]
+components = { tag = "leg" }
+"#,
+    )
+    .unwrap();
+    let mut scene = SceneManager::default();
+    let scene = scene.active_scene_mut();
+    let instance = scene.instantiate_prefab(&prefab, &components).unwrap();
+
+    // Entities are created depth-first, their parents are recorded in the instance.
+    let registry = scene.registry();
+    let tags = instance
+        .entities()
+        .iter()
+        .map(|e| registry.get_component::<Tag>(*e).unwrap().0.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(tags, vec!["root", "arm", "hand", "leg"]);
+    let [root, arm, hand, leg] = instance.entities() else {
+        panic!("Expected four entities.");
+    };
+    assert_eq!(instance.parent(*root), None);
+    assert_eq!(instance.parent(*arm), Some(*root));
+    assert_eq!(instance.parent(*hand), Some(*arm));
+    assert_eq!(instance.parent(*leg), Some(*root));
+}
+
+#[test]
+fn test_prefab_library_variant_generation() {
+    let library = PrefabLibrary::new(Arc::new(scene_components()));
+    let loads = std::cell::Cell::new(0);
+    let mut load = |_| {
+        loads.set(loads.get() + 1);
+        Ok(Prefab::default())
+    };
+    let asset_id = assets::AssetIdentifier::named("prefabs.player");
+
+    // Prefabs are loaded again once the active variants change.
+    library.load_with(0, asset_id, &mut load).unwrap();
+    library.load_with(0, asset_id, &mut load).unwrap();
+    assert_eq!(loads.get(), 1);
+    library.load_with(1, asset_id, &mut load).unwrap();
+    library.load_with(1, asset_id, &mut load).unwrap();
+    assert_eq!(loads.get(), 2);
+}
+
+#[test]
+fn test_prefab_child_depth() {
+    let nested = |depth: usize| {
+        (0..depth).fold(Prefab::default(), |child, _| Prefab {
+            children: vec![child],
+            ..Default::default()
+        })
+    };
+    assert!(nested(Prefab::MAX_CHILD_DEPTH)
+        .resolve(&mut |_| Ok(Prefab::default()))
+        .is_ok());
+    assert!(matches!(
+        nested(Prefab::MAX_CHILD_DEPTH + 1).resolve(&mut |_| Ok(Prefab::default())),
+        Err(SceneSerializationError::PrefabChildrenTooDeep(_))
+    ));
+
+    // Prefabs containing themselves as a child are caught by the child depth.
+    let prefab = Prefab {
+        children: vec![Prefab {
+            base: Some(String::from("assets.prefabs.recursive")),
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+    assert!(matches!(
+        prefab.clone().resolve(&mut |_| Ok(prefab.clone())),
+        Err(SceneSerializationError::PrefabChildrenTooDeep(_))
+    ));
+}
+
+/// Rotation of `degrees` around the z axis, as a quaternion.
+fn rotation_z(degrees: f32) -> Vec4f {
+    let half = degrees.to_radians() / 2.0;
+    Vec4f::from_components(0.0, 0.0, half.sin(), half.cos())
+}
+
+fn assert_close<const N: usize>(actual: &[f32; N], expected: &[f32; N]) {
+    assert!(
+        actual
+            .iter()
+            .zip(expected)
+            .all(|(a, e)| (a - e).abs() < 1e-5),
+        "{:?} != {:?}",
+        actual,
+        expected
+    );
+}
+
+fn assert_transform(transform: &Transform, position: [f32; 3], rotation: [f32; 4], scale: f32) {
+    assert_close(transform.position().as_array(), &position);
+    assert_close(transform.rotation().as_array(), &rotation);
+    assert_close(&[transform.scale()], &[scale]);
+}
+
+fn translation(x: f32, y: f32, z: f32) -> Transform {
+    Transform::new(Vec3f::from_components(x, y, z), rotation_z(0.0), 1.0)
+}
+
+fn children(registry: &Registry, entity: Entity) -> Vec<Entity> {
+    registry
+        .get_component::<Children>(entity)
+        .map(|e| e.entities().to_vec())
+        .unwrap_or_default()
+}
+
+fn parent(registry: &Registry, entity: Entity) -> Option<Entity> {
+    registry.get_component::<Parent>(entity).map(|e| e.entity())
+}
+
+#[test]
+fn test_transform_mul_transform() {
+    let parent = Transform::new(Vec3f::from_components(1.0, 0.0, 0.0), rotation_z(90.0), 2.0);
+    let child = Transform::new(Vec3f::from_components(1.0, 0.0, 0.0), rotation_z(90.0), 0.5);
+
+    // The child position is scaled and rotated by the parent before being offset.
+    let global = parent.mul_transform(&child);
+    assert_transform(&global, [1.0, 2.0, 0.0], *rotation_z(180.0).as_array(), 1.0);
+
+    // Zero rotations are treated as no rotation.
+    let unrotated = Transform::new(Vec3f::from_components(0.0, 1.0, 0.0), Vec4f::zero(), 1.0);
+    let global = unrotated.mul_transform(&child);
+    assert_transform(&global, [1.0, 1.0, 0.0], *rotation_z(90.0).as_array(), 0.5);
+    let global = child.mul_transform(&unrotated);
+    assert_transform(&global, [0.5, 0.0, 0.0], *rotation_z(90.0).as_array(), 0.5);
+}
+
+#[test]
+fn test_rotation_composition() {
+    // Rotations compose like their quaternions, the parent rotation is applied last.
+    let x = Transform::new(
+        Vec3f::zero(),
+        Vec4f::from_components(45f32.to_radians().sin(), 0.0, 0.0, 45f32.to_radians().cos()),
+        1.0,
+    );
+    let z = Transform::new(Vec3f::zero(), rotation_z(90.0), 1.0);
+    let point = translation(1.0, 0.0, 0.0);
+
+    // Rotating (1, 0, 0) around z first yields (0, 1, 0), then rotating around x yields (0, 0, 1).
+    let global = x.mul_transform(&z).mul_transform(&point);
+    assert_close(global.position().as_array(), &[0.0, 0.0, 1.0]);
+    // In the other order, the rotation around x does not move the point.
+    let global = z.mul_transform(&x).mul_transform(&point);
+    assert_close(global.position().as_array(), &[0.0, 1.0, 0.0]);
+
+    // The composed rotation is normalized.
+    let length = x
+        .mul_transform(&z)
+        .rotation()
+        .as_array()
+        .iter()
+        .map(|e| e * e)
+        .sum::<f32>();
+    assert_close(&[length], &[1.0]);
+}
+
+#[test]
+fn test_transform_interpolate_shortest_arc() {
+    use crate::render_world::Interpolate;
+
+    let from = Transform::new(Vec3f::zero(), rotation_z(0.0), 1.0);
+    let to = Transform::new(Vec3f::zero(), rotation_z(90.0), 1.0);
+    let negated = Transform::new(Vec3f::zero(), -rotation_z(90.0), 1.0);
+
+    // `q` and `-q` are the same rotation, both interpolate along the shortest arc.
+    let halfway = from.interpolate(&to, 0.5);
+    assert_close(halfway.rotation().as_array(), rotation_z(45.0).as_array());
+    let halfway = from.interpolate(&negated, 0.5);
+    assert_close(halfway.rotation().as_array(), rotation_z(45.0).as_array());
+
+    // Zero rotations interpolate to no rotation.
+    let zero = Transform::new(Vec3f::zero(), Vec4f::zero(), 1.0);
+    let halfway = zero.interpolate(&zero, 0.5);
+    assert_close(halfway.rotation().as_array(), rotation_z(0.0).as_array());
+}
+
+#[test]
+fn test_hierarchy_set_parent() {
+    let mut manager = SceneManager::default();
+    let registry = manager.active_scene_mut().registry_mut();
+    let root = registry.create_entity(translation(0.0, 0.0, 0.0)).unwrap();
+    let first = registry.create_entity(translation(1.0, 0.0, 0.0)).unwrap();
+    let second = registry.create_entity(translation(2.0, 0.0, 0.0)).unwrap();
+
+    assert!(TransformHierarchy::set_parent(registry, first, root));
+    assert!(TransformHierarchy::set_parent(registry, second, root));
+    assert_eq!(children(registry, root), vec![first, second]);
+    assert_eq!(parent(registry, first), Some(root));
+
+    // Setting the same parent again keeps the order of the children.
+    assert!(TransformHierarchy::set_parent(registry, first, root));
+    assert_eq!(children(registry, root), vec![first, second]);
+
+    // Reparenting removes the entity from its former parent.
+    assert!(TransformHierarchy::set_parent(registry, second, first));
+    assert_eq!(children(registry, root), vec![first]);
+    assert_eq!(children(registry, first), vec![second]);
+    assert_eq!(parent(registry, second), Some(first));
+
+    // Cycles are rejected without changing the hierarchy.
+    assert!(!TransformHierarchy::set_parent(registry, root, root));
+    assert!(!TransformHierarchy::set_parent(registry, root, second));
+    assert!(!TransformHierarchy::set_parent(registry, first, second));
+    assert_eq!(parent(registry, root), None);
+    assert_eq!(parent(registry, first), Some(root));
+    assert_eq!(children(registry, first), vec![second]);
+    assert!(!registry.has_component::<Children>(second));
+
+    // Destroyed entities can not be attached.
+    let destroyed = registry.create_entity(translation(0.0, 0.0, 0.0)).unwrap();
+    registry.destroy_entity(destroyed);
+    assert!(!TransformHierarchy::set_parent(registry, destroyed, root));
+    assert!(!TransformHierarchy::set_parent(registry, second, destroyed));
+    assert_eq!(children(registry, root), vec![first]);
+    assert_eq!(parent(registry, second), Some(first));
+}
+
+#[test]
+fn test_hierarchy_detach() {
+    let mut manager = SceneManager::default();
+    let registry = manager.active_scene_mut().registry_mut();
+    let root = registry.create_entity(translation(0.0, 0.0, 0.0)).unwrap();
+    let child = registry.create_entity(translation(1.0, 0.0, 0.0)).unwrap();
+    TransformHierarchy::set_parent(registry, child, root);
+
+    assert!(TransformHierarchy::detach(registry, child));
+    assert_eq!(parent(registry, child), None);
+    // Empty children components are removed.
+    assert!(!registry.has_component::<Children>(root));
+    assert_eq!(
+        registry.get_component::<Transform>(child),
+        Some(&translation(1.0, 0.0, 0.0))
+    );
+    assert!(!TransformHierarchy::detach(registry, child));
+}
+
+#[test]
+fn test_hierarchy_despawn_recursive() {
+    let mut manager = SceneManager::default();
+    let registry = manager.active_scene_mut().registry_mut();
+    let root = registry.create_entity(translation(0.0, 0.0, 0.0)).unwrap();
+    let child = registry.create_entity(translation(1.0, 0.0, 0.0)).unwrap();
+    let grandchild = registry.create_entity(translation(2.0, 0.0, 0.0)).unwrap();
+    let sibling = registry.create_entity(translation(3.0, 0.0, 0.0)).unwrap();
+    TransformHierarchy::set_parent(registry, child, root);
+    TransformHierarchy::set_parent(registry, grandchild, child);
+    TransformHierarchy::set_parent(registry, sibling, root);
+
+    assert!(TransformHierarchy::despawn_recursive(registry, child));
+    assert_eq!(
+        registry.iter_entities().collect::<Vec<_>>(),
+        vec![root, sibling]
+    );
+    assert_eq!(children(registry, root), vec![sibling]);
+    assert!(!TransformHierarchy::despawn_recursive(registry, child));
+}
+
+#[test]
+fn test_hierarchy_propagate() {
+    let mut manager = SceneManager::default();
+    let registry = manager.active_scene_mut().registry_mut();
+    let root = registry
+        .create_entity(Transform::new(
+            Vec3f::from_components(1.0, 0.0, 0.0),
+            rotation_z(90.0),
+            2.0,
+        ))
+        .unwrap();
+    let child = registry.create_entity(translation(1.0, 0.0, 0.0)).unwrap();
+    // Entities without a transform pass the transform of their parent on.
+    let group = registry.create_entity(Tag(String::from("group"))).unwrap();
+    let grandchild = registry.create_entity(translation(0.0, 1.0, 0.0)).unwrap();
+    TransformHierarchy::set_parent(registry, child, root);
+    TransformHierarchy::set_parent(registry, group, root);
+    TransformHierarchy::set_parent(registry, grandchild, group);
+
+    TransformHierarchy::propagate(registry);
+    let global = |entity| {
+        *registry
+            .get_component::<GlobalTransform>(entity)
+            .unwrap()
+            .transform()
+    };
+    assert_transform(
+        &global(root),
+        [1.0, 0.0, 0.0],
+        *rotation_z(90.0).as_array(),
+        2.0,
+    );
+    assert_transform(
+        &global(child),
+        [1.0, 2.0, 0.0],
+        *rotation_z(90.0).as_array(),
+        2.0,
+    );
+    assert_transform(
+        &global(grandchild),
+        [-1.0, 0.0, 0.0],
+        *rotation_z(90.0).as_array(),
+        2.0,
+    );
+    assert!(!registry.has_component::<GlobalTransform>(group));
+
+    // Moving the root moves its descendants on the next propagation.
+    *registry.get_component_mut::<Transform>(root).unwrap() = translation(0.0, 0.0, 5.0);
+    TransformHierarchy::propagate(registry);
+    let global = |entity| {
+        *registry
+            .get_component::<GlobalTransform>(entity)
+            .unwrap()
+            .transform()
+    };
+    assert_transform(
+        &global(child),
+        [1.0, 0.0, 5.0],
+        *rotation_z(0.0).as_array(),
+        1.0,
+    );
+    assert_transform(
+        &global(grandchild),
+        [0.0, 1.0, 5.0],
+        *rotation_z(0.0).as_array(),
+        1.0,
+    );
+}
+
+#[test]
+fn test_prefab_instance_hierarchy() {
+    let components = SceneComponentRegistry::default();
+    components.register_component::<Transform>("transform");
+    let child = Prefab {
+        components: [(
+            String::from("transform"),
+            serde_cbor::value::to_value(translation(1.0, 0.0, 0.0)).unwrap(),
+        )]
+        .into(),
+        ..Default::default()
+    };
+    let prefab = Prefab {
+        children: vec![
+            Prefab {
+                children: vec![child.clone()],
+                ..child.clone()
+            },
+            child.clone(),
+        ],
+        ..child
+    };
+
+    let mut manager = SceneManager::default();
+    let scene = manager.active_scene_mut();
+    let instance = scene.instantiate_prefab(&prefab, &components).unwrap();
+    let registry = scene.registry_mut();
+
+    // The entities are attached to their parents within the prefab.
+    let [root, arm, hand, leg] = instance.entities() else {
+        panic!("Expected four entities.");
+    };
+    assert_eq!(parent(registry, *root), None);
+    assert_eq!(children(registry, *root), vec![*arm, *leg]);
+    assert_eq!(children(registry, *arm), vec![*hand]);
+    TransformHierarchy::propagate(registry);
+    assert_eq!(
+        registry.get_component::<GlobalTransform>(*hand),
+        Some(&GlobalTransform::from(translation(3.0, 0.0, 0.0)))
+    );
+}
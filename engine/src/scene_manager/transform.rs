@@ -0,0 +1,117 @@
+use crate::render_world::Interpolate;
+use math::*;
+use serde::{Deserialize, Serialize};
+use shard_ecs::*;
+
+/// Position, uniform scale and rotation of an entity, relative to its [`Parent`] if it has one.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, Component, Serialize, Deserialize)]
+pub struct Transform {
+    position: Vec3f,
+    scale: f32,
+    rotation: Vec4f,
+}
+
+impl Transform {
+    pub fn position(&self) -> Vec3f {
+        self.position
+    }
+    pub fn rotation(&self) -> Vec4f {
+        self.rotation
+    }
+    pub fn scale(&self) -> f32 {
+        self.scale
+    }
+}
+
+impl Transform {
+    pub fn new(position: Vec3f, rotation: Vec4f, scale: f32) -> Self {
+        Transform {
+            position,
+            rotation,
+            scale,
+        }
+    }
+
+    pub fn identity() -> Self {
+        Transform::new(
+            Vec3f::zero(),
+            Vec4f::from_components(0.0, 0.0, 0.0, 1.0),
+            1.0,
+        )
+    }
+
+    /// Returns the transform of `child`, relative to `self`, in the space `self` is relative to.
+    /// The rotation is a quaternion stored as `(x, y, z, w)`, a zero rotation is treated as no rotation.
+    pub fn mul_transform(&self, child: &Transform) -> Transform {
+        let rotation = normalize_rotation(self.rotation);
+        Transform {
+            position: self.position + rotate(rotation, child.position * self.scale),
+            scale: self.scale * child.scale,
+            rotation: multiply_rotations(rotation, normalize_rotation(child.rotation)),
+        }
+    }
+}
+
+fn normalize_rotation(rotation: Vec4f) -> Vec4f {
+    let length = rotation
+        .as_array()
+        .iter()
+        .map(|e| e * e)
+        .sum::<f32>()
+        .sqrt();
+    if length > 0.0 {
+        rotation / length
+    } else {
+        Vec4f::from_components(0.0, 0.0, 0.0, 1.0)
+    }
+}
+
+fn dot(a: Vec4f, b: Vec4f) -> f32 {
+    a.as_array()
+        .iter()
+        .zip(b.as_array())
+        .map(|(a, b)| a * b)
+        .sum()
+}
+
+fn cross(a: Vec3f, b: Vec3f) -> Vec3f {
+    Vec3f::from_components(
+        a.y() * b.z() - a.z() * b.y(),
+        a.z() * b.x() - a.x() * b.z(),
+        a.x() * b.y() - a.y() * b.x(),
+    )
+}
+
+fn rotate(rotation: Vec4f, vector: Vec3f) -> Vec3f {
+    let axis = rotation.xyz();
+    let t = cross(axis, vector) * 2.0;
+    vector + t * rotation.w() + cross(axis, t)
+}
+
+fn multiply_rotations(a: Vec4f, b: Vec4f) -> Vec4f {
+    Vec4f::from_components(
+        a.w() * b.x() + a.x() * b.w() + a.y() * b.z() - a.z() * b.y(),
+        a.w() * b.y() - a.x() * b.z() + a.y() * b.w() + a.z() * b.x(),
+        a.w() * b.z() + a.x() * b.y() - a.y() * b.x() + a.z() * b.w(),
+        a.w() * b.w() - a.x() * b.x() - a.y() * b.y() - a.z() * b.z(),
+    )
+}
+
+impl Interpolate for Transform {
+    /// Linearly interpolates position and scale, the rotation is interpolated along the shortest arc
+    /// and normalized afterwards.
+    fn interpolate(&self, next: &Self, alpha: f32) -> Self {
+        // `q` and `-q` are the same rotation, flip `next` so both are in the same hemisphere.
+        let next_rotation = if dot(self.rotation, next.rotation) < 0.0 {
+            -next.rotation
+        } else {
+            next.rotation
+        };
+        Transform {
+            position: self.position + (next.position - self.position) * alpha,
+            scale: self.scale.interpolate(&next.scale, alpha),
+            rotation: normalize_rotation(self.rotation + (next_rotation - self.rotation) * alpha),
+        }
+    }
+}
@@ -0,0 +1,33 @@
+/// Merges `value` into `target`. Tables are merged recursively, any other value replaces the current one.
+pub(crate) fn merge_toml_values(target: &mut toml::Value, value: toml::Value) {
+    match (target, value) {
+        (toml::Value::Table(target), toml::Value::Table(table)) => {
+            for (key, value) in table {
+                match target.get_mut(&key) {
+                    Some(existing) => merge_toml_values(existing, value),
+                    None => {
+                        target.insert(key, value);
+                    }
+                }
+            }
+        }
+        (target, value) => *target = value,
+    }
+}
+
+/// Merges `value` into `target` like [`merge_toml_values`], with maps in place of tables.
+pub(crate) fn merge_cbor_values(target: &mut serde_cbor::Value, value: serde_cbor::Value) {
+    match (target, value) {
+        (serde_cbor::Value::Map(target), serde_cbor::Value::Map(map)) => {
+            for (key, value) in map {
+                match target.get_mut(&key) {
+                    Some(existing) => merge_cbor_values(existing, value),
+                    None => {
+                        target.insert(key, value);
+                    }
+                }
+            }
+        }
+        (target, value) => *target = value,
+    }
+}
@@ -1,11 +1,13 @@
 use assets::*;
+use engine::config::EngineConfig;
 use engine::engine_stages::RenderStageContainer;
+use engine::render_world::RenderWorld;
+use engine::scene_manager::SceneComponentRegistry;
 use engine::{engine_stages::*, *};
 use graphics::*;
 use math::*;
 use platform_winit::WinitPlatform;
 use scripting::*;
-use std::num::NonZeroUsize;
 use std::sync::Arc;
 use utils::dispatcher::Dispatcher;
 use utils::*;
@@ -36,16 +38,19 @@ fn create_native_scripting_stage<'r>(
                     input.scene_manager.active_scene().handle(),
                     CameraKind::Perspective,
                     RenderPathType::Forward,
-                    &transform,
                 );
 
                 let registry = input.scene_manager.active_scene_mut().registry_mut();
-                let entity = match registry.create_entity((transform, camera)) {
+                let entity = match registry.create_entity((
+                    transform,
+                    GlobalTransform::from(transform),
+                    camera,
+                )) {
                     Ok(handle) => handle,
                     _ => return EngineUpdateResult::Stop,
                 };
                 let (camera, transform) = registry
-                    .get_components::<(Camera, Transform)>(entity)
+                    .get_components::<(Camera, GlobalTransform)>(entity)
                     .unwrap();
                 manager.bind_camera_to_window(&transform, &camera, window.handle());
             }
@@ -58,7 +63,10 @@ fn create_native_scripting_stage<'r>(
     stage.into()
 }
 
-fn create_graphics_stage<'r>(input: RenderStageConstructorInput<'r>) -> Box<dyn AnyRenderStage> {
+fn create_graphics_stage<'r>(
+    input: RenderStageConstructorInput<'r>,
+    config: &EngineConfig,
+) -> Box<dyn AnyRenderStage> {
     let asset_cache: Arc<AssetCache> = match input.resources.get_resource::<AssetCache>() {
         Some(v) => v,
         None => {
@@ -73,15 +81,37 @@ fn create_graphics_stage<'r>(input: RenderStageConstructorInput<'r>) -> Box<dyn
     let options: GraphicsOptions = asset_cache
         .load_typed_into(asset_id!(assets.config.vulkan), &mut buffer)
         .unwrap();
+    let options = match GraphicsOptions::from_config(config, &options) {
+        Ok(v) => v,
+        Err(e) => {
+            fatal!("Invalid graphics configuration: {}", e);
+        }
+    };
 
     let application_info: ApplicationInfo = asset_cache
         .load_typed_into(asset_id!(assets.config.game), &mut buffer)
         .unwrap();
 
+    let render_world = match input.resources.get_resource::<RenderWorld>() {
+        Some(v) => v,
+        None => {
+            fatal!("This system requires a render world to be present!");
+        }
+    };
+
+    let scene_components = match input.resources.get_resource::<SceneComponentRegistry>() {
+        Some(v) => v,
+        None => {
+            fatal!("This system requires a scene component registry to be present!");
+        }
+    };
+
     let create_info = GraphicsStageCreateInfo {
         platform: input.platform_interface,
         application_info,
         asset_system: asset_cache,
+        render_world,
+        scene_components,
         options,
     };
 
@@ -91,22 +121,40 @@ fn create_graphics_stage<'r>(input: RenderStageConstructorInput<'r>) -> Box<dyn
 
 fn main() {
     setup_default_logger();
-    let create_info = EngineCreateInfo {
-        update_tick_rate: 20,
-        max_skipped_frames: 1,
-        max_frame_rate: None,
-        update_stages: vec![Box::new(create_native_scripting_stage)],
-        render_stages: vec![Box::new(create_graphics_stage)],
-        application_info: Box::new(|registry| {
-            registry.load_typed(asset_id!(assets.config.game)).unwrap()
-        }),
-        concurrency_settings: EngineConcurrencySettings {
-            max_async_threads: None,
-            max_worker_thread: None,
-            fallback_worker_threads: NonZeroUsize::new(8).unwrap(),
-            fallback_async_threads: NonZeroUsize::new(2).unwrap(),
+    let config = match EngineConfig::builder()
+        .optional_file("./game/config.toml")
+        .env("ZIRCON")
+        .args(std::env::args().skip(1))
+        .build()
+    {
+        Ok(v) => Arc::new(v),
+        Err(e) => {
+            t_fatal!("Could not load configuration: {}", e);
+        }
+    };
+    if let Err(e) =
+        config.ensure_known_sections(&[EngineSettings::SECTION, GraphicsOptions::SECTION])
+    {
+        t_fatal!("Could not load configuration: {}", e);
+    }
+
+    let defaults = EngineSettings {
+        frame_stats: EngineFrameStatsSettings {
+            log_every_second: true,
+            ..Default::default()
         },
-        asset_registry: Box::from(|dispatcher: Arc<Dispatcher>| {
+        ..Default::default()
+    };
+    let settings = match config.section(EngineSettings::SECTION, &defaults) {
+        Ok(v) => v,
+        Err(e) => {
+            t_fatal!("Invalid engine configuration: {}", e);
+        }
+    };
+
+    let mut create_info = EngineCreateInfo::from_settings(
+        settings,
+        Box::from(|dispatcher: Arc<Dispatcher>| {
             let registry = AssetRegistry::default();
             dispatcher.spawn_async_blocking(async move {
                 let archives = AssetArchive::load_from_directory("./game/asset_archives/", "zarc")
@@ -126,8 +174,20 @@ fn main() {
                 registry
             })
         }),
-    };
+        Box::new(|registry| registry.load_typed(asset_id!(assets.config.game)).unwrap()),
+    );
+    create_info
+        .update_stages
+        .push(Box::new(create_native_scripting_stage));
+    create_info
+        .render_stages
+        .push(Box::new(move |input| create_graphics_stage(input, &config)));
+    create_info.message_delivery_policies =
+        MessageDeliveryPolicies::default().with::<WindowDidResize>(DeliveryPolicy::Coalesce);
+
     let engine = Engine::from(create_info);
     let platform = WinitPlatform::default();
-    engine.run(platform);
+    if let Err(e) = engine.run(platform) {
+        t_fatal!("{}", e);
+    }
 }
@@ -1,11 +1,43 @@
 use rayon_core::{Scope, ScopeFifo, ThreadPool, ThreadPoolBuilder};
-use std::{future::Future, num::NonZeroUsize};
+use std::{
+    future::Future,
+    num::NonZeroUsize,
+    sync::{Arc, Condvar, Mutex},
+};
 use tokio::{runtime::*, task::JoinHandle};
 
 #[derive(Debug)]
 pub struct Dispatcher {
     thread_pool: ThreadPool,
     runtime: Runtime,
+    pending_jobs: Arc<PendingJobs>,
+}
+
+/// Counts jobs which were spawned on the thread pool or the async runtime but did not finish yet.
+#[derive(Debug, Default)]
+struct PendingJobs {
+    count: Mutex<usize>,
+    condvar: Condvar,
+}
+
+/// Marks a spawned job as finished when dropped, also when the job panics.
+struct PendingJobGuard(Arc<PendingJobs>);
+
+impl PendingJobs {
+    fn begin(self: &Arc<Self>) -> PendingJobGuard {
+        *self.count.lock().unwrap() += 1;
+        PendingJobGuard(Arc::clone(self))
+    }
+}
+
+impl Drop for PendingJobGuard {
+    fn drop(&mut self) {
+        let mut count = self.0.count.lock().unwrap();
+        *count -= 1;
+        if *count == 0 {
+            self.0.condvar.notify_all();
+        }
+    }
 }
 
 impl Dispatcher {
@@ -41,6 +73,7 @@ impl Dispatcher {
         Self {
             thread_pool,
             runtime,
+            pending_jobs: Default::default(),
         }
         .into()
     }
@@ -52,7 +85,11 @@ impl Dispatcher {
         F: Future + Send + 'static,
         F::Output: Send + 'static,
     {
-        self.runtime.spawn(future)
+        let guard = self.pending_jobs.begin();
+        self.runtime.spawn(async move {
+            let _guard = guard;
+            future.await
+        })
     }
 
     // pub fn spawn_async_blocking<F, R>(&self, func: F) -> JoinHandle<R>
@@ -110,7 +147,11 @@ impl Dispatcher {
     where
         OP: FnOnce() + Send + 'static,
     {
-        self.thread_pool.spawn(op)
+        let guard = self.pending_jobs.begin();
+        self.thread_pool.spawn(move || {
+            let _guard = guard;
+            op()
+        })
     }
 
     #[inline(always)]
@@ -118,6 +159,20 @@ impl Dispatcher {
     where
         OP: FnOnce() + Send + 'static,
     {
-        self.thread_pool.spawn_fifo(op)
+        let guard = self.pending_jobs.begin();
+        self.thread_pool.spawn_fifo(move || {
+            let _guard = guard;
+            op()
+        })
+    }
+
+    /// Blocks until all jobs spawned through [`Dispatcher::spawn`], [`Dispatcher::spawn_fifo`] and
+    /// [`Dispatcher::spawn_async`] finished.
+    /// Must not be called from within the thread pool or the async runtime, as that would wait on itself.
+    pub fn wait_for_spawned_jobs(&self) {
+        let mut count = self.pending_jobs.count.lock().unwrap();
+        while *count > 0 {
+            count = self.pending_jobs.condvar.wait(count).unwrap();
+        }
     }
 }